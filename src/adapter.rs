@@ -0,0 +1,151 @@
+//! This module provides a declarative adapter subsystem for registering external
+//! classifiers and aligners from a config file, merged with the built-in set of
+//! tools handled directly in `cleaner`. Adapters describe their binary, argument
+//! template and output layout instead of requiring a bespoke Rust command builder,
+//! which lets users wire up new ecosystem tools (Centrifuge, ganon, MMseqs2
+//! taxonomy, ...) without recompiling Scrubby.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ScrubbyError;
+
+/// Placeholders that can be substituted into an adapter's argument template.
+pub const PLACEHOLDER_INPUT: &str = "{input}";
+pub const PLACEHOLDER_DB: &str = "{db}";
+pub const PLACEHOLDER_DB_NAME: &str = "{db_name}";
+pub const PLACEHOLDER_THREADS: &str = "{threads}";
+pub const PLACEHOLDER_OUTPUT: &str = "{output}";
+pub const PLACEHOLDER_EXTRA_ARGS: &str = "{extra_args}";
+
+/// The read layout an adapter argument template is written for.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AdapterLayout {
+    Single,
+    Paired,
+    Long,
+}
+
+impl AdapterLayout {
+    /// Mirrors the `input.len()` to `MetabuliSeqMode` selection used by the built-in tools.
+    pub fn from_input_len(len: usize) -> Self {
+        match len {
+            2 => Self::Paired,
+            _ => Self::Long,
+        }
+    }
+}
+
+/// Describes how to pull `(read_id, classified, taxid)` triples out of an
+/// adapter's stdout or output file.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OutputParserSpec {
+    /// Column delimiter, usually a tab.
+    pub delimiter: char,
+    /// Column index of the read identifier.
+    pub read_id_col: usize,
+    /// Column index of the classified/unclassified flag.
+    pub classified_col: usize,
+    /// Value in `classified_col` that indicates a classified read.
+    pub classified_value: String,
+    /// Column index of the taxonomic identifier.
+    pub taxid_col: usize,
+}
+
+/// A single registered external tool, loaded from the adapter config file.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AdapterSpec {
+    /// Name under which this adapter is selected, e.g. `"centrifuge"`.
+    pub name: String,
+    /// Executable to invoke.
+    pub binary: String,
+    /// Optional command used to probe the tool is installed, e.g. `"centrifuge --version"`.
+    pub version_probe: Option<String>,
+    /// Argument templates keyed by read layout, each a list of tokens that may
+    /// contain the placeholder constants in this module.
+    pub layouts: HashMap<AdapterLayout, Vec<String>>,
+    /// How to parse this adapter's classification output.
+    pub output_parser: OutputParserSpec,
+}
+
+impl AdapterSpec {
+    /// Formats the argv for this adapter given the run configuration.
+    pub fn build_command(
+        &self,
+        input: &[PathBuf],
+        db_path: &Path,
+        db_name: &str,
+        threads: u32,
+        output: &Path,
+        extra_args: &str,
+    ) -> Result<Vec<String>, ScrubbyError> {
+        let layout = AdapterLayout::from_input_len(input.len());
+        let template = self.layouts.get(&layout).ok_or_else(|| {
+            ScrubbyError::AdapterLayoutNotSupported(self.name.clone(), format!("{layout:?}"))
+        })?;
+
+        let input_joined = input
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut args = Vec::with_capacity(template.len() + 1);
+        args.push(self.binary.clone());
+
+        for token in template {
+            let token = token
+                .replace(PLACEHOLDER_INPUT, &input_joined)
+                .replace(PLACEHOLDER_DB, &db_path.display().to_string())
+                .replace(PLACEHOLDER_DB_NAME, db_name)
+                .replace(PLACEHOLDER_THREADS, &threads.to_string())
+                .replace(PLACEHOLDER_OUTPUT, &output.display().to_string())
+                .replace(PLACEHOLDER_EXTRA_ARGS, extra_args);
+            args.push(token);
+        }
+
+        Ok(args)
+    }
+}
+
+/// A collection of adapters loaded from a config file, keyed by name.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AdapterRegistry {
+    pub adapters: HashMap<String, AdapterSpec>,
+}
+
+impl AdapterRegistry {
+    /// Loads a registry of custom adapters from a JSON config file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use scrubby::adapter::AdapterRegistry;
+    /// use std::path::PathBuf;
+    ///
+    /// let registry = AdapterRegistry::from_path(&PathBuf::from("adapters.json")).unwrap();
+    /// ```
+    pub fn from_path(path: &Path) -> Result<Self, ScrubbyError> {
+        let reader = BufReader::new(File::open(path)?);
+        let registry: Self = serde_json::from_reader(reader)?;
+        Ok(registry)
+    }
+
+    /// Merges another registry into this one, with `other` taking precedence
+    /// on name collisions so user config can override built-in adapters.
+    pub fn merge(mut self, other: Self) -> Self {
+        for (name, spec) in other.adapters {
+            self.adapters.insert(name, spec);
+        }
+        self
+    }
+
+    /// Looks up a registered adapter by name.
+    pub fn get(&self, name: &str) -> Option<&AdapterSpec> {
+        self.adapters.get(name)
+    }
+}
@@ -0,0 +1,84 @@
+//! Merges per-read taxonomic assignments from several classifier output files
+//! into a single consensus tax_id per read, using the NCBI taxonomy graph to
+//! compute a (possibly partial) lowest common ancestor across classifiers.
+//! This reduces false-positive host removal caused by a single classifier's
+//! misassignment of a read.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use crate::classifier::KrakenReadRecord;
+use crate::error::ScrubbyError;
+use crate::taxonomy::Taxonomy;
+
+/// Merges per-read tax_id assignments from `inputs` (Kraken-style read
+/// classification files: `classified\tread_id\ttax_id\t...`) into a single
+/// consensus tax_id per read.
+///
+/// For each read, every input file's assigned tax_id is resolved to its
+/// root-to-node lineage in `taxonomy`, and the result is walked from the
+/// root: at each level, the deepest node is kept as the consensus as long as
+/// at least `min_support` of the read's classifiers agree on it, falling back
+/// to the lowest common ancestor of the agreeing subset once support drops
+/// below the threshold. A read classified in only one input file passes
+/// through with that file's own assignment.
+pub fn aggregate_reads(
+    inputs: &[PathBuf],
+    taxonomy: &Taxonomy,
+    min_support: f64,
+) -> Result<HashMap<String, String>, ScrubbyError> {
+    let mut assignments: HashMap<String, Vec<String>> = HashMap::new();
+
+    for input in inputs {
+        let file = BufReader::new(File::open(input)?);
+        for line in file.lines() {
+            let record = KrakenReadRecord::from_str(line?)?;
+            if !record.classified || record.tax_id == "0" {
+                continue;
+            }
+            assignments.entry(record.read_id).or_default().push(record.tax_id);
+        }
+    }
+
+    let mut consensus = HashMap::new();
+    for (read_id, tax_ids) in assignments {
+        let lineages: Vec<Vec<String>> = tax_ids.iter().map(|tax_id| taxonomy.lineage(tax_id)).collect();
+        consensus.insert(read_id, lowest_common_ancestor(&lineages, min_support));
+    }
+
+    Ok(consensus)
+}
+
+/// Walks `lineages` (each root-first) from the root, keeping the deepest node
+/// shared by at least `min_support` of them as the running consensus.
+fn lowest_common_ancestor(lineages: &[Vec<String>], min_support: f64) -> String {
+    let total = lineages.len() as f64;
+    let mut consensus = lineages[0][0].clone();
+    let mut active: Vec<&Vec<String>> = lineages.iter().collect();
+
+    let mut depth = 1;
+    loop {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for lineage in &active {
+            if let Some(node) = lineage.get(depth) {
+                *counts.entry(node.as_str()).or_insert(0) += 1;
+            }
+        }
+
+        let Some((node, count)) = counts.into_iter().max_by_key(|&(_, count)| count) else {
+            break;
+        };
+
+        if (count as f64) / total < min_support {
+            break;
+        }
+
+        consensus = node.to_string();
+        active.retain(|lineage| lineage.get(depth).map(String::as_str) == Some(node));
+        depth += 1;
+    }
+
+    consensus
+}
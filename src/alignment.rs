@@ -1,16 +1,41 @@
 #[cfg(feature = "htslib")]
-use rust_htslib::{bam, bam::record::Cigar, bam::Read};
+use rust_htslib::{bam, bam::record::{Aux, Cigar}, bam::Read};
 #[cfg(feature = "htslib")]
 use std::str::from_utf8;
 
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::fmt;
 
-use crate::error::ScrubbyError;
+use crate::error::{ScrubbyError, ParseContext};
 use crate::utils::is_file_empty;
 
+/// Policy for combining the minimum query alignment length and minimum query
+/// coverage thresholds when deciding whether a read counts as "mapped".
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, clap::ValueEnum, schemars::JsonSchema)]
+pub enum PafFilterMode {
+    /// A read is mapped if its length OR its coverage meets the threshold (default, permissive).
+    #[serde(rename = "any")]
+    Any,
+    /// A read is mapped only if both its length AND its coverage meet their thresholds.
+    #[serde(rename = "all")]
+    All,
+}
+impl Default for PafFilterMode {
+    fn default() -> Self {
+        PafFilterMode::Any
+    }
+}
+impl fmt::Display for PafFilterMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PafFilterMode::Any => write!(f, "any"),
+            PafFilterMode::All => write!(f, "all"),
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, clap::ValueEnum)]
 pub enum AlignmentFormat {
@@ -36,22 +61,30 @@ impl ReadAlignment {
         min_qaln_cov: f64,
         min_mapq: u8,
         alignment_format: Option<AlignmentFormat>,
+        filter_mode: &PafFilterMode,
+        skip_secondary: bool,
+        require_proper_pair: bool,
+        min_identity: f64,
+        reference: Option<PathBuf>,
     ) -> Result<Self, ScrubbyError> {
         match alignment_format {
             Some(format) => match format {
-                AlignmentFormat::Paf | AlignmentFormat::Gaf => ReadAlignment::from_paf(path, min_qaln_len, min_qaln_cov, min_mapq),
+                AlignmentFormat::Paf | AlignmentFormat::Gaf => ReadAlignment::from_paf(path, min_qaln_len, min_qaln_cov, min_mapq, filter_mode, min_identity),
                 AlignmentFormat::Txt => ReadAlignment::from_txt(path),
                 #[cfg(feature = "htslib")]
-                AlignmentFormat::Sam | AlignmentFormat::Bam | AlignmentFormat::Cram  => ReadAlignment::from_bam(path, min_qaln_len, min_qaln_cov, min_mapq),
+                AlignmentFormat::Sam | AlignmentFormat::Bam | AlignmentFormat::Cram  => ReadAlignment::from_bam(path, min_qaln_len, min_qaln_cov, min_mapq, filter_mode, skip_secondary, require_proper_pair, min_identity, reference, matches!(format, AlignmentFormat::Cram)),
                 #[cfg(not(feature = "htslib"))]
                 _ =>  Err(ScrubbyError::AlignmentInputFormatInvalid),
             },
             None => match path.extension().map(|s| s.to_str()) {
-                Some(Some("paf")) | Some(Some("paf.gz")) | Some(Some("paf.xz")) | Some(Some("paf.bz")) | Some(Some("paf.bz2")) => ReadAlignment::from_paf(path, min_qaln_len, min_qaln_cov, min_mapq),
-                Some(Some("gaf")) | Some(Some("gaf.gz")) | Some(Some("gaf.xz")) | Some(Some("gaf.bz")) | Some(Some("gaf.bz2")) => ReadAlignment::from_paf(path, min_qaln_len, min_qaln_cov, min_mapq),
+                Some(Some("paf")) | Some(Some("paf.gz")) | Some(Some("paf.xz")) | Some(Some("paf.bz")) | Some(Some("paf.bz2")) => ReadAlignment::from_paf(path, min_qaln_len, min_qaln_cov, min_mapq, filter_mode, min_identity),
+                Some(Some("gaf")) | Some(Some("gaf.gz")) | Some(Some("gaf.xz")) | Some(Some("gaf.bz")) | Some(Some("gaf.bz2")) => ReadAlignment::from_paf(path, min_qaln_len, min_qaln_cov, min_mapq, filter_mode, min_identity),
                 Some(Some("txt")) |  Some(Some("txt.gz")) | Some(Some("txt.xz")) | Some(Some("txt.bz")) | Some(Some("txt.bz2")) => ReadAlignment::from_txt(path),
                 #[cfg(feature = "htslib")]
-                Some(Some("bam") | Some("sam") | Some("cram")) => ReadAlignment::from_bam(path, min_qaln_len, min_qaln_cov, min_mapq),
+                Some(Some("bam") | Some("sam") | Some("cram")) => {
+                    let is_cram = path.extension().and_then(|s| s.to_str()) == Some("cram");
+                    ReadAlignment::from_bam(path, min_qaln_len, min_qaln_cov, min_mapq, filter_mode, skip_secondary, require_proper_pair, min_identity, reference, is_cram)
+                },
                 _ => Err(ScrubbyError::AlignmentInputFormatNotRecognized),
             },
         }
@@ -62,18 +95,13 @@ impl ReadAlignment {
         let mut target_reads: HashSet<String> = HashSet::new();
 
         if !is_file_empty(path)? {
-            let reader: Box<dyn BufRead> = if path.to_str() == Some("-") {
-                Box::new(BufReader::new(std::io::stdin()))
-            } else {
-                let (reader, _) = niffler::from_path(path)?;
-                Box::new(BufReader::new(reader))
-            };
-    
+            let reader = open_alignment_reader(path)?;
+
             for line in reader.lines() {
                 let line = line?;
                 target_reads.insert(line);
             }
-        } 
+        }
     
 
         Ok(Self {
@@ -86,39 +114,58 @@ impl ReadAlignment {
         min_qaln_len: u64,
         min_qaln_cov: f64,
         min_mapq: u8,
+        filter_mode: &PafFilterMode,
+        min_identity: f64,
     ) -> Result<Self, ScrubbyError> {
-        
-        let mut target_reads: HashSet<String> = HashSet::new();
+
+        let mut reads: HashMap<String, PafReadAccumulator> = HashMap::new();
 
         if !is_file_empty(path)? {
-            let reader: Box<dyn BufRead> = if path.to_str() == Some("-") {
-                Box::new(BufReader::new(std::io::stdin()))
-            } else {
-                let (reader, _) = niffler::from_path(path)?;
-                Box::new(BufReader::new(reader))
-            };
-            for result in reader.lines() {
-                let record: PafRecord = PafRecord::from_str(&result?)?;
-                if (record.query_aligned_length() >= min_qaln_len
-                    || record.query_coverage() >= min_qaln_cov)
-                    && record.mapq >= min_mapq
-                {
-                    target_reads.insert(record.qname);
-                }
+            let reader = open_alignment_reader(path)?;
+            for (index, result) in reader.lines().enumerate() {
+                let context = ParseContext::new(path.clone(), (index + 1) as u64);
+                let record: PafRecord = PafRecord::from_str(&result?, &context)?;
+                reads
+                    .entry(record.qname.clone())
+                    .and_modify(|accumulator| accumulator.add(&record))
+                    .or_insert_with(|| PafReadAccumulator::new(&record));
             }
         }
 
+        let target_reads = reads
+            .into_iter()
+            .filter(|(_, accumulator)| accumulator.passes_filters(min_qaln_len, min_qaln_cov, min_mapq, filter_mode, min_identity))
+            .map(|(qname, _)| qname)
+            .collect();
+
         Ok(Self {
             aligned_reads: target_reads,
         })
     }
     #[cfg(feature = "htslib")]
     // Parse alignments from file
+    //
+    // Paired-end input needs no separate "mark the whole template" step: both
+    // mates of a template share the same QNAME, so a passing alignment on
+    // either one already adds that shared identifier to `target_reads`, which
+    // depletes/extracts the pair together via `Cleaner::clean_reads`'s
+    // paired-mode writer. `skip_secondary` and `require_proper_pair` instead
+    // guard against a record flagging its template on weaker grounds than a
+    // caller wants: a secondary/supplementary alignment of the same read, or
+    // (for paired-end input) one mate of a template whose pair didn't map concordantly.
+    // CRAM records are reference-compressed, so `reference` must be set for `is_cram` inputs
+    // or decoding fails on the first record; this is checked before any record is read.
     pub fn from_bam(
         path: &PathBuf,
         min_qaln_len: u64,
         min_qaln_cov: f64,
         min_mapq: u8,
+        filter_mode: &PafFilterMode,
+        skip_secondary: bool,
+        require_proper_pair: bool,
+        min_identity: f64,
+        reference: Option<PathBuf>,
+        is_cram: bool,
     ) -> Result<Self, ScrubbyError> {
 
         let mut reader = if path.to_str() == Some("-") {
@@ -127,17 +174,43 @@ impl ReadAlignment {
             bam::Reader::from_path(path)?
         };
 
+        if is_cram {
+            let reference = reference.ok_or_else(|| ScrubbyError::CramReferenceRequired(path.clone()))?;
+            reader.set_reference(&reference)?;
+        }
+
         let mut target_reads: HashSet<String> = HashSet::new();
 
-        for result in reader.records() {
+        for (index, result) in reader.records().enumerate() {
             let record = result?;
             if record.is_unmapped() {
                 continue;
             }
-            let bam_record = BamRecord::from(&record)?;
-            if (bam_record.qalen >= min_qaln_len || bam_record.query_coverage() >= min_qaln_cov)
-                && bam_record.mapq >= min_mapq
-            {
+            let context = ParseContext::new(path.clone(), (index + 1) as u64);
+            let bam_record = BamRecord::from(&record, &context)?;
+
+            if skip_secondary && (bam_record.is_secondary || bam_record.is_supplementary) {
+                continue;
+            }
+            if require_proper_pair && bam_record.is_paired && !bam_record.is_proper_pair {
+                continue;
+            }
+            // `identity` is `None` when the record has no `NM` tag (not every
+            // aligner writes one); such records are left to the length/coverage/
+            // mapq thresholds rather than being silently dropped.
+            if let Some(identity) = bam_record.identity {
+                if identity < min_identity {
+                    continue;
+                }
+            }
+
+            let length_ok = bam_record.qalen >= min_qaln_len;
+            let coverage_ok = bam_record.query_coverage() >= min_qaln_cov;
+            let combined = match filter_mode {
+                PafFilterMode::Any => length_ok || coverage_ok,
+                PafFilterMode::All => length_ok && coverage_ok,
+            };
+            if combined && bam_record.mapq >= min_mapq {
                 target_reads.insert(bam_record.qname);
             }
         }
@@ -148,6 +221,19 @@ impl ReadAlignment {
     }
 }
 
+/// Opens a PAF/GAF/TXT alignment file for line-by-line reading, transparently
+/// decompressing gzip/bzip2/xz/zstd input. `"-"` streams from stdin instead of
+/// opening `path`, sniffing the same leading magic bytes from the piped data
+/// so a compressed stream (e.g. `minimap2 ... | bgzip | scrubby --alignment -`)
+/// is decoded without requiring the caller to decompress it first.
+fn open_alignment_reader(path: &PathBuf) -> Result<Box<dyn BufRead>, ScrubbyError> {
+    if path.to_str() == Some("-") {
+        let (reader, _) = niffler::get_reader(Box::new(std::io::stdin()))?;
+        Ok(Box::new(BufReader::new(reader)))
+    } else {
+        crate::compression::open_reader(path)
+    }
+}
 
 /*
 =================
@@ -172,6 +258,35 @@ fn qalen_from_cigar<'a>(cigar: impl Iterator<Item = &'a Cigar>) -> u32 {
         .sum()
 }
 
+#[cfg(feature = "htslib")]
+/// Returns the alignment block length from a CIGAR string: the sum of
+/// matches/mismatches (M, =, X), insertions (I) and deletions (D) - the
+/// denominator `NM` (edit distance) is defined against, unlike
+/// `qalen_from_cigar`'s query-only span.
+fn alignment_block_len_from_cigar<'a>(cigar: impl Iterator<Item = &'a Cigar>) -> u32 {
+    cigar
+        .map(|x| match x {
+            Cigar::Match(_) | Cigar::Equal(_) | Cigar::Diff(_) | Cigar::Ins(_) | Cigar::Del(_) => x.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(feature = "htslib")]
+/// Reads the `NM` (edit distance) aux tag, returning `None` if the record
+/// doesn't carry one (not every aligner writes it).
+fn nm_tag(record: &bam::Record) -> Option<i64> {
+    match record.aux(b"NM") {
+        Ok(Aux::I8(v)) => Some(v as i64),
+        Ok(Aux::U8(v)) => Some(v as i64),
+        Ok(Aux::I16(v)) => Some(v as i64),
+        Ok(Aux::U16(v)) => Some(v as i64),
+        Ok(Aux::I32(v)) => Some(v as i64),
+        Ok(Aux::U32(v)) => Some(v as i64),
+        _ => None,
+    }
+}
+
 #[cfg(feature = "htslib")]
 #[derive(Debug, Clone)]
 pub struct BamRecord {
@@ -183,22 +298,54 @@ pub struct BamRecord {
     pub qalen: u64,
     /// Mapping quality (0-255; 255 for missing).
     pub mapq: u8,
+    /// Whether the read is part of a paired-end template.
+    pub is_paired: bool,
+    /// Whether the read's template aligned in the expected orientation/distance
+    /// ("proper pair" SAM flag). Always `false` for single-end reads.
+    pub is_proper_pair: bool,
+    /// Whether this is a secondary alignment (an alternative mapping of a read
+    /// already reported elsewhere as primary).
+    pub is_secondary: bool,
+    /// Whether this is a supplementary alignment (part of a split/chimeric read).
+    pub is_supplementary: bool,
+    /// Whether this read's mate is unmapped. Always `false` for single-end reads.
+    pub is_mate_unmapped: bool,
+    /// Alignment identity, `1 - NM / alignment_block_len`, reconstructed from
+    /// the `NM` edit-distance aux tag and the CIGAR string. `None` when the
+    /// record has no `NM` tag.
+    pub identity: Option<f64>,
 }
 #[cfg(feature = "htslib")]
 impl BamRecord {
     /// Create a new (reduced) BamRecord from a BAM HTS LIB record
-    pub fn from(record: &bam::Record) -> Result<Self, ScrubbyError> {
-        let qname = from_utf8(record.qname())?.to_string();
+    pub fn from(record: &bam::Record, context: &ParseContext) -> Result<Self, ScrubbyError> {
+        let qname = from_utf8(record.qname())
+            .map_err(|error| ScrubbyError::RecordNameUtf8Error(context.clone(), error))?
+            .to_string();
         let qlen = record.seq_len() as u32;
         let mapq = record.mapq();
 
         let qalen = qalen_from_cigar(record.cigar().iter());
+        let identity = nm_tag(record).map(|nm| {
+            let block_len = alignment_block_len_from_cigar(record.cigar().iter());
+            if block_len == 0 {
+                0.0
+            } else {
+                1.0 - (nm as f64 / block_len as f64)
+            }
+        });
 
         Ok(Self {
             qname,
             qlen,
             qalen: qalen as u64,
             mapq,
+            is_paired: record.is_paired(),
+            is_proper_pair: record.is_paired() && record.is_proper_pair(),
+            is_secondary: record.is_secondary(),
+            is_supplementary: record.is_supplementary(),
+            is_mate_unmapped: record.is_paired() && record.is_mate_unmapped(),
+            identity,
         })
     }
     /// Coverage of the aligned query sequence.
@@ -241,22 +388,28 @@ pub struct PafRecord {
 
 impl PafRecord {
     // Create a record from a parsed line
-    pub fn from_str(paf: &str) -> Result<Self, ScrubbyError> {
+    pub fn from_str(paf: &str, context: &ParseContext) -> Result<Self, ScrubbyError> {
         let fields: Vec<&str> = paf.split('\t').collect();
 
+        macro_rules! parse_field {
+            ($index:expr, $name:expr, $ty:ty) => {
+                fields[$index].parse::<$ty>().map_err(|error| ScrubbyError::PafRecordIntegerError(context.with_field($name), error))?
+            };
+        }
+
         let record = Self {
             qname: fields[0].to_string(),
-            qlen: fields[1].parse::<u64>()?,
-            qstart: fields[2].parse::<usize>()?,
-            qend: fields[3].parse::<usize>()?,
+            qlen: parse_field!(1, "qlen", u64),
+            qstart: parse_field!(2, "qstart", usize),
+            qend: parse_field!(3, "qend", usize),
             strand: fields[4].to_string(),
             tname: fields[5].to_string(),
-            tlen: fields[6].parse::<u64>()?,
-            tstart: fields[7].parse::<usize>()?,
-            tend: fields[8].parse::<usize>()?,
-            mlen: fields[9].parse::<u64>()?,
-            blen: fields[10].parse::<u64>()?,
-            mapq: fields[11].parse::<u8>()?,
+            tlen: parse_field!(6, "tlen", u64),
+            tstart: parse_field!(7, "tstart", usize),
+            tend: parse_field!(8, "tend", usize),
+            mlen: parse_field!(9, "mlen", u64),
+            blen: parse_field!(10, "blen", u64),
+            mapq: parse_field!(11, "mapq", u8),
         };
 
         Ok(record)
@@ -273,4 +426,86 @@ impl PafRecord {
             false => self.query_aligned_length() as f64 / self.qlen as f64,
         }
     }
+    /// Alignment identity, the fraction of the alignment block that matches: `mlen / blen`.
+    pub fn identity(&self) -> f64 {
+        match self.blen == 0 {
+            true => 0f64,
+            false => self.mlen as f64 / self.blen as f64,
+        }
+    }
+    /// Whether this single record, in isolation, meets the minimum query
+    /// alignment length and/or coverage threshold (combined according to
+    /// `filter_mode`), the minimum mapping quality, and the minimum identity.
+    /// Records for the same query across multiple (e.g. supplementary)
+    /// alignment lines should instead be combined with `PafReadAccumulator`
+    /// before thresholding.
+    pub fn passes_filters(&self, min_qaln_len: u64, min_qaln_cov: f64, min_mapq: u8, filter_mode: &PafFilterMode, min_identity: f64) -> bool {
+        let length_ok = self.query_aligned_length() >= min_qaln_len;
+        let coverage_ok = self.query_coverage() >= min_qaln_cov;
+        let combined = match filter_mode {
+            PafFilterMode::Any => length_ok || coverage_ok,
+            PafFilterMode::All => length_ok && coverage_ok,
+        };
+        combined && self.mapq >= min_mapq && self.identity() >= min_identity
+    }
+}
+
+/// Accumulates query alignment length and mapping quality across multiple
+/// PAF records for the same query name (e.g. supplementary/chimeric
+/// alignments of one read), so length/coverage thresholds are judged on the
+/// read's combined alignment rather than on each alignment line independently.
+#[derive(Debug, Clone)]
+pub struct PafReadAccumulator {
+    qlen: u64,
+    query_aligned_length: u64,
+    mapq: u8,
+    mlen: u64,
+    blen: u64,
+}
+impl PafReadAccumulator {
+    /// Starts accumulation from the first alignment seen for a query.
+    pub fn new(record: &PafRecord) -> Self {
+        Self {
+            qlen: record.qlen,
+            query_aligned_length: record.query_aligned_length(),
+            mapq: record.mapq,
+            mlen: record.mlen,
+            blen: record.blen,
+        }
+    }
+    /// Folds in another alignment of the same query, summing aligned length
+    /// (capped at the query length), matching/block bases, and keeping the
+    /// highest mapping quality seen.
+    pub fn add(&mut self, record: &PafRecord) {
+        self.query_aligned_length = (self.query_aligned_length + record.query_aligned_length()).min(self.qlen);
+        self.mapq = self.mapq.max(record.mapq);
+        self.mlen += record.mlen;
+        self.blen += record.blen;
+    }
+    /// Combined coverage of the query across all accumulated alignments.
+    pub fn query_coverage(&self) -> f64 {
+        match self.qlen == 0 {
+            true => 0f64,
+            false => self.query_aligned_length as f64 / self.qlen as f64,
+        }
+    }
+    /// Combined alignment identity across all accumulated alignments: `mlen / blen`.
+    pub fn identity(&self) -> f64 {
+        match self.blen == 0 {
+            true => 0f64,
+            false => self.mlen as f64 / self.blen as f64,
+        }
+    }
+    /// Whether the accumulated alignments meet the minimum query alignment
+    /// length and/or coverage threshold (combined according to `filter_mode`),
+    /// the minimum mapping quality, and the minimum identity.
+    pub fn passes_filters(&self, min_qaln_len: u64, min_qaln_cov: f64, min_mapq: u8, filter_mode: &PafFilterMode, min_identity: f64) -> bool {
+        let length_ok = self.query_aligned_length >= min_qaln_len;
+        let coverage_ok = self.query_coverage() >= min_qaln_cov;
+        let combined = match filter_mode {
+            PafFilterMode::Any => length_ok || coverage_ok,
+            PafFilterMode::All => length_ok && coverage_ok,
+        };
+        combined && self.mapq >= min_mapq && self.identity() >= min_identity
+    }
 }
@@ -0,0 +1,108 @@
+//! This module provides an opt-in audit trail that attributes each removed or
+//! extracted read to the pipeline stage and reference database that flagged it,
+//! so users running a classifier and an aligner together (or several reference
+//! databases in sequence) can reproduce and justify exactly why a given read
+//! was depleted.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ScrubbyError;
+
+/// Attribution for a single read: the stage that flagged it (`"classifier"`,
+/// `"aligner"`, `"bracken"`, `"complexity"` or `"sketch"`) and the reference it
+/// was flagged against (the index or database path, the complexity method
+/// name, or empty for a combined aligner/classifier vote).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReadAttribution {
+    pub read_id: String,
+    pub stage: String,
+    pub db: String,
+    pub taxid: Option<String>,
+}
+
+/// Accumulates read attributions across one or more classifier/aligner stages.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ReadAudit {
+    pub records: Vec<ReadAttribution>,
+}
+
+impl ReadAudit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records attribution for every read in `read_ids` as having been flagged
+    /// by `stage` against reference database `db`.
+    pub fn record(&mut self, read_ids: &HashSet<String>, stage: &str, db: &str, taxid: Option<&str>) {
+        for read_id in read_ids {
+            self.records.push(ReadAttribution {
+                read_id: read_id.clone(),
+                stage: stage.to_string(),
+                db: db.to_string(),
+                taxid: taxid.map(str::to_string),
+            });
+        }
+    }
+
+    /// Aggregate removed-read counts per reference database.
+    pub fn counts_per_db(&self) -> HashMap<String, u64> {
+        let mut counts = HashMap::new();
+        for record in &self.records {
+            *counts.entry(record.db.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Aggregate removed-read counts per taxonomic identifier.
+    pub fn counts_per_taxid(&self) -> HashMap<String, u64> {
+        let mut counts = HashMap::new();
+        for record in &self.records {
+            if let Some(taxid) = &record.taxid {
+                *counts.entry(taxid.clone()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Writes the audit trail as a TSV with one row per removed read.
+    pub fn write_tsv(&self, path: &PathBuf) -> Result<(), ScrubbyError> {
+        let mut file = File::create(path)?;
+        writeln!(file, "read_id\tstage\tdb\ttaxid")?;
+        for record in &self.records {
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}",
+                record.read_id,
+                record.stage,
+                record.db,
+                record.taxid.as_deref().unwrap_or("")
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the audit trail as JSON including the per-db and per-taxid aggregates.
+    pub fn write_json(&self, path: &PathBuf) -> Result<(), ScrubbyError> {
+        let summary = AuditSummary {
+            records: self.records.clone(),
+            reads_per_db: self.counts_per_db(),
+            reads_per_taxid: self.counts_per_taxid(),
+        };
+        let mut file = File::create(path)?;
+        let json_string = serde_json::to_string_pretty(&summary)?;
+        file.write_all(json_string.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct AuditSummary {
+    records: Vec<ReadAttribution>,
+    reads_per_db: HashMap<String, u64>,
+    reads_per_taxid: HashMap<String, u64>,
+}
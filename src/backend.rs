@@ -0,0 +1,243 @@
+//! Extension point for aligner/classifier tools not covered by the built-in
+//! `Aligner`/`Classifier` enums. Adding a new built-in tool means editing
+//! those enums plus every `match` arm in `cleaner.rs`/`scrubby.rs`; this
+//! module lets a library user register a custom backend by name instead,
+//! selected at runtime via `ScrubbyConfig.custom_aligner`/`custom_classifier`
+//! (`--aligner custom:<name>` is not a CLI concept - the CLI keeps its
+//! closed `clap::ValueEnum` surface and custom backends are a library-only
+//! extension). The built-in aligners are themselves registered under their
+//! `Display` name (see `resolve_aligner_backend`), so `ScrubbyBuilder::build`
+//! validates presets/indices through the same trait rather than a hardcoded
+//! per-variant match.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::alignment::AlignmentFormat;
+use crate::error::ScrubbyError;
+use crate::scrubby::{Aligner, Preset, Scrubby};
+
+/// A custom aligner, registered with `register_aligner_backend` and selected
+/// by name via `ScrubbyConfig.custom_aligner`. Implementations build the
+/// shell command to invoke their tool; `Cleaner` runs it and parses the
+/// resulting alignment file with the existing SAM/BAM/PAF/GAF parsers, the
+/// same as the built-in aligners. `validate_index`/`default_preset`/
+/// `supported_presets` back the same checks `ScrubbyBuilder::build` runs for
+/// the built-in aligners, so a custom backend gets the same config-time
+/// validation without `build` knowing about it.
+pub trait AlignerBackend: Send + Sync {
+    /// Registry key, matched against `ScrubbyConfig.custom_aligner` (for
+    /// custom backends) or an `Aligner`'s `Display` output (for built-ins).
+    fn short_name(&self) -> &str;
+    /// Builds the full shell command to align `scrubby`'s configured input
+    /// against its configured index, writing the result to `output_path`.
+    /// Only called for custom backends - built-in aligners are still run by
+    /// `Cleaner`'s existing per-tool methods.
+    fn command(&self, scrubby: &Scrubby, output_path: &Path) -> Result<String, ScrubbyError>;
+    /// Format `output_path` is written in, so it can be parsed back with `ReadAlignment::from`.
+    fn output_format(&self) -> AlignmentFormat;
+    /// Validates an index path before a run starts. Default accepts any
+    /// existing file; override for tools with a non-standard index layout
+    /// (e.g. the built-in Bowtie2/Strobealign profiles).
+    fn validate_index(&self, index: &Path) -> Result<(), ScrubbyError> {
+        if !index.exists() || !index.is_file() {
+            return Err(ScrubbyError::MissingAlignmentIndexFile(index.to_path_buf()));
+        }
+        Ok(())
+    }
+    /// Preset to fall back to when none is configured, if this tool has a
+    /// concept of presets at all. Default: none.
+    fn default_preset(&self, _paired_end: bool) -> Option<Preset> {
+        None
+    }
+    /// Presets this tool accepts. An empty slice (the default) means presets
+    /// are not validated - used for tools with no preset concept at all.
+    fn supported_presets(&self) -> &[Preset] {
+        &[]
+    }
+    /// Error returned when a user-supplied preset is absent from
+    /// `supported_presets`. Default is a generic message; built-ins override
+    /// this to preserve their existing dedicated error variants.
+    fn preset_not_supported_error(&self, preset: &Preset) -> ScrubbyError {
+        ScrubbyError::AlignerBackendPresetNotSupported(self.short_name().to_string(), preset.to_string())
+    }
+}
+
+/// A custom classifier, registered with `register_classifier_backend` and
+/// selected by name via `ScrubbyConfig.custom_classifier`. Implementations
+/// must produce a Kraken2-style report and per-read classification file
+/// pair, which `Cleaner` then parses the same way as the built-in classifiers.
+pub trait ClassifierBackend: Send + Sync {
+    /// Registry key, matched against `ScrubbyConfig.custom_classifier`.
+    fn short_name(&self) -> &str;
+    /// Builds the full shell command to classify `scrubby`'s configured
+    /// input against its configured index, writing a Kraken2-style report to
+    /// `report_path` and per-read classifications to `reads_path`.
+    fn command(&self, scrubby: &Scrubby, report_path: &Path, reads_path: &Path) -> Result<String, ScrubbyError>;
+}
+
+type AlignerRegistry = Mutex<HashMap<String, Arc<dyn AlignerBackend>>>;
+type ClassifierRegistry = Mutex<HashMap<String, Arc<dyn ClassifierBackend>>>;
+
+static ALIGNER_BACKENDS: OnceLock<AlignerRegistry> = OnceLock::new();
+static CLASSIFIER_BACKENDS: OnceLock<ClassifierRegistry> = OnceLock::new();
+
+/// Registers a custom aligner backend under `backend.short_name()`, making it
+/// selectable via `ScrubbyConfig.custom_aligner`/`ScrubbyBuilder::custom_aligner`
+/// without forking the crate.
+pub fn register_aligner_backend(backend: Arc<dyn AlignerBackend>) {
+    let registry = aligner_registry();
+    registry.lock()
+        .expect("aligner backend registry poisoned")
+        .insert(backend.short_name().to_string(), backend);
+}
+
+/// Registers a custom classifier backend, see `register_aligner_backend`.
+pub fn register_classifier_backend(backend: Arc<dyn ClassifierBackend>) {
+    let registry = CLASSIFIER_BACKENDS.get_or_init(|| Mutex::new(HashMap::new()));
+    registry.lock()
+        .expect("classifier backend registry poisoned")
+        .insert(backend.short_name().to_string(), backend);
+}
+
+pub(crate) fn get_aligner_backend(name: &str) -> Option<Arc<dyn AlignerBackend>> {
+    aligner_registry().lock().expect("aligner backend registry poisoned").get(name).cloned()
+}
+
+pub(crate) fn get_classifier_backend(name: &str) -> Option<Arc<dyn ClassifierBackend>> {
+    CLASSIFIER_BACKENDS.get()?.lock().expect("classifier backend registry poisoned").get(name).cloned()
+}
+
+/// Resolves the `AlignerBackend` profile for a built-in `Aligner` variant, so
+/// `ScrubbyBuilder::build` can validate its index/preset through the trait
+/// instead of a per-variant match. Always present - built-ins are seeded into
+/// the registry on first access, see `aligner_registry`.
+pub(crate) fn resolve_aligner_backend(aligner: &Aligner) -> Arc<dyn AlignerBackend> {
+    get_aligner_backend(&aligner.to_string())
+        .expect("built-in aligner backends are seeded into the registry on first access")
+}
+
+/// Returns the shared aligner registry, seeding it with the built-in aligner
+/// profiles (keyed by their `Display` name) on first access so they are
+/// resolved through the same trait/registry as custom backends.
+fn aligner_registry() -> &'static AlignerRegistry {
+    ALIGNER_BACKENDS.get_or_init(|| {
+        let mut builtins: HashMap<String, Arc<dyn AlignerBackend>> = HashMap::new();
+        builtins.insert(Aligner::Minimap2.to_string(), Arc::new(Minimap2Profile));
+        builtins.insert(Aligner::Minigraph.to_string(), Arc::new(MinigraphProfile));
+        builtins.insert(Aligner::Bowtie2.to_string(), Arc::new(Bowtie2Profile));
+        builtins.insert(Aligner::Strobealign.to_string(), Arc::new(StrobealignProfile));
+        #[cfg(feature = "mm2")]
+        builtins.insert(Aligner::Minimap2Rs.to_string(), Arc::new(Minimap2RsProfile));
+        Mutex::new(builtins)
+    })
+}
+
+/// Presets minimap2 (and the integrated `minimap2-rs`) accept - every preset except `Lr`, which is minigraph-only.
+const MINIMAP2_PRESETS: &[Preset] = &[
+    Preset::LrHq, Preset::Splice, Preset::SpliceHq, Preset::Asm, Preset::Asm5,
+    Preset::Asm10, Preset::Asm20, Preset::Sr, Preset::MapPb, Preset::MapHifi,
+    Preset::MapOnt, Preset::AvaPb, Preset::AvaOnt,
+];
+const MINIGRAPH_PRESETS: &[Preset] = &[Preset::Lr, Preset::Sr, Preset::Asm];
+
+/// Built-in profile for `Aligner::Minimap2`.
+struct Minimap2Profile;
+impl AlignerBackend for Minimap2Profile {
+    fn short_name(&self) -> &str { "minimap2" }
+    fn command(&self, _scrubby: &Scrubby, _output_path: &Path) -> Result<String, ScrubbyError> {
+        unreachable!("built-in aligners are run by Cleaner's dedicated methods, not through the backend registry")
+    }
+    fn output_format(&self) -> AlignmentFormat { AlignmentFormat::Sam }
+    fn default_preset(&self, paired_end: bool) -> Option<Preset> {
+        Some(if paired_end { Preset::Sr } else { Preset::MapOnt })
+    }
+    fn supported_presets(&self) -> &[Preset] { MINIMAP2_PRESETS }
+    fn preset_not_supported_error(&self, preset: &Preset) -> ScrubbyError {
+        ScrubbyError::Minimap2PresetNotSupported(preset.clone())
+    }
+}
+
+/// Built-in profile for `Aligner::Minimap2Rs` (the `mm2` feature's integrated aligner), same preset rules as `Minimap2Profile`.
+#[cfg(feature = "mm2")]
+struct Minimap2RsProfile;
+#[cfg(feature = "mm2")]
+impl AlignerBackend for Minimap2RsProfile {
+    fn short_name(&self) -> &str { "minimap2-rs" }
+    fn command(&self, _scrubby: &Scrubby, _output_path: &Path) -> Result<String, ScrubbyError> {
+        unreachable!("built-in aligners are run by Cleaner's dedicated methods, not through the backend registry")
+    }
+    fn output_format(&self) -> AlignmentFormat { AlignmentFormat::Sam }
+    fn default_preset(&self, paired_end: bool) -> Option<Preset> {
+        Some(if paired_end { Preset::Sr } else { Preset::MapOnt })
+    }
+    fn supported_presets(&self) -> &[Preset] { MINIMAP2_PRESETS }
+    fn preset_not_supported_error(&self, preset: &Preset) -> ScrubbyError {
+        ScrubbyError::Minimap2PresetNotSupported(preset.clone())
+    }
+}
+
+/// Built-in profile for `Aligner::Minigraph`.
+struct MinigraphProfile;
+impl AlignerBackend for MinigraphProfile {
+    fn short_name(&self) -> &str { "minigraph" }
+    fn command(&self, _scrubby: &Scrubby, _output_path: &Path) -> Result<String, ScrubbyError> {
+        unreachable!("built-in aligners are run by Cleaner's dedicated methods, not through the backend registry")
+    }
+    fn output_format(&self) -> AlignmentFormat { AlignmentFormat::Gaf }
+    fn default_preset(&self, paired_end: bool) -> Option<Preset> {
+        Some(if paired_end { Preset::Sr } else { Preset::Lr })
+    }
+    fn supported_presets(&self) -> &[Preset] { MINIGRAPH_PRESETS }
+    fn preset_not_supported_error(&self, preset: &Preset) -> ScrubbyError {
+        ScrubbyError::MinigraphPresetNotSupported(preset.clone())
+    }
+}
+
+/// Built-in profile for `Aligner::Bowtie2`: no preset concept, but requires
+/// the full `.bt2`/`.bt2l` index file set rather than a single file.
+struct Bowtie2Profile;
+impl AlignerBackend for Bowtie2Profile {
+    fn short_name(&self) -> &str { "bowtie2" }
+    fn command(&self, _scrubby: &Scrubby, _output_path: &Path) -> Result<String, ScrubbyError> {
+        unreachable!("built-in aligners are run by Cleaner's dedicated methods, not through the backend registry")
+    }
+    fn output_format(&self) -> AlignmentFormat { AlignmentFormat::Sam }
+    fn validate_index(&self, index: &Path) -> Result<(), ScrubbyError> {
+        let small_extensions = ["1.bt2", "2.bt2", "3.bt2", "4.bt2", "rev.1.bt2", "rev.2.bt2"];
+        let large_extensions = ["1.bt2l", "2.bt2l", "3.bt2l", "4.bt2l", "rev.1.bt2l", "rev.2.bt2l"];
+        for (small_ext, large_ext) in small_extensions.iter().zip(large_extensions.iter()) {
+            let small_index_file = index.with_extension(small_ext);
+            let large_index_file = index.with_extension(large_ext);
+            if (!small_index_file.exists() || !small_index_file.is_file())
+                && (!large_index_file.exists() || !large_index_file.is_file()) {
+                return Err(ScrubbyError::MissingBowtie2IndexFiles(index.to_path_buf()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Built-in profile for `Aligner::Strobealign`: no preset concept, but a
+/// `.sti` index requires the matching reference FASTA alongside it.
+struct StrobealignProfile;
+impl AlignerBackend for StrobealignProfile {
+    fn short_name(&self) -> &str { "strobealign" }
+    fn command(&self, _scrubby: &Scrubby, _output_path: &Path) -> Result<String, ScrubbyError> {
+        unreachable!("built-in aligners are run by Cleaner's dedicated methods, not through the backend registry")
+    }
+    fn output_format(&self) -> AlignmentFormat { AlignmentFormat::Sam }
+    fn validate_index(&self, index: &Path) -> Result<(), ScrubbyError> {
+        if !index.exists() || !index.is_file() {
+            return Err(ScrubbyError::MissingAlignmentIndexFile(index.to_path_buf()));
+        }
+        if index.extension().unwrap_or_default() == "sti" {
+            let index_base_file = index.with_extension("").with_extension("");
+            if !index_base_file.exists() {
+                return Err(ScrubbyError::MissingStrobealignIndexBaseFile(index_base_file));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+//! Filters an already-aligned BAM/CRAM/SAM by taxonomic assignment, analogous
+//! to viral-ngs's `filter_bam_to_taxa`: given a read-to-taxid mapping (either
+//! a classifier's own per-read output or a precomputed TSV) and a set of
+//! target taxids, writes a BAM/CRAM/SAM containing (or excluding) the reads
+//! assigned to those taxa, so host contamination can be scrubbed from
+//! already-aligned data without round-tripping back to FASTQ.
+
+#[cfg(feature = "htslib")]
+use rust_htslib::{bam, bam::Read};
+#[cfg(feature = "htslib")]
+use std::str::from_utf8;
+
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::path::PathBuf;
+#[cfg(feature = "htslib")]
+use std::path::Path;
+
+use crate::classifier::{get_taxid_reads_for_format, ClassifierOutputFormat};
+#[cfg(feature = "htslib")]
+use crate::error::ParseContext;
+use crate::error::ScrubbyError;
+
+/// Resolves the read identifiers assigned to `target_taxids`, either from a
+/// classifier's per-read output file (`format` given) or from a precomputed
+/// `read_id<TAB>tax_id` TSV (`format` is `None`).
+pub fn read_ids_for_taxa(
+    reads: &PathBuf,
+    format: Option<ClassifierOutputFormat>,
+    target_taxids: &HashSet<String>,
+) -> Result<HashSet<String>, ScrubbyError> {
+    match format {
+        Some(format) => get_taxid_reads_for_format(format, target_taxids, reads),
+        None => read_ids_from_taxid_tsv(reads, target_taxids),
+    }
+}
+
+/// Parses a two-column `read_id<TAB>tax_id` TSV, for assignments that did not
+/// come from one of the classifier read-output formats `get_taxid_reads_for_format`
+/// already knows how to parse.
+fn read_ids_from_taxid_tsv(path: &PathBuf, target_taxids: &HashSet<String>) -> Result<HashSet<String>, ScrubbyError> {
+    let mut read_ids = HashSet::new();
+
+    let reader = crate::compression::open_reader(path)?;
+    for line in reader.lines() {
+        let line = line?;
+        let mut fields = line.splitn(2, '\t');
+        let (Some(read_id), Some(tax_id)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if target_taxids.contains(tax_id.trim()) {
+            read_ids.insert(read_id.trim().to_string());
+        }
+    }
+
+    log::debug!("{} matching classified reads were detected in taxid map", read_ids.len());
+    Ok(read_ids)
+}
+
+/// Writes a BAM/CRAM/SAM containing the records whose read name is in
+/// `target_read_ids` (`extract = true`), or everything else (`extract =
+/// false`), preserving the input header. Paired records share a query name,
+/// so mate pairs are kept or dropped together without any extra bookkeeping.
+#[cfg(feature = "htslib")]
+pub fn filter_bam_by_read_ids(
+    input: &Path,
+    output: &Path,
+    target_read_ids: &HashSet<String>,
+    extract: bool,
+) -> Result<(), ScrubbyError> {
+    let mut reader = if input.to_str() == Some("-") {
+        bam::Reader::from_stdin()?
+    } else {
+        bam::Reader::from_path(input)?
+    };
+
+    let header = bam::Header::from_template(reader.header());
+    let mut writer = bam::Writer::from_path(output, &header, output_format(output))?;
+
+    for (index, result) in reader.records().enumerate() {
+        let record = result?;
+        let context = ParseContext::new(input.to_path_buf(), (index + 1) as u64);
+        let qname = from_utf8(record.qname())
+            .map_err(|error| ScrubbyError::RecordNameUtf8Error(context, error))?;
+
+        if target_read_ids.contains(qname) == extract {
+            writer.write(&record)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Infers the htslib output format from the output path's extension,
+/// defaulting to BAM when it is absent or unrecognized.
+#[cfg(feature = "htslib")]
+fn output_format(path: &Path) -> bam::Format {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("cram") => bam::Format::Cram,
+        Some("sam") => bam::Format::Sam,
+        _ => bam::Format::Bam,
+    }
+}
+
+#[cfg(not(feature = "htslib"))]
+pub fn filter_bam_by_read_ids(
+    _input: &std::path::Path,
+    _output: &std::path::Path,
+    _target_read_ids: &HashSet<String>,
+    _extract: bool,
+) -> Result<(), ScrubbyError> {
+    Err(ScrubbyError::AlignmentInputFormatInvalid)
+}
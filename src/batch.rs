@@ -0,0 +1,330 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+
+use crate::compression::CompressionAlgorithm;
+use crate::error::ScrubbyError;
+use crate::scrubby::{Aligner, Classifier, Preset, ScrubbyBuilder};
+
+/// One row of a `batch --sheet` file: a single sequencing run of a sample,
+/// optionally paired-end. `run` and `fastq_2` are optional so the same sheet
+/// can describe single-run and multi-run samples, short and long reads.
+#[derive(Deserialize, Debug, Clone)]
+pub struct BatchSampleRow {
+    pub sample: String,
+    #[serde(default)]
+    pub run: Option<String>,
+    pub fastq_1: PathBuf,
+    #[serde(default)]
+    pub fastq_2: Option<PathBuf>,
+}
+
+/// Options shared by every sample in a `batch` run, the subset of `ReadsArgs`
+/// that makes sense to fix once for a whole sheet rather than per row.
+#[derive(Clone, Debug)]
+pub struct BatchOptions {
+    pub index: PathBuf,
+    pub aligner: Option<Aligner>,
+    pub classifier: Option<Classifier>,
+    pub preset: Option<Preset>,
+    pub taxa: Vec<String>,
+    pub taxa_direct: Vec<String>,
+    pub extract: bool,
+    pub threads: usize,
+    pub compression_format: Option<CompressionAlgorithm>,
+    pub compression_level: Option<u32>,
+    pub compression_threads: Option<usize>,
+    pub merge_runs: bool,
+    pub parallel: usize,
+    pub resume: bool,
+}
+
+/// Status of one sample's task in the persisted queue (`outdir/queue.json`),
+/// modeled loosely on task-queue schedulers like meilisearch's
+/// index-scheduler: a task starts `Enqueued`, moves to `Processing` once a
+/// worker picks it up, and ends in a terminal `Succeeded`/`Failed`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Enqueued,
+    Processing,
+    Succeeded,
+    Failed,
+}
+
+/// One sample's entry in the persisted task queue, carrying enough of its
+/// eventual `BatchSampleOutcome` that a `Succeeded` task can be reported
+/// again on `--resume` without re-running it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchTask {
+    pub sample: String,
+    pub status: TaskStatus,
+    pub runs: Vec<String>,
+    pub output: Vec<PathBuf>,
+    pub error: Option<String>,
+}
+
+/// Persisted task queue for a `batch` run, written to `outdir/queue.json`
+/// after every status change so a killed or crashed run can be resumed with
+/// `--resume`, skipping samples already `Succeeded`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct BatchQueue {
+    pub tasks: Vec<BatchTask>,
+}
+
+impl BatchQueue {
+    fn load(path: &Path) -> Result<Self, ScrubbyError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn write(&self, path: &Path) -> Result<(), ScrubbyError> {
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    fn status(&self, sample: &str) -> Option<TaskStatus> {
+        self.tasks.iter().find(|task| task.sample == sample).map(|task| task.status)
+    }
+
+    fn set(&mut self, sample: &str, status: TaskStatus, runs: Vec<String>, output: Vec<PathBuf>, error: Option<String>) {
+        match self.tasks.iter_mut().find(|task| task.sample == sample) {
+            Some(task) => {
+                task.status = status;
+                task.runs = runs;
+                task.output = output;
+                task.error = error;
+            }
+            None => self.tasks.push(BatchTask { sample: sample.to_string(), status, runs, output, error }),
+        }
+    }
+}
+
+/// Outcome of running one sample's job, recorded whether it succeeded or
+/// failed so one bad sample does not drop the others from `--json`.
+#[derive(Serialize, Debug)]
+pub struct BatchSampleOutcome {
+    pub sample: String,
+    pub runs: Vec<String>,
+    pub output: Vec<PathBuf>,
+    pub error: Option<String>,
+}
+
+/// Aggregated `batch --json` summary, one entry per sample sheet group.
+#[derive(Serialize, Debug)]
+pub struct BatchReport {
+    pub samples: Vec<BatchSampleOutcome>,
+}
+
+impl BatchReport {
+    pub fn write_json(&self, path: &Path) -> Result<(), ScrubbyError> {
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Loads a sample sheet, delimiter inferred from the file extension (`.tsv`
+/// is tab-delimited, anything else comma-delimited).
+pub fn read_sample_sheet(path: &Path) -> Result<Vec<BatchSampleRow>, ScrubbyError> {
+    let delimiter = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("tsv") => b'\t',
+        _ => b',',
+    };
+    let mut reader = csv::ReaderBuilder::new().delimiter(delimiter).has_headers(true).from_path(path)?;
+
+    let rows = reader.deserialize().collect::<Result<Vec<BatchSampleRow>, csv::Error>>()?;
+    if rows.is_empty() {
+        return Err(ScrubbyError::EmptySampleSheet(path.to_path_buf()));
+    }
+
+    Ok(rows)
+}
+
+/// One sample's input, after grouping sheet rows by sample (or, without
+/// `--merge-runs`, by sample and run) and staging concatenated runs to a
+/// temporary directory kept alive for the job's lifetime.
+struct SampleJob {
+    sample: String,
+    runs: Vec<String>,
+    input: Vec<PathBuf>,
+    _tempdir: Option<TempDir>,
+}
+
+fn group_rows(rows: Vec<BatchSampleRow>, merge_runs: bool) -> BTreeMap<String, Vec<BatchSampleRow>> {
+    let mut groups: BTreeMap<String, Vec<BatchSampleRow>> = BTreeMap::new();
+    for row in rows {
+        let key = if merge_runs {
+            row.sample.clone()
+        } else {
+            match &row.run {
+                Some(run) => format!("{}_{run}", row.sample),
+                None => row.sample.clone(),
+            }
+        };
+        groups.entry(key).or_default().push(row);
+    }
+    groups
+}
+
+/// Concatenates `paths` into a single file under `tempdir`. Gzip and bzip2
+/// both decode a concatenation of independently-compressed members as one
+/// stream, so this works whether the runs are compressed or not.
+fn concat_fastqs(paths: &[PathBuf], tempdir: &Path, name: &str) -> Result<PathBuf, ScrubbyError> {
+    let staged = tempdir.join(name);
+    let mut writer = std::fs::File::create(&staged)?;
+    for path in paths {
+        let mut reader = std::fs::File::open(path)?;
+        std::io::copy(&mut reader, &mut writer)?;
+    }
+    Ok(staged)
+}
+
+fn build_sample_job(sample: String, rows: Vec<BatchSampleRow>, merge_runs: bool) -> Result<SampleJob, ScrubbyError> {
+    let runs = rows.iter().map(|row| row.run.clone().unwrap_or_else(|| sample.clone())).collect();
+
+    if rows.len() == 1 && !merge_runs {
+        let row = rows.into_iter().next().expect("checked rows.len() == 1 above");
+        let mut input = vec![row.fastq_1];
+        if let Some(fastq_2) = row.fastq_2 {
+            input.push(fastq_2);
+        }
+        return Ok(SampleJob { sample, runs, input, _tempdir: None });
+    }
+
+    let tempdir = TempDir::new()?;
+    let fastq_1_paths: Vec<PathBuf> = rows.iter().map(|row| row.fastq_1.clone()).collect();
+    let mut input = vec![concat_fastqs(&fastq_1_paths, tempdir.path(), "R1.fastq")?];
+
+    let fastq_2_paths: Vec<PathBuf> = rows.iter().filter_map(|row| row.fastq_2.clone()).collect();
+    if !fastq_2_paths.is_empty() {
+        if fastq_2_paths.len() != rows.len() {
+            return Err(ScrubbyError::BatchMixedPairing(sample));
+        }
+        input.push(concat_fastqs(&fastq_2_paths, tempdir.path(), "R2.fastq")?);
+    }
+
+    Ok(SampleJob { sample, runs, input, _tempdir: Some(tempdir) })
+}
+
+fn sample_output_paths(job: &SampleJob, outdir: &Path) -> Vec<PathBuf> {
+    job.input.iter().enumerate()
+        .map(|(i, _)| outdir.join(format!("{}_{}.fastq.gz", job.sample, i + 1)))
+        .collect()
+}
+
+fn run_sample(job: SampleJob, outdir: &Path, options: &BatchOptions) -> BatchSampleOutcome {
+    let output = sample_output_paths(&job, outdir);
+    let json = outdir.join(format!("{}.json", job.sample));
+
+    let result = (|| -> Result<(), ScrubbyError> {
+        ScrubbyBuilder::new(job.input.clone(), output.clone())
+            .index(options.index.clone())
+            .aligner(options.aligner.clone())
+            .classifier(options.classifier.clone())
+            .preset(options.preset.clone())
+            .taxa(options.taxa.clone())
+            .taxa_direct(options.taxa_direct.clone())
+            .extract(options.extract)
+            .threads(options.threads)
+            .compression_format(options.compression_format)
+            .compression_level(options.compression_level)
+            .compression_threads(options.compression_threads)
+            .json(json)
+            .build()?
+            .clean()
+    })();
+
+    BatchSampleOutcome {
+        sample: job.sample,
+        runs: job.runs,
+        output,
+        error: result.err().map(|error| error.to_string()),
+    }
+}
+
+/// Runs every sample in `sheet` through the depletion pipeline described by
+/// `options`, writing per-sample outputs and `--json` reports into `outdir`,
+/// and returns an aggregated summary. Samples run concurrently up to
+/// `options.parallel` at a time; a failing sample is recorded in its
+/// `BatchSampleOutcome.error` rather than aborting the remaining samples.
+///
+/// Every sample's progress is tracked as a task in `outdir/queue.json`
+/// (`Enqueued` -> `Processing` -> `Succeeded`/`Failed`), persisted after every
+/// transition. With `options.resume`, samples already `Succeeded` in an
+/// existing queue file are reported again without being re-run, so a killed
+/// or crashed run can be restarted over the same sheet and outdir.
+pub fn run_batch(sheet: &Path, outdir: &Path, options: BatchOptions) -> Result<BatchReport, ScrubbyError> {
+    std::fs::create_dir_all(outdir)?;
+    let queue_path = outdir.join("queue.json");
+
+    let rows = read_sample_sheet(sheet)?;
+    let groups = group_rows(rows, options.merge_runs);
+    let jobs = groups.into_iter()
+        .map(|(sample, rows)| build_sample_job(sample, rows, options.merge_runs))
+        .collect::<Result<Vec<SampleJob>, _>>()?;
+
+    let mut queue = if options.resume { BatchQueue::load(&queue_path)? } else { BatchQueue::default() };
+    for job in &jobs {
+        if queue.status(&job.sample) != Some(TaskStatus::Succeeded) {
+            queue.set(&job.sample, TaskStatus::Enqueued, job.runs.clone(), Vec::new(), None);
+        }
+    }
+    let queue = Arc::new(Mutex::new(queue));
+    queue.lock().expect("batch queue poisoned").write(&queue_path)?;
+
+    let outdir = outdir.to_path_buf();
+    let (resumed, jobs): (Vec<SampleJob>, Vec<SampleJob>) = jobs.into_iter()
+        .partition(|job| queue.lock().expect("batch queue poisoned").status(&job.sample) == Some(TaskStatus::Succeeded));
+
+    let mut samples: Vec<BatchSampleOutcome> = resumed.into_iter()
+        .map(|job| BatchSampleOutcome { output: sample_output_paths(&job, &outdir), sample: job.sample, runs: job.runs, error: None })
+        .collect();
+
+    let parallel = options.parallel.max(1);
+    let jobs = Arc::new(Mutex::new(jobs.into_iter()));
+    let options = Arc::new(options);
+
+    let (sender, receiver) = mpsc::channel();
+    let workers: Vec<_> = (0..parallel).map(|_| {
+        let jobs = Arc::clone(&jobs);
+        let outdir = outdir.clone();
+        let options = Arc::clone(&options);
+        let queue = Arc::clone(&queue);
+        let queue_path = queue_path.clone();
+        let sender = sender.clone();
+        thread::spawn(move || loop {
+            let next = jobs.lock().expect("sample job queue poisoned").next();
+            let Some(job) = next else { break };
+
+            let runs = job.runs.clone();
+            queue.lock().expect("batch queue poisoned").set(&job.sample, TaskStatus::Processing, runs.clone(), Vec::new(), None);
+            queue.lock().expect("batch queue poisoned").write(&queue_path).ok();
+
+            let outcome = run_sample(job, &outdir, &options);
+            let status = if outcome.error.is_none() { TaskStatus::Succeeded } else { TaskStatus::Failed };
+            queue.lock().expect("batch queue poisoned").set(&outcome.sample, status, runs, outcome.output.clone(), outcome.error.clone());
+            queue.lock().expect("batch queue poisoned").write(&queue_path).ok();
+
+            sender.send(outcome).expect("batch report channel closed");
+        })
+    }).collect();
+    drop(sender);
+
+    samples.extend(receiver.into_iter());
+    for worker in workers {
+        worker.join().expect("sample worker thread panicked");
+    }
+    samples.sort_by(|a, b| a.sample.cmp(&b.sample));
+
+    Ok(BatchReport { samples })
+}
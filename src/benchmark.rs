@@ -0,0 +1,234 @@
+//! Benchmarking harness: runs a set of named depletion configurations
+//! described by a declarative workload file (e.g. Kraken2 vs minimap2, or a
+//! preset/thread sweep) and reports wall-clock time and depletion
+//! sensitivity for each, so a maintainer can compare configurations on their
+//! own hardware instead of scripting `scrubby reads` in a loop and timing it
+//! externally. Reuses the standard `ScrubbyBuilder`/`Scrubby::clean`
+//! pipeline for every run rather than a bespoke execution path, so a
+//! benchmarked run behaves identically to an ad hoc invocation.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ScrubbyError;
+use crate::report::ScrubbyReport;
+use crate::scrubby::{Aligner, Classifier, Preset, ScrubbyBuilder};
+
+/// One named run in a `benchmark --workload` file.
+#[derive(Deserialize, Debug, Clone)]
+pub struct BenchmarkRun {
+    pub name: String,
+    pub input: Vec<PathBuf>,
+    pub index: PathBuf,
+    #[serde(default)]
+    pub aligner: Option<Aligner>,
+    #[serde(default)]
+    pub classifier: Option<Classifier>,
+    #[serde(default)]
+    pub preset: Option<Preset>,
+    #[serde(default)]
+    pub taxa: Vec<String>,
+    #[serde(default)]
+    pub taxa_direct: Vec<String>,
+    #[serde(default = "default_threads")]
+    pub threads: usize,
+    #[serde(default)]
+    pub min_query_length: u64,
+    #[serde(default)]
+    pub min_query_coverage: f64,
+    #[serde(default)]
+    pub min_mapq: u8,
+    #[serde(default)]
+    pub extract: bool,
+}
+
+fn default_threads() -> usize {
+    4
+}
+
+/// A `benchmark --workload` file: a list of independently configured runs,
+/// each executed in turn against its own `input`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct BenchmarkWorkload {
+    pub runs: Vec<BenchmarkRun>,
+}
+
+impl BenchmarkWorkload {
+    /// Loads a workload file (JSON).
+    pub fn from_json(path: &Path) -> Result<Self, ScrubbyError> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// One run's recorded outcome. `reads_removed`/`reads_extracted` are the
+/// depletion sensitivity - a first-class output alongside timing, so a
+/// config can be judged on aggressiveness as well as speed.
+#[derive(Serialize, Debug)]
+pub struct BenchmarkResult {
+    pub name: String,
+    pub reads_in: u64,
+    pub reads_out: u64,
+    pub reads_removed: u64,
+    pub reads_extracted: u64,
+    pub wall_clock_ms: u128,
+    /// Throughput, `reads_in` per second of `wall_clock_ms`. `0.0` for a failed
+    /// run or any run whose `wall_clock_ms` is `0`.
+    pub reads_per_sec: f64,
+    /// Best-effort peak resident set size in MB, `None` where the platform
+    /// does not expose it (only Linux's `/proc/self/status` is read).
+    /// Reports the whole process's peak since start, not this run in
+    /// isolation, since every run in a workload shares one process - run a
+    /// single-entry workload if an isolated figure is needed.
+    pub peak_memory_mb: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Aggregated `benchmark --json`/`--tsv` summary, one row per workload run, in execution order.
+#[derive(Serialize, Debug)]
+pub struct BenchmarkReport {
+    pub results: Vec<BenchmarkResult>,
+}
+
+impl BenchmarkReport {
+    pub fn write_json(&self, path: &Path) -> Result<(), ScrubbyError> {
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+    /// Writes the results table as TSV, one row per run.
+    pub fn write_tsv(&self, path: &Path) -> Result<(), ScrubbyError> {
+        let mut file = std::fs::File::create(path)?;
+        writeln!(file, "name\treads_in\treads_out\treads_removed\treads_extracted\twall_clock_ms\treads_per_sec\tpeak_memory_mb\terror")?;
+        for result in &self.results {
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{:.1}\t{}\t{}",
+                result.name,
+                result.reads_in,
+                result.reads_out,
+                result.reads_removed,
+                result.reads_extracted,
+                result.wall_clock_ms,
+                result.reads_per_sec,
+                result.peak_memory_mb.map(|mb| mb.to_string()).unwrap_or_default(),
+                result.error.as_deref().unwrap_or(""),
+            )?;
+        }
+        Ok(())
+    }
+    /// Appends one NDJSON line per run in this report to `path` (creating it
+    /// if missing), each stamped with the time it was written. Unlike
+    /// `write_json`/`write_tsv`, which are overwritten snapshots of a single
+    /// invocation, this file only grows, so runs from many workloads or many
+    /// commits can be concatenated and diffed later to catch regressions.
+    pub fn append_jsonl(&self, path: &Path) -> Result<(), ScrubbyError> {
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        for result in &self.results {
+            let mut record = serde_json::to_value(result)?;
+            if let Some(record) = record.as_object_mut() {
+                record.insert("timestamp".to_string(), serde_json::Value::String(chrono::Utc::now().to_rfc3339()));
+            }
+            writeln!(file, "{record}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every `BenchmarkRun` in `workload` in turn, writing per-run output
+/// and `--json` reports into `outdir`. Runs are sequential, not concurrent,
+/// so each run's wall-clock timing reflects its own resource use rather than
+/// contention with a neighbour; a run's own `threads` still parallelizes its
+/// tool invocation as usual. A failing run is recorded in its
+/// `BenchmarkResult.error` rather than aborting the remaining runs.
+pub fn run_benchmark(workload: &BenchmarkWorkload, outdir: &Path) -> Result<BenchmarkReport, ScrubbyError> {
+    std::fs::create_dir_all(outdir)?;
+
+    let results = workload.runs.iter().map(|run| run_single(run, outdir)).collect();
+
+    Ok(BenchmarkReport { results })
+}
+
+fn run_single(run: &BenchmarkRun, outdir: &Path) -> BenchmarkResult {
+    let output: Vec<PathBuf> = run.input.iter().enumerate()
+        .map(|(i, _)| outdir.join(format!("{}_{}.fastq.gz", run.name, i + 1)))
+        .collect();
+    let json = outdir.join(format!("{}.json", run.name));
+
+    let start = Instant::now();
+
+    let report = (|| -> Result<ScrubbyReport, ScrubbyError> {
+        ScrubbyBuilder::new(run.input.clone(), output)
+            .index(run.index.clone())
+            .aligner(run.aligner.clone())
+            .classifier(run.classifier.clone())
+            .preset(run.preset.clone())
+            .taxa(run.taxa.clone())
+            .taxa_direct(run.taxa_direct.clone())
+            .extract(run.extract)
+            .threads(run.threads)
+            .min_query_length(run.min_query_length)
+            .min_query_coverage(run.min_query_coverage)
+            .min_mapq(run.min_mapq)
+            .json(json.clone())
+            .build()?
+            .clean()?;
+
+        ScrubbyReport::from_json(&json)
+    })();
+
+    let wall_clock_ms = start.elapsed().as_millis();
+    let peak_memory_mb = read_peak_memory_mb();
+
+    match report {
+        Ok(report) => BenchmarkResult {
+            name: run.name.clone(),
+            reads_in: report.reads_in,
+            reads_out: report.reads_out,
+            reads_removed: report.reads_removed,
+            reads_extracted: report.reads_extracted,
+            wall_clock_ms,
+            reads_per_sec: reads_per_sec(report.reads_in, wall_clock_ms),
+            peak_memory_mb,
+            error: None,
+        },
+        Err(error) => BenchmarkResult {
+            name: run.name.clone(),
+            reads_in: 0,
+            reads_out: 0,
+            reads_removed: 0,
+            reads_extracted: 0,
+            wall_clock_ms,
+            reads_per_sec: 0.0,
+            peak_memory_mb,
+            error: Some(error.to_string()),
+        },
+    }
+}
+
+fn reads_per_sec(reads_in: u64, wall_clock_ms: u128) -> f64 {
+    if wall_clock_ms == 0 {
+        return 0.0;
+    }
+    reads_in as f64 / (wall_clock_ms as f64 / 1000.0)
+}
+
+/// Reads this process's peak resident set size from `/proc/self/status`
+/// (`VmHWM`), or `None` off Linux where no equivalent is read here.
+#[cfg(target_os = "linux")]
+fn read_peak_memory_mb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status.lines()
+        .find(|line| line.starts_with("VmHWM:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb / 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_peak_memory_mb() -> Option<u64> {
+    None
+}
@@ -0,0 +1,301 @@
+//! This module implements a Bracken-style one-pass redistribution of higher-rank
+//! Kraken2 read assignments down to the species level, so that depleting a
+//! selected species also captures reads Kraken2 only resolved to an ancestor
+//! node (genus, family, ...). Redistribution is proportional to a precomputed
+//! k-mer distribution database rather than Bracken's full EM, which the
+//! one-pass estimate approximates well enough for depletion purposes.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::classifier::{get_tax_level, parse_taxonomic_level, KrakenReportRecord, TaxonomicLevel};
+use crate::error::{ParseContext, ScrubbyError};
+
+/// A loaded Bracken k-mer distribution database: `P(node | species)`, indexed by
+/// species taxid then ancestor node taxid. The on-disk format is a tab-separated
+/// file with columns `species_taxid`, `node_taxid`, `probability`.
+#[derive(Debug, Clone, Default)]
+pub struct BrackenDatabase {
+    distributions: HashMap<String, HashMap<String, f64>>,
+}
+
+impl BrackenDatabase {
+    /// Loads a Bracken k-mer distribution file.
+    pub fn from_path(path: &PathBuf) -> Result<Self, ScrubbyError> {
+        let reader = crate::compression::open_reader(path)?;
+        let mut distributions: HashMap<String, HashMap<String, f64>> = HashMap::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.trim().split('\t').collect();
+            if fields.len() < 3 {
+                continue;
+            }
+            let species_taxid = fields[0].to_string();
+            let node_taxid = fields[1].to_string();
+            let probability: f64 = fields[2].parse().unwrap_or(0.0);
+
+            distributions.entry(species_taxid).or_default().insert(node_taxid, probability);
+        }
+
+        Ok(Self { distributions })
+    }
+
+    /// Returns `P(node | species)`, or `0.0` if unobserved in the database.
+    pub fn probability(&self, species_taxid: &str, node_taxid: &str) -> f64 {
+        self.distributions
+            .get(species_taxid)
+            .and_then(|nodes| nodes.get(node_taxid))
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+/// Redistributes reads directly assigned at ancestor nodes down to species,
+/// proportionally to `species_reads * P(node | species)`.
+///
+/// `node_reads` maps an ancestor node's taxid to its directly-assigned read count.
+/// `species_reads` maps each descendant species' taxid to its own directly-assigned
+/// read count, used as the prior for the proportional split. Returns, for each
+/// ancestor node, the estimated number of its reads attributable to each species.
+pub fn redistribute(
+    db: &BrackenDatabase,
+    node_reads: &HashMap<String, u64>,
+    species_reads: &HashMap<String, u64>,
+) -> HashMap<String, HashMap<String, f64>> {
+    let mut redistributed: HashMap<String, HashMap<String, f64>> = HashMap::new();
+
+    for (node_taxid, &reads) in node_reads {
+        if reads == 0 {
+            continue;
+        }
+
+        let weights: Vec<(&String, f64)> = species_reads
+            .iter()
+            .map(|(species_taxid, &prior)| (species_taxid, prior as f64 * db.probability(species_taxid, node_taxid)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+
+        let total_weight: f64 = weights.iter().map(|(_, w)| w).sum();
+        if total_weight <= 0.0 {
+            continue;
+        }
+
+        let node_estimates = redistributed.entry(node_taxid.clone()).or_default();
+        for (species_taxid, weight) in weights {
+            node_estimates.insert(species_taxid.clone(), reads as f64 * (weight / total_weight));
+        }
+    }
+
+    redistributed
+}
+
+/// Given the per-node, per-species redistribution and a set of selected species
+/// taxids, returns the fraction of each ancestor node's reads estimated to belong
+/// to a selected species.
+pub fn selected_fraction_per_node(
+    redistributed: &HashMap<String, HashMap<String, f64>>,
+    node_reads: &HashMap<String, u64>,
+    selected: &std::collections::HashSet<String>,
+) -> HashMap<String, f64> {
+    let mut fractions = HashMap::new();
+
+    for (node_taxid, species_estimates) in redistributed {
+        let reads = *node_reads.get(node_taxid).unwrap_or(&0) as f64;
+        if reads <= 0.0 {
+            continue;
+        }
+        let selected_reads: f64 = species_estimates
+            .iter()
+            .filter(|(species_taxid, _)| selected.contains(*species_taxid))
+            .map(|(_, &estimate)| estimate)
+            .sum();
+
+        if selected_reads > 0.0 {
+            fractions.insert(node_taxid.clone(), (selected_reads / reads).min(1.0));
+        }
+    }
+
+    fractions
+}
+
+/// One row of the `--bracken-report` abundance re-estimation table produced
+/// by [`estimate_abundance`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AbundanceRecord {
+    pub name: String,
+    pub taxid: String,
+    pub rank: String,
+    pub kraken_assigned_reads: u64,
+    pub added_reads: f64,
+    pub new_est_reads: f64,
+    pub fraction_total_reads: f64,
+}
+
+/// One taxon in the tree built from a Kraken-style report by [`estimate_abundance`].
+struct ReportNode {
+    tax_id: String,
+    tax_name: String,
+    rank: String,
+    level: TaxonomicLevel,
+    reads_direct: u64,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// Collects the node indices of `idx`'s descendants at `target_level`, not
+/// descending past the first one found along each path (its rolled-up
+/// `kraken_assigned` total already accounts for anything further below it).
+fn collect_target_descendants(nodes: &[ReportNode], idx: usize, target_level: TaxonomicLevel, out: &mut Vec<usize>) {
+    for &child in &nodes[idx].children {
+        if nodes[child].level == target_level {
+            out.push(child);
+        } else {
+            collect_target_descendants(nodes, child, target_level, out);
+        }
+    }
+}
+
+/// Re-estimates per-taxon abundance at `target_rank` from a Kraken-style
+/// `kraken_report`, without requiring a precomputed k-mer distribution
+/// database (unlike [`redistribute`]).
+///
+/// Builds the taxonomy tree implied by the report's rank ordering (mirroring
+/// the lineage-stack approach in [`crate::classifier::build_krona_entries`]),
+/// then applies Bracken's own two-step algorithm: taxa more specific than
+/// `target_rank` (e.g. species below a genus target) roll their directly-assigned
+/// reads up into their nearest `target_rank` ancestor's `kraken_assigned_reads`;
+/// taxa less specific than `target_rank` (genus and above) then distribute
+/// their own directly-assigned reads down to their `target_rank` descendants,
+/// in proportion to each descendant's current (direct + already-redistributed)
+/// read count. Ancestors are processed from the closest to `target_rank`
+/// outward to the root, so each `target_rank` taxon accumulates its direct
+/// reads plus its fractional share inherited from every ancestor above it.
+///
+/// Levels come from [`get_tax_level`], which gives Kraken2's numbered
+/// sub-rank rows (`S1`, `G1`, ...) the level of their base rank: a strain
+/// row under a species target is reported as a `target_rank` taxon of its
+/// own, and its reads are not rolled up into its parent species.
+pub fn estimate_abundance(kraken_report: &PathBuf, target_rank: &str) -> Result<Vec<AbundanceRecord>, ScrubbyError> {
+    let target_level = parse_taxonomic_level(target_rank);
+
+    let report = crate::compression::open_reader(kraken_report)?;
+
+    let mut nodes: Vec<ReportNode> = Vec::new();
+    let mut stack: Vec<(TaxonomicLevel, usize)> = Vec::new();
+    let mut total_reads: u64 = 0;
+
+    for (index, line) in report.lines().enumerate() {
+        let context = ParseContext::new(kraken_report.clone(), (index + 1) as u64);
+        let record = KrakenReportRecord::from_str(line?, &context)?;
+        let level = get_tax_level(&record);
+
+        total_reads += record.reads_direct;
+
+        while stack.last().map_or(false, |&(stack_level, _)| stack_level >= level) {
+            stack.pop();
+        }
+        let parent = stack.last().map(|&(_, idx)| idx);
+
+        nodes.push(ReportNode {
+            tax_id: record.tax_id,
+            tax_name: record.tax_name,
+            rank: record.tax_level,
+            level,
+            reads_direct: record.reads_direct,
+            parent,
+            children: Vec::new(),
+        });
+
+        let node_index = nodes.len() - 1;
+        if let Some(parent_index) = parent {
+            nodes[parent_index].children.push(node_index);
+        }
+        stack.push((level, node_index));
+    }
+
+    // Roll reads from taxa more specific than `target_level` up into their
+    // nearest `target_level` ancestor.
+    let mut kraken_assigned: HashMap<usize, u64> = HashMap::new();
+    for (index, node) in nodes.iter().enumerate() {
+        if node.level < target_level || node.reads_direct == 0 {
+            continue;
+        }
+        let mut ancestor = index;
+        while nodes[ancestor].level != target_level {
+            match nodes[ancestor].parent {
+                Some(parent) if nodes[parent].level >= target_level => ancestor = parent,
+                _ => break,
+            }
+        }
+        if nodes[ancestor].level == target_level {
+            *kraken_assigned.entry(ancestor).or_insert(0) += node.reads_direct;
+        }
+    }
+
+    let mut est_reads: HashMap<usize, f64> = kraken_assigned.iter().map(|(&idx, &reads)| (idx, reads as f64)).collect();
+    let mut added_reads: HashMap<usize, f64> = kraken_assigned.keys().map(|&idx| (idx, 0.0)).collect();
+
+    // Redistribute ancestors above `target_level`, closest ancestor first, so
+    // each step sees the already-redistributed totals from the step before it.
+    let mut internal: Vec<usize> = nodes.iter().enumerate()
+        .filter(|(_, node)| node.level < target_level && node.level >= TaxonomicLevel::Domain && node.reads_direct > 0)
+        .map(|(index, _)| index)
+        .collect();
+    internal.sort_by(|&a, &b| nodes[b].level.partial_cmp(&nodes[a].level).unwrap_or(std::cmp::Ordering::Equal));
+
+    for idx in internal {
+        let mut targets = Vec::new();
+        collect_target_descendants(&nodes, idx, target_level, &mut targets);
+        if targets.is_empty() {
+            continue;
+        }
+
+        let reads = nodes[idx].reads_direct as f64;
+        let weights: Vec<(usize, f64)> = targets.iter().map(|&t| (t, *est_reads.get(&t).unwrap_or(&0.0))).collect();
+        let total_weight: f64 = weights.iter().map(|(_, w)| w).sum();
+
+        for (target, weight) in weights {
+            let share = if total_weight > 0.0 { reads * (weight / total_weight) } else { reads / targets.len() as f64 };
+            *est_reads.entry(target).or_insert(0.0) += share;
+            *added_reads.entry(target).or_insert(0.0) += share;
+        }
+    }
+
+    let mut records: Vec<AbundanceRecord> = kraken_assigned.keys().map(|&idx| {
+        let node = &nodes[idx];
+        let new_est_reads = *est_reads.get(&idx).unwrap_or(&0.0);
+        AbundanceRecord {
+            name: node.tax_name.clone(),
+            taxid: node.tax_id.clone(),
+            rank: node.rank.clone(),
+            kraken_assigned_reads: *kraken_assigned.get(&idx).unwrap_or(&0),
+            added_reads: *added_reads.get(&idx).unwrap_or(&0.0),
+            new_est_reads,
+            fraction_total_reads: if total_reads > 0 { new_est_reads / total_reads as f64 } else { 0.0 },
+        }
+    }).collect();
+
+    records.sort_by(|a, b| b.new_est_reads.partial_cmp(&a.new_est_reads).unwrap_or(std::cmp::Ordering::Equal));
+
+    Ok(records)
+}
+
+/// Writes `records` to `output` in the classic Bracken report column order:
+/// `name`, `taxid`, `rank`, `kraken_assigned_reads`, `added_reads`,
+/// `new_est_reads`, `fraction_total_reads`.
+pub fn write_abundance_tsv(records: &[AbundanceRecord], output: &PathBuf) -> Result<(), ScrubbyError> {
+    let mut writer = std::fs::File::create(output)?;
+    writeln!(writer, "name\ttaxid\trank\tkraken_assigned_reads\tadded_reads\tnew_est_reads\tfraction_total_reads")?;
+    for record in records {
+        writeln!(
+            writer, "{}\t{}\t{}\t{}\t{:.0}\t{:.0}\t{:.5}",
+            record.name, record.taxid, record.rank, record.kraken_assigned_reads,
+            record.added_reads, record.new_est_reads, record.fraction_total_reads,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+//! Persists which stages of a single `clean` pipeline run have already
+//! completed, so a run interrupted partway through (a killed process, a
+//! crashed external tool) can be resumed with `--resume` instead of
+//! re-invoking the aligner/classifier from scratch. Scoped to one sample's
+//! pipeline stages rather than a sheet of samples, the way `batch.rs`'s
+//! `BatchQueue`/`--resume` is scoped to a sheet rather than a single stage.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::ScrubbyError;
+
+/// One stage's entry in the persisted checkpoint file: the hash of the
+/// configuration that produced it, so a changed `--aligner-args`/index/input
+/// invalidates the checkpoint rather than silently reusing stale output, and
+/// the cache file it wrote, which must still exist for the checkpoint to be honored.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckpointEntry {
+    pub hash: String,
+    pub cache_path: PathBuf,
+}
+
+/// Persisted stage checkpoint for a `clean` pipeline run, written to
+/// `workdir/scrubby.checkpoint.json` after every completed stage.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Checkpoint {
+    pub stages: BTreeMap<String, CheckpointEntry>,
+}
+
+impl Checkpoint {
+    /// Loads the checkpoint file from `workdir`, or an empty checkpoint if it
+    /// does not exist.
+    pub fn load(workdir: &Path) -> Result<Self, ScrubbyError> {
+        let path = Self::path(workdir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Returns the cached read IDs for `stage` if it is recorded complete
+    /// with the same `hash` and its cache file still exists, so output
+    /// deleted out from under the checkpoint is re-run rather than silently
+    /// treated as still valid.
+    pub fn cached_read_ids(&self, stage: &str, hash: &str) -> Option<std::collections::HashSet<String>> {
+        let entry = self.stages.get(stage)?;
+        if entry.hash != hash || !entry.cache_path.exists() {
+            return None;
+        }
+        let content = std::fs::read_to_string(&entry.cache_path).ok()?;
+        Some(content.lines().map(str::to_string).collect())
+    }
+
+    /// Writes `read_ids` to `workdir/scrubby.checkpoint.<stage>.ids`, records
+    /// `stage` complete with `hash`, and writes the checkpoint file
+    /// immediately, so a crash during a later stage still leaves this one resumable.
+    pub fn mark_complete(&mut self, workdir: &Path, stage: &str, hash: &str, read_ids: &std::collections::HashSet<String>) -> Result<(), ScrubbyError> {
+        let cache_path = workdir.join(format!("scrubby.checkpoint.{stage}.ids"));
+        std::fs::write(&cache_path, read_ids.iter().cloned().collect::<Vec<_>>().join("\n"))?;
+
+        self.stages.insert(stage.to_string(), CheckpointEntry { hash: hash.to_string(), cache_path });
+        self.write(workdir)
+    }
+
+    fn write(&self, workdir: &Path) -> Result<(), ScrubbyError> {
+        let mut file = std::fs::File::create(Self::path(workdir))?;
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    fn path(workdir: &Path) -> PathBuf {
+        workdir.join("scrubby.checkpoint.json")
+    }
+}
+
+/// Hashes `parts` (a stage's relevant config fields, stringified) into a hex
+/// digest, so a checkpoint entry is invalidated by any change to the
+/// settings that produced it.
+pub fn hash_parts(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update(b"\0");
+    }
+    format!("{:x}", hasher.finalize())
+}
@@ -3,14 +3,16 @@
 //! for handling taxonomic levels, counting reads, and extracting taxonomic identifiers.
 
 use anyhow::Result;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::PathBuf;
 
-use crate::error::ScrubbyError;
+use crate::error::{ScrubbyError, ParseContext};
+use crate::taxonomy::Taxonomy;
 
 /// Enumeration representing taxonomic levels.
 ///
@@ -58,6 +60,8 @@ impl fmt::Display for TaxonomicLevel {
 #[derive(Debug, Clone)]
 pub struct TaxonCounts {
     taxa: HashMap<String, HashMap<String, u64>>,
+    tax_ids: HashMap<String, String>,
+    tax_ranks: HashMap<String, String>,
 }
 
 impl TaxonCounts {
@@ -72,6 +76,8 @@ impl TaxonCounts {
     pub fn new() -> Self {
         TaxonCounts {
             taxa: HashMap::new(),
+            tax_ids: HashMap::new(),
+            tax_ranks: HashMap::new(),
         }
     }
 
@@ -99,6 +105,96 @@ impl TaxonCounts {
             })
             .or_insert(HashMap::from([(tax_name.clone(), tax_reads)]));
     }
+
+    /// Records the taxid and report rank for a taxon name, so they can be looked
+    /// up again when writing [`TaxonCounts::write_tsv`].
+    pub fn annotate(&mut self, tax_name: String, tax_id: String, tax_rank: String) {
+        self.tax_ids.insert(tax_name.clone(), tax_id);
+        self.tax_ranks.insert(tax_name, tax_rank);
+    }
+
+    /// Resolves the accumulated counts into one [`TaxonCountRecord`] per
+    /// parent/child pair, with `fraction` taken relative to the total
+    /// directly-assigned reads across every taxon. When `taxonomy` is given,
+    /// `tax_rank` and `tax_name` are resolved from the loaded NCBI taxonomy
+    /// graph, taking precedence over the report's own values; the report's
+    /// values are kept only for taxids the taxonomy does not know.
+    pub fn records(&self, taxonomy: Option<&Taxonomy>) -> Vec<TaxonCountRecord> {
+        let total_reads: u64 = self.taxa.values().flat_map(|subtaxa| subtaxa.values()).sum();
+
+        let mut records = Vec::new();
+        for (parent, subtaxa) in &self.taxa {
+            for (tax_name, reads_direct) in subtaxa {
+                let tax_id = self.tax_ids.get(tax_name).cloned().unwrap_or_default();
+
+                let tax_rank = self.tax_ranks.get(tax_name).cloned().unwrap_or_default();
+                let tax_rank = taxonomy
+                    .and_then(|t| t.rank(&tax_id))
+                    .map(str::to_string)
+                    .unwrap_or(tax_rank);
+
+                let tax_name = taxonomy
+                    .and_then(|t| t.name(&tax_id))
+                    .map(str::to_string)
+                    .unwrap_or_else(|| tax_name.clone());
+
+                let fraction = match total_reads {
+                    0 => 0f64,
+                    _ => *reads_direct as f64 / total_reads as f64,
+                };
+
+                records.push(TaxonCountRecord {
+                    tax_id,
+                    tax_name,
+                    tax_rank,
+                    parent: parent.clone(),
+                    reads_direct: *reads_direct,
+                    fraction,
+                });
+            }
+        }
+
+        records
+    }
+
+    /// Writes a machine-readable table of the taxa and directly-assigned read
+    /// counts accumulated via [`TaxonCounts::update`], with columns `tax_id`,
+    /// `tax_name`, `tax_rank`, `parent`, `reads_direct`, `fraction`.
+    pub fn write_tsv<W: std::io::Write>(&self, taxonomy: Option<&Taxonomy>, mut writer: W) -> Result<(), ScrubbyError> {
+        writeln!(writer, "tax_id\ttax_name\ttax_rank\tparent\treads_direct\tfraction")?;
+
+        for record in self.records(taxonomy) {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}\t{:.6}",
+                record.tax_id, record.tax_name, record.tax_rank, record.parent, record.reads_direct, record.fraction
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the same per-taxon records as [`TaxonCounts::write_tsv`] as a
+    /// pretty-printed JSON array, for pipelines that would rather parse
+    /// structured output than a TSV.
+    pub fn write_json<W: std::io::Write>(&self, taxonomy: Option<&Taxonomy>, mut writer: W) -> Result<(), ScrubbyError> {
+        let records = self.records(taxonomy);
+        writer.write_all(serde_json::to_string_pretty(&records)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// One row of [`TaxonCounts::records`]: a taxon's directly-assigned read
+/// count and its fraction of the total directly-assigned reads across every
+/// depleted taxon.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxonCountRecord {
+    pub tax_id: String,
+    pub tax_name: String,
+    pub tax_rank: String,
+    pub parent: String,
+    pub reads_direct: u64,
+    pub fraction: f64,
 }
 
 /// Parses the Kraken output report file to extract taxonomic identifiers.
@@ -116,18 +212,44 @@ impl TaxonCounts {
 ///
 /// * `Result<HashSet<String>, ScrubbyError>` - A set of extracted taxonomic identifiers.
 ///
+/// When `taxonomy` is given, a matched taxon's entire true subtree is pulled from
+/// the loaded NCBI taxonomy graph via [`Taxonomy::descendants`] rather than
+/// reconstructed from the report's rank ordering, so extraction is correct
+/// regardless of `no rank` clades, strain-level entries, or reports that don't
+/// preserve indentation.
+///
+/// `min_reads` and `min_fraction`, when set, suppress a matched taxon (and its
+/// sub-levels/subtree) whose cumulative `reads` or parsed `fraction` column falls
+/// below the threshold, so spurious low-confidence taxa don't trigger depletion.
+///
+/// When `taxon_report` is given, an audit table of every depleted taxon is
+/// written there, as JSON via [`TaxonCounts::write_json`] if the path ends in
+/// `.json`, otherwise as TSV via [`TaxonCounts::write_tsv`].
+///
+/// When `prune_rank` is given, any sub-level node strictly below that rank
+/// (e.g. a `Species` row when `prune_rank` is `Genus`) has its directly
+/// assigned reads rolled up into the nearest enclosing ancestor at or above
+/// `prune_rank` instead of being recorded, and depleted, under its own
+/// taxid - mirroring KrakMap's `pruningLevel`, so single-read species-level
+/// noise does not each mint its own entry in `taxids`/`TaxonCounts`.
+///
 /// # Example
 ///
 /// ```
-/// let taxids = get_taxids_from_report(&report_path, &vec!["Eukaryota".to_string()], &vec![]).unwrap();
+/// let taxids = get_taxids_from_report(&report_path, &vec!["Eukaryota".to_string()], &vec![], None, None, None, None, None).unwrap();
 /// ```
 pub fn get_taxids_from_report(
     kraken_report: &PathBuf,
     kraken_taxa: &[String],
     kraken_taxa_direct: &[String],
+    taxonomy: Option<&Taxonomy>,
+    min_reads: Option<u64>,
+    min_fraction: Option<f64>,
+    prune_rank: Option<TaxonomicLevel>,
+    taxon_report: Option<&PathBuf>,
 ) -> Result<HashSet<String>, ScrubbyError> {
 
-    let report = BufReader::new(File::open(kraken_report)?);
+    let report = crate::compression::open_reader(kraken_report)?;
 
     let kraken_taxa: Vec<String> = kraken_taxa.iter().map(|x| x.trim().to_string()).collect();
     let kraken_taxa_direct: Vec<String> = kraken_taxa_direct.iter().map(|x| x.trim().to_string()).collect();
@@ -137,21 +259,39 @@ pub fn get_taxids_from_report(
 
     let mut extract_taxlevel: TaxonomicLevel = TaxonomicLevel::None;
     let mut extract_parent: String = String::from("");
+    // Nearest-seen ancestor (tax_id, tax_name) at or above `prune_rank` within
+    // the currently open subtree, reset whenever that subtree closes.
+    let mut rollup_ancestor: Option<(String, String)> = None;
 
-    'report: for line in report.lines() {
-        let record: KrakenReportRecord = KrakenReportRecord::from_str(line?)?;
+    'report: for (index, line) in report.lines().enumerate() {
+        let context = ParseContext::new(kraken_report.clone(), (index + 1) as u64);
+        let record: KrakenReportRecord = KrakenReportRecord::from_str(line?, &context)?;
         let tax_level = get_tax_level(&record);
 
+        let meets_abundance_thresholds = min_reads.map_or(true, |min| record.reads >= min)
+            && min_fraction.map_or(true, |min| record.fraction.trim().parse::<f64>().map(|f| f >= min).unwrap_or(false));
+
         if kraken_taxa_direct.contains(&record.tax_name) || kraken_taxa_direct.contains(&record.tax_id) {
-            log::debug!(
-                "Detected direct taxon to deplete ({} : {} : {} : {})",
-                &tax_level.to_string(),
-                &record.tax_level,
-                &record.tax_id,
-                &record.tax_name
-            );
-            taxids.insert(record.tax_id.clone());
-            tax_counts.update(record.tax_name.clone(), record.tax_name.clone(), record.reads_direct);
+            if !meets_abundance_thresholds {
+                log::debug!(
+                    "Suppressing direct taxon below abundance threshold ({} reads, {} fraction: {})",
+                    record.reads, record.fraction, &record.tax_name
+                );
+            } else {
+                log::debug!(
+                    "Detected direct taxon to deplete ({} : {} : {} : {})",
+                    &tax_level.to_string(),
+                    &record.tax_level,
+                    &record.tax_id,
+                    &record.tax_name
+                );
+                taxids.insert(record.tax_id.clone());
+                if let Some(taxonomy) = taxonomy {
+                    taxids.extend(taxonomy.descendants(&record.tax_id));
+                }
+                tax_counts.update(record.tax_name.clone(), record.tax_name.clone(), record.reads_direct);
+                tax_counts.annotate(record.tax_name.clone(), record.tax_id.clone(), record.tax_level.clone());
+            }
         }
 
         if tax_level < TaxonomicLevel::Domain {
@@ -166,6 +306,13 @@ pub fn get_taxids_from_report(
         }
 
         if kraken_taxa.contains(&record.tax_name) || kraken_taxa.contains(&record.tax_id) {
+            if !meets_abundance_thresholds {
+                log::debug!(
+                    "Suppressing taxon level below abundance threshold ({} reads, {} fraction: {})",
+                    record.reads, record.fraction, &record.tax_name
+                );
+                continue 'report;
+            }
             log::debug!(
                 "Detected taxon level ({} : {} : {} : {})",
                 &tax_level.to_string(),
@@ -175,15 +322,25 @@ pub fn get_taxids_from_report(
             );
             extract_taxlevel = tax_level;
             extract_parent = record.tax_name.clone();
+            rollup_ancestor = prune_rank
+                .filter(|prune_rank| tax_level <= *prune_rank)
+                .map(|_| (record.tax_id.clone(), record.tax_name.clone()));
 
             log::debug!(
                 "Setting taxon level for parsing sub-levels to {} ({})",
                 extract_taxlevel.to_string(),
                 &record.tax_name
             );
+            if let Some(taxonomy) = taxonomy {
+                // The true subtree is pulled from the taxonomy graph as soon as the
+                // taxon is matched, so sub-level rows below are only needed for the
+                // per-taxon read counts logged via `tax_counts`, not for membership.
+                taxids.extend(taxonomy.descendants(&record.tax_id));
+            }
             if record.reads_direct > 0 {
-                taxids.insert(record.tax_id);
                 tax_counts.update(record.tax_name.clone(), record.tax_name.clone(), record.reads_direct);
+                tax_counts.annotate(record.tax_name.clone(), record.tax_id.clone(), record.tax_level.clone());
+                taxids.insert(record.tax_id);
             }
         } else {
             if extract_taxlevel == TaxonomicLevel::None {
@@ -206,7 +363,14 @@ pub fn get_taxids_from_report(
                     &record.tax_name
                 );
                 extract_taxlevel = TaxonomicLevel::None;
+                rollup_ancestor = None;
             } else {
+                if let Some(prune_rank) = prune_rank {
+                    if tax_level <= prune_rank {
+                        rollup_ancestor = Some((record.tax_id.clone(), record.tax_name.clone()));
+                    }
+                }
+
                 if record.reads_direct > 0 {
                     log::debug!(
                         "Detected taxon sub-level with reads ({} : {} : {} : {})",
@@ -215,11 +379,16 @@ pub fn get_taxids_from_report(
                         &record.tax_id,
                         &record.tax_name
                     );
-                    taxids.insert(record.tax_id);
+                    let (tax_id, tax_name) = match (prune_rank, &rollup_ancestor) {
+                        (Some(prune_rank), Some(ancestor)) if tax_level > prune_rank => ancestor.clone(),
+                        _ => (record.tax_id.clone(), record.tax_name.clone()),
+                    };
                     match extract_parent.as_str() {
-                        "" => return Err(ScrubbyError::KrakenReportTaxonParent),
-                        _ => tax_counts.update(record.tax_name.clone(), extract_parent.clone(), record.reads_direct),
+                        "" => return Err(ScrubbyError::KrakenReportTaxonParent(context.clone())),
+                        _ => tax_counts.update(tax_name.clone(), extract_parent.clone(), record.reads_direct),
                     }
+                    tax_counts.annotate(tax_name, tax_id.clone(), record.tax_level.clone());
+                    taxids.insert(tax_id);
                 }
             }
         }
@@ -248,9 +417,360 @@ pub fn get_taxids_from_report(
     log::debug!("{} directly assigned reads collected from report", reads);
     log::debug!("{}", "=".repeat(46 + num_reads_chars));
 
+    if let Some(taxon_report) = taxon_report {
+        match taxon_report.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => tax_counts.write_json(taxonomy, File::create(taxon_report)?)?,
+            _ => tax_counts.write_tsv(taxonomy, File::create(taxon_report)?)?,
+        }
+    }
+
+    Ok(taxids)
+}
+
+/// Parses a `KrakenUniq` report to extract taxonomic identifiers, suppressing taxa whose
+/// estimated number of distinct k-mers (the HyperLogLog-derived `kmers` column) falls below
+/// `min_unique_kmers` even if their name/taxid was otherwise selected for depletion.
+///
+/// This mirrors the tree-walk in [`get_taxids_from_report`] but reads the `KrakenUniq`
+/// report column layout (`%, reads, taxReads, kmers, dup, cov, taxID, rank, taxName`),
+/// which differs from the plain `Kraken2` report.
+///
+/// # Arguments
+///
+/// * `krakenuniq_report` - The path to the `KrakenUniq` taxonomic report file.
+/// * `krakenuniq_taxa` - A list of taxa names or identifiers to extract.
+/// * `krakenuniq_taxa_direct` - A list of taxa names or identifiers to extract directly.
+/// * `min_unique_kmers` - The minimum number of distinct k-mers required for a taxon to be retained.
+///
+/// # Returns
+///
+/// * `Result<HashSet<String>, ScrubbyError>` - A set of extracted taxonomic identifiers.
+pub fn get_taxids_from_krakenuniq_report(
+    krakenuniq_report: &PathBuf,
+    krakenuniq_taxa: &[String],
+    krakenuniq_taxa_direct: &[String],
+    min_unique_kmers: u64,
+) -> Result<HashSet<String>, ScrubbyError> {
+
+    let report = crate::compression::open_reader(krakenuniq_report)?;
+
+    let krakenuniq_taxa: Vec<String> = krakenuniq_taxa.iter().map(|x| x.trim().to_string()).collect();
+    let krakenuniq_taxa_direct: Vec<String> = krakenuniq_taxa_direct.iter().map(|x| x.trim().to_string()).collect();
+
+    let mut taxids: HashSet<String> = HashSet::new();
+
+    let mut extract_taxlevel: TaxonomicLevel = TaxonomicLevel::None;
+
+    'report: for (index, line) in report.lines().enumerate() {
+        let context = ParseContext::new(krakenuniq_report.clone(), (index + 1) as u64);
+        let record: KrakenUniqReportRecord = KrakenUniqReportRecord::from_str(line?, &context)?;
+        let tax_level = get_krakenuniq_tax_level(&record);
+        let sufficient_kmers = record.kmers >= min_unique_kmers;
+
+        if krakenuniq_taxa_direct.contains(&record.tax_name) || krakenuniq_taxa_direct.contains(&record.tax_id) {
+            if sufficient_kmers {
+                taxids.insert(record.tax_id.clone());
+            } else {
+                log::debug!(
+                    "Suppressing direct taxon below unique k-mer threshold ({} < {}: {})",
+                    record.kmers, min_unique_kmers, &record.tax_name
+                );
+            }
+        }
+
+        if tax_level < TaxonomicLevel::Domain {
+            continue 'report;
+        }
+
+        if krakenuniq_taxa.contains(&record.tax_name) || krakenuniq_taxa.contains(&record.tax_id) {
+            extract_taxlevel = tax_level;
+            if record.reads_direct > 0 && sufficient_kmers {
+                taxids.insert(record.tax_id);
+            }
+        } else {
+            if extract_taxlevel == TaxonomicLevel::None {
+                continue 'report;
+            }
+            if (tax_level <= extract_taxlevel) && (record.tax_level.len() == 1) {
+                extract_taxlevel = TaxonomicLevel::None;
+            } else if record.reads_direct > 0 && sufficient_kmers {
+                taxids.insert(record.tax_id);
+            }
+        }
+    }
+
+    log::debug!("{} taxonomic levels with sufficient unique k-mer support detected", taxids.len());
+
     Ok(taxids)
 }
 
+/// Parses a rank name (e.g. `"genus"`, `"family"`) into a `TaxonomicLevel` for comparison
+/// against report records, used to resolve the `--bracken-rank` cutoff.
+pub fn parse_taxonomic_level(rank: &str) -> TaxonomicLevel {
+    get_tax_level(&KrakenReportRecord {
+        fraction: String::new(),
+        reads: 0,
+        reads_direct: 0,
+        tax_level: rank.to_string(),
+        tax_id: String::new(),
+        tax_name: String::new(),
+    })
+}
+
+/// Walks a Kraken2 report to collect directly-assigned read counts for species-level
+/// taxa and for ancestor taxa at or above `bracken_rank`, the two inputs required by
+/// [`crate::bracken::redistribute`] to estimate how reads assigned above the species
+/// level should be split back down to species.
+///
+/// # Returns
+///
+/// * `(node_reads, species_reads)` - directly-assigned read counts keyed by taxid, for
+///   ancestor nodes and for species respectively.
+pub fn get_bracken_node_counts(
+    kraken_report: &PathBuf,
+    bracken_rank: TaxonomicLevel,
+) -> Result<(HashMap<String, u64>, HashMap<String, u64>), ScrubbyError> {
+    let report = crate::compression::open_reader(kraken_report)?;
+
+    let mut node_reads = HashMap::new();
+    let mut species_reads = HashMap::new();
+
+    for (index, line) in report.lines().enumerate() {
+        let context = ParseContext::new(kraken_report.clone(), (index + 1) as u64);
+        let record = KrakenReportRecord::from_str(line?, &context)?;
+        let tax_level = get_tax_level(&record);
+
+        if record.reads_direct == 0 {
+            continue;
+        }
+
+        if tax_level == TaxonomicLevel::Species {
+            species_reads.insert(record.tax_id.clone(), record.reads_direct);
+        } else if tax_level <= bracken_rank && tax_level >= TaxonomicLevel::Domain {
+            node_reads.insert(record.tax_id.clone(), record.reads_direct);
+        }
+    }
+
+    Ok((node_reads, species_reads))
+}
+
+/// Extracts read identifiers for directly selected taxa, plus a deterministic share of
+/// the reads assigned to each ancestor node in `node_fractions` - the fraction of that
+/// node's reads Bracken-style redistribution estimates belong to a selected species.
+///
+/// Reads are taken in file order up to the running fraction of each node seen so far,
+/// which approximates a proportional sample without requiring the read file to be
+/// buffered in memory or revisited.
+///
+/// # Arguments
+///
+/// * `taxids` - A set of directly selected taxonomic identifiers.
+/// * `node_fractions` - The estimated fraction of each ancestor node's reads to include.
+/// * `kraken_reads` - The path to the Kraken reads file.
+pub fn get_taxid_reads_kraken_bracken(
+    taxids: HashSet<String>,
+    node_fractions: &HashMap<String, f64>,
+    kraken_reads: &PathBuf,
+) -> Result<HashSet<String>, ScrubbyError> {
+    let mut reads: HashSet<String> = HashSet::new();
+
+    if !kraken_reads.exists() {
+        return Ok(reads);
+    }
+
+    let mut node_seen: HashMap<String, u64> = HashMap::new();
+    let mut node_taken: HashMap<String, u64> = HashMap::new();
+
+    let file = crate::compression::open_reader(kraken_reads)?;
+    for line in file.lines() {
+        let record: KrakenReadRecord = KrakenReadRecord::from_str(line?)?;
+
+        if taxids.contains(&record.tax_id) {
+            reads.insert(record.read_id.clone());
+            continue;
+        }
+
+        if let Some(&fraction) = node_fractions.get(&record.tax_id) {
+            let seen = *node_seen.entry(record.tax_id.clone()).and_modify(|s| *s += 1).or_insert(1);
+            let taken = node_taken.entry(record.tax_id.clone()).or_insert(0);
+            if (*taken as f64) < (seen as f64) * fraction {
+                *taken += 1;
+                reads.insert(record.read_id.clone());
+            }
+        }
+    }
+
+    log::debug!("{} matching classified reads were detected (including Bracken-redistributed reads)", reads.len());
+    Ok(reads)
+}
+
+/// Walks a Kraken2 report to build the root-to-taxon lineage and directly-assigned
+/// read count for every taxon in `taxids`, the input a Krona text report is built from.
+///
+/// Lineage depth is tracked with a stack ordered by `TaxonomicLevel` rather than the
+/// report's leading-whitespace indentation, which [`KrakenReportRecord::from_str`]
+/// discards when trimming the taxon name.
+pub fn build_krona_entries(
+    kraken_report: &PathBuf,
+    taxids: &HashSet<String>,
+) -> Result<Vec<(u64, Vec<String>)>, ScrubbyError> {
+    let report = crate::compression::open_reader(kraken_report)?;
+
+    let mut lineage_stack: Vec<(TaxonomicLevel, String)> = Vec::new();
+    let mut entries = Vec::new();
+
+    for (index, line) in report.lines().enumerate() {
+        let context = ParseContext::new(kraken_report.clone(), (index + 1) as u64);
+        let record = KrakenReportRecord::from_str(line?, &context)?;
+        let tax_level = get_tax_level(&record);
+
+        if tax_level < TaxonomicLevel::Domain {
+            continue;
+        }
+
+        while lineage_stack.last().map_or(false, |(level, _)| *level >= tax_level) {
+            lineage_stack.pop();
+        }
+        lineage_stack.push((tax_level, record.tax_name.clone()));
+
+        if taxids.contains(&record.tax_id) && record.reads_direct > 0 {
+            let lineage = lineage_stack.iter().map(|(_, name)| name.clone()).collect();
+            entries.push((record.reads_direct, lineage));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Utility function to extract the taxonomic level from a `KrakenUniq` report record.
+pub fn get_krakenuniq_tax_level(record: &KrakenUniqReportRecord) -> TaxonomicLevel {
+    get_tax_level(record)
+}
+
+/// A single read's classification, abstracting over the per-classifier output
+/// formats (Kraken2, Metabuli, Centrifuge, Kaiju, ...) so the taxid-matching
+/// loop in [`get_taxid_reads`] only has to be written once.
+pub trait ClassifiedRead: Sized {
+    /// Parses one line of the classifier's read-level output file.
+    fn from_line(line: String) -> Result<Self, ScrubbyError>;
+    /// The classified read's identifier.
+    fn read_id(&self) -> &str;
+    /// The taxonomic identifier the read was assigned to.
+    fn tax_id(&self) -> &str;
+}
+
+impl ClassifiedRead for KrakenReadRecord {
+    fn from_line(line: String) -> Result<Self, ScrubbyError> {
+        Self::from_str(line)
+    }
+    fn read_id(&self) -> &str {
+        &self.read_id
+    }
+    fn tax_id(&self) -> &str {
+        &self.tax_id
+    }
+}
+
+impl ClassifiedRead for MetabuliReadRecord {
+    fn from_line(line: String) -> Result<Self, ScrubbyError> {
+        Self::from_str(line)
+    }
+    fn read_id(&self) -> &str {
+        &self.read_id
+    }
+    fn tax_id(&self) -> &str {
+        &self.tax_id
+    }
+}
+
+impl ClassifiedRead for CentrifugeReadRecord {
+    fn from_line(line: String) -> Result<Self, ScrubbyError> {
+        Self::from_str(line)
+    }
+    fn read_id(&self) -> &str {
+        &self.read_id
+    }
+    fn tax_id(&self) -> &str {
+        &self.tax_id
+    }
+}
+
+impl ClassifiedRead for KaijuReadRecord {
+    fn from_line(line: String) -> Result<Self, ScrubbyError> {
+        Self::from_str(line)
+    }
+    fn read_id(&self) -> &str {
+        &self.read_id
+    }
+    fn tax_id(&self) -> &str {
+        &self.tax_id
+    }
+}
+
+/// Classifier output formats [`get_taxid_reads`] knows how to parse, for
+/// callers that select a format at runtime rather than at compile time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ClassifierOutputFormat {
+    Kraken2,
+    Metabuli,
+    Centrifuge,
+    Kaiju,
+}
+
+/// Extracts read identifiers for `taxids` from a classifier read-level output file,
+/// dispatching to the `ClassifiedRead` parser for `format`.
+pub fn get_taxid_reads_for_format(
+    format: ClassifierOutputFormat,
+    taxids: &HashSet<String>,
+    reads: &PathBuf,
+) -> Result<HashSet<String>, ScrubbyError> {
+    match format {
+        ClassifierOutputFormat::Kraken2 => get_taxid_reads::<KrakenReadRecord>(taxids, reads),
+        ClassifierOutputFormat::Metabuli => get_taxid_reads::<MetabuliReadRecord>(taxids, reads),
+        ClassifierOutputFormat::Centrifuge => get_taxid_reads::<CentrifugeReadRecord>(taxids, reads),
+        ClassifierOutputFormat::Kaiju => get_taxid_reads::<KaijuReadRecord>(taxids, reads),
+    }
+}
+
+/// Extracts read identifiers for given taxonomic identifiers from a classifier
+/// read-level output file, generic over the record format via [`ClassifiedRead`].
+///
+/// # Arguments
+///
+/// * `taxids` - A set of taxonomic identifiers.
+/// * `reads` - The path to the classifier's read-level output file.
+///
+/// # Returns
+///
+/// * `Result<HashSet<String>, ScrubbyError>` - A set of read identifiers matching the given taxonomic identifiers.
+///
+/// # Example
+///
+/// ```
+/// let read_ids = get_taxid_reads::<KrakenReadRecord>(&taxids, &reads_path).unwrap();
+/// ```
+pub fn get_taxid_reads<R: ClassifiedRead>(
+    taxids: &HashSet<String>,
+    reads: &PathBuf,
+) -> Result<HashSet<String>, ScrubbyError> {
+    let mut read_ids: HashSet<String> = HashSet::new();
+
+    if !reads.exists() {
+        return Ok(read_ids);
+    }
+
+    let file = crate::compression::open_reader(reads)?;
+    for line in file.lines() {
+        let record = R::from_line(line?)?;
+        if taxids.contains(record.tax_id()) {
+            read_ids.insert(record.read_id().to_string());
+        }
+    }
+
+    log::debug!("{} matching classified reads were detected", read_ids.len());
+    Ok(read_ids)
+}
+
 /// Extracts read identifiers for given taxonomic identifiers from a Kraken reads file.
 ///
 /// # Arguments
@@ -271,30 +791,121 @@ pub fn get_taxid_reads_kraken(
     taxids: HashSet<String>,
     kraken_reads: &PathBuf,
 ) -> Result<HashSet<String>, ScrubbyError> {
-    let mut reads: HashSet<String> = HashSet::new();
+    get_taxid_reads::<KrakenReadRecord>(&taxids, kraken_reads)
+}
+
+/// Counts directly-assigned reads per taxid in a Kraken2/KrakenUniq reads file, restricted to `taxids`.
+///
+/// # Example
+///
+/// ```
+/// let counts = get_taxid_counts_kraken(&taxids, &reads_path).unwrap();
+/// ```
+pub fn get_taxid_counts_kraken(
+    taxids: &HashSet<String>,
+    kraken_reads: &PathBuf,
+) -> Result<HashMap<String, u64>, ScrubbyError> {
+    let mut counts: HashMap<String, u64> = HashMap::new();
 
     if !kraken_reads.exists() {
-        return Ok(reads);
+        return Ok(counts);
     }
 
-    let file = BufReader::new(File::open(&kraken_reads)?);
+    let file = crate::compression::open_reader(kraken_reads)?;
     for line in file.lines() {
         let record: KrakenReadRecord = KrakenReadRecord::from_str(line?)?;
         if taxids.contains(&record.tax_id) {
-            reads.insert(record.read_id.clone());
+            *counts.entry(record.tax_id).or_insert(0) += 1;
         }
     }
 
-    log::debug!("{} matching classified reads were detected", reads.len());
-    Ok(reads)
+    Ok(counts)
+}
+
+/// Resolves Centrifuge's potentially multi-row-per-read output (a read can be
+/// reported against several references, each on its own row) to a single best
+/// taxon per read, keeping the highest-`score` row seen for each `read_id`.
+/// This is the single decision later matched against `--taxa`/`--taxa-direct`.
+fn best_centrifuge_assignment(
+    centrifuge_reads: &PathBuf,
+) -> Result<HashMap<String, CentrifugeReadRecord>, ScrubbyError> {
+    let mut best: HashMap<String, CentrifugeReadRecord> = HashMap::new();
+
+    if !centrifuge_reads.exists() {
+        return Ok(best);
+    }
+
+    let file = crate::compression::open_reader(centrifuge_reads)?;
+    for line in file.lines() {
+        let record = CentrifugeReadRecord::from_str(line?)?;
+        let score: i64 = record.score.parse().unwrap_or(0);
+        let replace = match best.get(&record.read_id) {
+            Some(existing) => score > existing.score.parse().unwrap_or(0),
+            None => true,
+        };
+        if replace {
+            best.insert(record.read_id.clone(), record);
+        }
+    }
+
+    Ok(best)
+}
+
+/// Extracts read identifiers for given taxonomic identifiers from a Centrifuge
+/// reads file, resolving each read's best-scoring hit to a single taxon first.
+///
+/// # Example
+///
+/// ```
+/// let read_ids = get_taxid_reads_centrifuge(taxids, &reads_path).unwrap();
+/// ```
+pub fn get_taxid_reads_centrifuge(
+    taxids: HashSet<String>,
+    centrifuge_reads: &PathBuf,
+) -> Result<HashSet<String>, ScrubbyError> {
+    let assignments = best_centrifuge_assignment(centrifuge_reads)?;
+    Ok(assignments
+        .into_values()
+        .filter(|record| taxids.contains(&record.tax_id))
+        .map(|record| record.read_id)
+        .collect())
+}
+
+/// Counts best-scoring reads per taxid in a Centrifuge reads file, restricted to `taxids`.
+///
+/// # Example
+///
+/// ```
+/// let counts = get_taxid_counts_centrifuge(&taxids, &reads_path).unwrap();
+/// ```
+pub fn get_taxid_counts_centrifuge(
+    taxids: &HashSet<String>,
+    centrifuge_reads: &PathBuf,
+) -> Result<HashMap<String, u64>, ScrubbyError> {
+    let assignments = best_centrifuge_assignment(centrifuge_reads)?;
+    let mut counts: HashMap<String, u64> = HashMap::new();
+    for record in assignments.values() {
+        if taxids.contains(&record.tax_id) {
+            *counts.entry(record.tax_id.clone()).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
 }
 
-/// Extracts read identifiers for given taxonomic identifiers from a Metabuli reads file.
+/// Extracts read identifiers for given taxonomic identifiers from a Metabuli reads file,
+/// additionally requiring each read's `dna_score` to be at least `min_score`.
+///
+/// Unlike the generic [`get_taxid_reads`] used for the other classifiers,
+/// this does not go through the [`ClassifiedRead`] trait, since `dna_score`
+/// is specific to Metabuli's output format (mirroring how `min_mapq` is
+/// threaded through the alignment path rather than being part of a
+/// cross-format trait).
 ///
 /// # Arguments
 ///
 /// * `taxids` - A set of taxonomic identifiers.
 /// * `metabuli_reads` - The path to the Metabuli reads file.
+/// * `min_score` - Minimum `dna_score` a read must have to be included; `0.0` disables the filter.
 ///
 /// # Returns
 ///
@@ -303,28 +914,86 @@ pub fn get_taxid_reads_kraken(
 /// # Example
 ///
 /// ```
-/// let read_ids = get_taxid_reads_metabuli(taxids, &reads_path).unwrap();
+/// let read_ids = get_taxid_reads_metabuli(taxids, &reads_path, 0.0).unwrap();
 /// ```
 pub fn get_taxid_reads_metabuli(
     taxids: HashSet<String>,
     metabuli_reads: &PathBuf,
+    min_score: f64,
 ) -> Result<HashSet<String>, ScrubbyError> {
-    let mut reads: HashSet<String> = HashSet::new();
+    let mut read_ids = HashSet::new();
 
     if !metabuli_reads.exists() {
-        return Ok(reads);
+        return Ok(read_ids);
+    }
+
+    let file = crate::compression::open_reader(metabuli_reads)?;
+    for line in file.lines() {
+        let record = MetabuliReadRecord::from_str(line?)?;
+        if !taxids.contains(&record.tax_id) {
+            continue;
+        }
+        if record.dna_score.trim().parse::<f64>().unwrap_or(0.0) < min_score {
+            continue;
+        }
+        read_ids.insert(record.read_id);
+    }
+
+    log::debug!("{} matching classified reads were detected", read_ids.len());
+    Ok(read_ids)
+}
+
+/// Counts directly-assigned reads per taxid in a Metabuli classifications file, restricted to `taxids`.
+///
+/// # Example
+///
+/// ```
+/// let counts = get_taxid_counts_metabuli(&taxids, &reads_path).unwrap();
+/// ```
+pub fn get_taxid_counts_metabuli(
+    taxids: &HashSet<String>,
+    metabuli_reads: &PathBuf,
+) -> Result<HashMap<String, u64>, ScrubbyError> {
+    let mut counts: HashMap<String, u64> = HashMap::new();
+
+    if !metabuli_reads.exists() {
+        return Ok(counts);
     }
 
-    let file = BufReader::new(File::open(&metabuli_reads)?);
+    let file = crate::compression::open_reader(metabuli_reads)?;
     for line in file.lines() {
         let record: MetabuliReadRecord = MetabuliReadRecord::from_str(line?)?;
         if taxids.contains(&record.tax_id) {
-            reads.insert(record.read_id.clone());
+            *counts.entry(record.tax_id).or_insert(0) += 1;
         }
     }
 
-    log::debug!("{} matching classified reads were detected", reads.len());
-    Ok(reads)
+    Ok(counts)
+}
+
+/// A parsed hierarchical report row, abstracting over each classifier's report
+/// column layout so [`get_tax_level`] only has to map rank strings into
+/// [`TaxonomicLevel`] once rather than being re-derived per tool, the way
+/// [`ClassifiedRead`] already does for per-read output. Centrifuge has no
+/// hierarchical report of its own in this crate - its abundance is read
+/// straight from the per-read output via [`ClassifiedRead`] - so it has no
+/// implementation here.
+pub trait ClassifierReportRecord {
+    /// The report row's taxonomic rank column, in whatever vocabulary the
+    /// tool uses (a single-letter Kraken2-style code or a spelled-out rank name).
+    fn tax_level(&self) -> &str;
+}
+
+impl ClassifierReportRecord for KrakenReportRecord {
+    fn tax_level(&self) -> &str {
+        &self.tax_level
+    }
+}
+
+impl ClassifierReportRecord for KrakenUniqReportRecord {
+    fn tax_level(&self) -> &str {
+        &self.tax_level
+    }
 }
 
 /// Utility function to extract the taxonomic level from a Kraken report record.
@@ -342,8 +1011,8 @@ pub fn get_taxid_reads_metabuli(
 /// ```
 /// let tax_level = get_tax_level(&record);
 /// ```
-pub fn get_tax_level(record: &KrakenReportRecord) -> TaxonomicLevel {
-    let tax_level_str = &record.tax_level;
+pub fn get_tax_level<R: ClassifierReportRecord>(record: &R) -> TaxonomicLevel {
+    let tax_level_str = record.tax_level();
 
     if tax_level_str.starts_with('U') {
         TaxonomicLevel::Unclassified
@@ -436,6 +1105,7 @@ impl KrakenReportRecord {
     /// # Arguments
     ///
     /// * `report_line` - A string containing the tab-separated fields of a Kraken report record.
+    /// * `context` - The source file and record number, attached to any parse error raised.
     ///
     /// # Returns
     ///
@@ -444,19 +1114,20 @@ impl KrakenReportRecord {
     /// # Example
     ///
     /// ```
-    /// let record = KrakenReportRecord::from_str("0.05\t100\t50\tS\t12345\ttaxon_name".to_string()).unwrap();
+    /// let context = ParseContext::new(PathBuf::from("taxa_report.tsv"), 1);
+    /// let record = KrakenReportRecord::from_str("0.05\t100\t50\tS\t12345\ttaxon_name".to_string(), &context).unwrap();
     /// ```
-    pub fn from_str(report_line: String) -> Result<Self, ScrubbyError> {
+    pub fn from_str(report_line: String, context: &ParseContext) -> Result<Self, ScrubbyError> {
         let fields: Vec<&str> = report_line.split('\t').collect();
 
         let record = Self {
             fraction: fields[0].to_string(),
             reads: fields[1]
                 .parse::<u64>()
-                .map_err(|_| ScrubbyError::KrakenReportReadFieldConversion)?,
+                .map_err(|_| ScrubbyError::KrakenReportReadFieldConversion(context.clone()))?,
             reads_direct: fields[2]
                 .parse::<u64>()
-                .map_err(|_| ScrubbyError::KrakenReportDirectReadFieldConversion)?,
+                .map_err(|_| ScrubbyError::KrakenReportDirectReadFieldConversion(context.clone()))?,
             tax_level: fields[3].trim().to_string(),
             tax_id: fields[4].trim().to_string(),
             tax_name: fields[5].trim().to_string(),
@@ -466,6 +1137,65 @@ impl KrakenReportRecord {
     }
 }
 
+/// Structure representing a `KrakenUniq` report record.
+///
+/// `KrakenUniq` extends the Kraken2-style report with HyperLogLog-estimated
+/// unique k-mer counts (`kmers`), their duplication factor (`dup`) and genome
+/// coverage (`cov`), inserted ahead of the taxon rank/identifier/name columns.
+#[derive(Debug, Clone)]
+pub struct KrakenUniqReportRecord {
+    pub fraction: String,
+    pub reads: u64,
+    pub reads_direct: u64,
+    pub kmers: u64,
+    pub dup: String,
+    pub cov: String,
+    pub tax_id: String,
+    pub tax_level: String,
+    pub tax_name: String,
+}
+
+impl KrakenUniqReportRecord {
+    /// Creates a `KrakenUniqReportRecord` instance from a tab-separated string.
+    ///
+    /// # Arguments
+    ///
+    /// * `report_line` - A string containing the tab-separated fields of a `KrakenUniq` report record.
+    /// * `context` - The source file and record number, attached to any parse error raised.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<KrakenUniqReportRecord, ScrubbyError>` - The created `KrakenUniqReportRecord` instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = ParseContext::new(PathBuf::from("taxa_report.tsv"), 1);
+    /// let record = KrakenUniqReportRecord::from_str("0.05\t100\t50\t42\t1.19\t0.01\t12345\tS\ttaxon_name".to_string(), &context).unwrap();
+    /// ```
+    pub fn from_str(report_line: String, context: &ParseContext) -> Result<Self, ScrubbyError> {
+        let fields: Vec<&str> = report_line.split('\t').collect();
+
+        let record = Self {
+            fraction: fields[0].to_string(),
+            reads: fields[1]
+                .parse::<u64>()
+                .map_err(|_| ScrubbyError::KrakenReportReadFieldConversion(context.clone()))?,
+            reads_direct: fields[2]
+                .parse::<u64>()
+                .map_err(|_| ScrubbyError::KrakenReportDirectReadFieldConversion(context.clone()))?,
+            kmers: fields[3].trim().parse::<u64>().unwrap_or(0),
+            dup: fields[4].trim().to_string(),
+            cov: fields[5].trim().to_string(),
+            tax_id: fields[6].trim().to_string(),
+            tax_level: fields[7].trim().to_string(),
+            tax_name: fields[8].trim().to_string(),
+        };
+
+        Ok(record)
+    }
+}
+
 /// Structure representing a Metabuli read classification record.
 #[derive(Debug, Clone)]
 pub struct MetabuliReadRecord {
@@ -516,3 +1246,80 @@ impl MetabuliReadRecord {
         Ok(record)
     }
 }
+
+/// Structure representing a Centrifuge read classification record.
+///
+/// Centrifuge's per-read output is tab-separated `readID`, `seqID`, `taxID`,
+/// `score`, `2ndBestScore`, `hitLength`, `queryLength`, `numMatches`, with one
+/// row per read-to-reference alignment - a read with multiple equally-good
+/// hits appears on multiple rows sharing the same `readID`.
+#[derive(Debug, Clone)]
+pub struct CentrifugeReadRecord {
+    pub read_id: String,
+    pub seq_id: String,
+    pub tax_id: String,
+    pub score: String,
+    pub second_best_score: String,
+    pub hit_length: String,
+    pub query_length: String,
+    pub num_matches: String,
+}
+
+impl CentrifugeReadRecord {
+    /// Creates a `CentrifugeReadRecord` instance from a tab-separated string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let record = CentrifugeReadRecord::from_str("read1\tNC_000001.1\t12345\t100\t80\t50\t150\t1".to_string()).unwrap();
+    /// ```
+    pub fn from_str(centrifuge_line: String) -> Result<Self, ScrubbyError> {
+        let fields: Vec<&str> = centrifuge_line.split('\t').collect();
+
+        let record = Self {
+            read_id: fields[0].trim().to_string(),
+            seq_id: fields[1].trim().to_string(),
+            tax_id: fields[2].trim().to_string(),
+            score: fields[3].trim().to_string(),
+            second_best_score: fields[4].trim().to_string(),
+            hit_length: fields[5].trim().to_string(),
+            query_length: fields[6].trim().to_string(),
+            num_matches: fields[7].trim().to_string(),
+        };
+
+        Ok(record)
+    }
+}
+
+/// Structure representing a Kaiju read classification record.
+///
+/// Kaiju's per-read output is tab-separated `C`/`U` (classified/unclassified),
+/// `readID`, `taxID`, followed by optional score/length/accession/fragment
+/// columns that only the `-v` flag adds and which Scrubby does not need.
+#[derive(Debug, Clone)]
+pub struct KaijuReadRecord {
+    pub classified: bool,
+    pub read_id: String,
+    pub tax_id: String,
+}
+
+impl KaijuReadRecord {
+    /// Creates a `KaijuReadRecord` instance from a tab-separated string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let record = KaijuReadRecord::from_str("C\tread1\t12345".to_string()).unwrap();
+    /// ```
+    pub fn from_str(kaiju_line: String) -> Result<Self, ScrubbyError> {
+        let fields: Vec<&str> = kaiju_line.split('\t').collect();
+
+        let record = Self {
+            classified: fields[0] == "C",
+            read_id: fields[1].trim().to_string(),
+            tax_id: fields[2].trim().to_string(),
+        };
+
+        Ok(record)
+    }
+}
@@ -2,28 +2,164 @@
 //! using various aligners and classifiers. It includes the core structures and 
 //! implementations for executing the cleaning pipeline with the Scrubby tool.
 
+use std::cell::RefCell;
 use std::io::{BufRead, BufReader};
-use std::process::{Command, Output, Stdio};
+use std::process::{Child, Command, Output, Stdio};
 use tempfile::{Builder, TempDir};
 use std::collections::HashSet;
-use std::path::PathBuf;
-use rayon::iter::ParallelIterator;
-use rayon::iter::IntoParallelRefIterator;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+use crate::audit::ReadAudit;
+use crate::checkpoint::{hash_parts, Checkpoint};
+use crate::complexity::{ComplexityFilter, ComplexityMethod};
+use crate::sketch::{FracMinHashSketch, SketchFilter};
+use crate::bracken::{AbundanceRecord, BrackenDatabase, estimate_abundance, redistribute, selected_fraction_per_node, write_abundance_tsv};
+use crate::report::{DepletionStats, EnsembleStat, FileDigest, Provenance, ReportWriter, ScrubbyReport, ToolVersion};
 
 #[cfg(feature = "mm2")]
 use crate::scrubby::Preset;
 #[cfg(feature = "mm2")]
 use crossbeam::channel;
-#[cfg(feature = "mm2")]
-use rayon::iter::IntoParallelIterator;
-#[cfg(feature = "mm2")]
-use std::sync::{Arc, Mutex};
 
-use crate::alignment::{PafRecord, ReadAlignment};
-use crate::error::ScrubbyError;
-use crate::scrubby::{Aligner, Classifier, Scrubby};
-use crate::classifier::{get_taxid_reads_kraken, get_taxid_reads_metabuli, get_taxids_from_report};
-use crate::utils::{get_id, get_fastx_writer, parse_fastx_file_with_check};
+#[cfg(feature = "htslib")]
+use rust_htslib::bam::{self, Read as BamRead};
+#[cfg(feature = "htslib")]
+use std::str::from_utf8;
+
+use crate::alignment::{PafReadAccumulator, PafRecord, ReadAlignment};
+use crate::error::{ScrubbyError, ParseContext};
+use crate::scrubby::{Aligner, Classifier, ClassifierOutput, CombineMode, Scrubby};
+use crate::classifier::{
+    get_taxid_reads_kraken, get_taxid_reads_metabuli, get_taxids_from_report,
+    get_taxids_from_krakenuniq_report, get_bracken_node_counts, get_taxid_reads_kraken_bracken,
+    parse_taxonomic_level, build_krona_entries, get_taxid_counts_kraken, get_taxid_counts_metabuli,
+    get_taxid_reads_centrifuge, get_taxid_counts_centrifuge,
+};
+use crate::krona::write_krona_report;
+use crate::readid::ReadIdNormalizer;
+use crate::taxonomy::Taxonomy;
+use crate::compression::{build_output_writer, Compression, CompressionAlgorithm};
+use crate::utils::{get_id, parse_fastx_file_with_check};
+use crate::preprocess::{preprocess_paired, preprocess_single, PreprocessConfig};
+
+/// Maximum number of trailing stderr bytes carried in a `CommandError`.
+const STDERR_TAIL_BYTES: usize = 4096;
+
+/// Number of reads between `--ndjson` progress records.
+const PROGRESS_INTERVAL: u64 = 100_000;
+
+/// Truncates a captured stderr buffer to its trailing `STDERR_TAIL_BYTES` and
+/// renders it lossily so non-UTF8 tool output never blocks error reporting.
+fn bounded_stderr_tail(stderr: &[u8]) -> String {
+    let tail = if stderr.len() > STDERR_TAIL_BYTES {
+        &stderr[stderr.len() - STDERR_TAIL_BYTES..]
+    } else {
+        stderr
+    };
+    String::from_utf8_lossy(tail).trim().to_string()
+}
+
+/// Extracts the first non-empty line of a version command's combined
+/// stdout/stderr, since tools disagree on which stream they print `--version`
+/// output to (and some, like `kraken2 --version`, print several lines).
+fn parse_tool_version(output: &Output) -> String {
+    let combined = [output.stdout.as_slice(), output.stderr.as_slice()].concat();
+    String::from_utf8_lossy(&combined)
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("")
+        .trim()
+        .to_string()
+}
+
+/// Clears the preprocessing/interleaving flags on a `Scrubby` clone whose
+/// `input` has already gone through `Cleaner::from_scrubby`'s preprocessing
+/// once, so re-running `from_scrubby` against an additional chained index
+/// (see `run_aligner_ids_with_index`/`run_classifier_ids_with_index`) doesn't
+/// trim, merge or de-interleave the already-processed input a second time.
+fn disable_reapplied_preprocessing(scrubby: &mut Scrubby) {
+    scrubby.config.interleaved = false;
+    scrubby.config.trim_quality = None;
+    scrubby.config.trim_adapter = None;
+    scrubby.config.merge_pairs = false;
+}
+
+/// Re-runs the configured aligner against `index` in place of `base`'s
+/// primary `aligner_index`, by cloning `base` with the index swapped in and
+/// `additional_indices` cleared (so the clone resolves exactly one index
+/// instead of re-expanding the whole chain). Used by `Cleaner::run_aligner_ids`
+/// to fold a sequence of reference indices into one combined read-ID set,
+/// taking `base: &Scrubby` rather than `&Cleaner` so it can be called from
+/// worker threads spawned by `resolve_indices` without requiring `Cleaner`
+/// itself to be `Sync`.
+fn run_aligner_ids_with_index(base: &Scrubby, index: &Path) -> Result<HashSet<String>, ScrubbyError> {
+    let mut scrubby = base.clone();
+    scrubby.config.aligner_index = Some(index.to_path_buf());
+    scrubby.config.additional_indices.clear();
+    disable_reapplied_preprocessing(&mut scrubby);
+    Cleaner::from_scrubby(&scrubby)?.run_aligner_ids()
+}
+
+/// Re-runs the configured classifier against `index` in place of `base`'s
+/// primary `classifier_index`, see `run_aligner_ids_with_index`.
+fn run_classifier_ids_with_index(base: &Scrubby, index: &Path) -> Result<HashSet<String>, ScrubbyError> {
+    let mut scrubby = base.clone();
+    scrubby.config.classifier_index = Some(index.to_path_buf());
+    scrubby.config.additional_indices.clear();
+    disable_reapplied_preprocessing(&mut scrubby);
+    Cleaner::from_scrubby(&scrubby)?.run_classifier_ids()
+}
+
+/// Resolves each of `indices` through `run_one`, up to `concurrency` jobs at
+/// a time, mirroring `batch::run_batch`'s bounded worker pool. Results are
+/// returned in the same order as `indices` regardless of which job finishes
+/// first, so folding them into a combined read-ID set (a `HashSet::extend`
+/// per entry) is reproducible independent of thread scheduling.
+///
+/// Uses scoped threads (rather than `run_batch`'s channel-based pool) since
+/// `run_one` borrows the calling `Cleaner`'s `Scrubby` instead of owning it;
+/// `thread::scope` lets the workers borrow `indices`/`run_one` directly
+/// without requiring a `'static` bound.
+///
+/// Falls back to a plain sequential loop when `concurrency` is 1 or there is
+/// at most one index, avoiding thread-spawn overhead for the common case.
+fn resolve_indices(
+    indices: &[PathBuf],
+    concurrency: usize,
+    run_one: impl Fn(&Path) -> Result<HashSet<String>, ScrubbyError> + Send + Sync,
+) -> Result<Vec<HashSet<String>>, ScrubbyError> {
+    if concurrency <= 1 || indices.len() <= 1 {
+        return indices.iter().map(|index| run_one(index)).collect();
+    }
+
+    let next_index = AtomicUsize::new(0);
+    let mut results: Vec<Option<Result<HashSet<String>, ScrubbyError>>> = (0..indices.len()).map(|_| None).collect();
+
+    {
+        let results = Mutex::new(&mut results);
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..concurrency.min(indices.len())).map(|_| {
+                scope.spawn(|| loop {
+                    let i = next_index.fetch_add(1, Ordering::SeqCst);
+                    let Some(index) = indices.get(i) else { break };
+                    let result = run_one(index);
+                    results.lock().expect("index result vector poisoned")[i] = Some(result);
+                })
+            }).collect();
+            for worker in workers {
+                worker.join().expect("index resolver thread panicked");
+            }
+        });
+    }
+
+    results.into_iter()
+        .map(|result| result.expect("every queued index produces exactly one result"))
+        .collect()
+}
 
 /// Configuration for Samtools commands used in the cleaning process.
 pub struct SamtoolsConfig {
@@ -88,6 +224,34 @@ impl SamtoolsConfig {
 pub struct Cleaner {
     scrubby: Scrubby,
     samtools: SamtoolsConfig,
+    audit: RefCell<ReadAudit>,
+    stats: RefCell<DepletionStats>,
+    /// Abundance re-estimation table written by `run_bracken_report`, folded
+    /// into the summary report returned by `create_report`. Empty unless
+    /// `--bracken-report` is configured.
+    abundance: RefCell<Vec<AbundanceRecord>>,
+    /// Per-backend/combined counts from `run_combined`, folded into the
+    /// summary report returned by `create_report`. `None` unless a `--combine` run was performed.
+    ensemble: RefCell<Option<EnsembleStat>>,
+    /// Opened once from `scrubby.ndjson` (if set) and shared between the
+    /// periodic progress records written while reads are processed and the
+    /// final summary record written by `write_ndjson_summary`.
+    ndjson: RefCell<Option<ReportWriter>>,
+    /// Holds the split R1/R2 files for `--interleaved` input for the lifetime
+    /// of the pipeline; cleaned up on drop. `None` for non-interleaved input.
+    _interleaved_tempdir: Option<TempDir>,
+    /// Holds the trimmed/merged reads produced by the preprocessing stage for
+    /// the lifetime of the pipeline; cleaned up on drop. `None` if no
+    /// preprocessing was configured.
+    _preprocess_tempdir: Option<TempDir>,
+    /// Loaded from `workdir/scrubby.checkpoint.json` when `scrubby.config.resume`
+    /// and `scrubby.workdir` are both set, consulted and updated by
+    /// `run_aligner_ids`/`run_classifier_ids`. `None` disables checkpointing.
+    checkpoint: RefCell<Option<Checkpoint>>,
+    /// Tool-version/digest manifest populated in `from_scrubby` when
+    /// `scrubby.config.provenance` is set, folded into the summary report
+    /// returned by `create_report`. Empty otherwise.
+    provenance: RefCell<Provenance>,
 }
 
 impl Cleaner {
@@ -104,9 +268,80 @@ impl Cleaner {
     /// let cleaner = Cleaner::from_scrubby(&scrubby_instance).unwrap();
     /// ```
     pub fn from_scrubby(scrubby: &Scrubby) -> Result<Self, ScrubbyError> {
-        let pipeline = Cleaner { 
-            scrubby: scrubby.clone(), 
+        let mut scrubby = scrubby.clone();
+
+        let interleaved_tempdir = if scrubby.config.interleaved {
+            let temp_dir = match &scrubby.workdir {
+                Some(path) => Builder::new().tempdir_in(path)?,
+                None => TempDir::new()?,
+            };
+            let input_r1 = temp_dir.path().join("interleaved_R1.fastq");
+            let input_r2 = temp_dir.path().join("interleaved_R2.fastq");
+            deinterleave_fastq(&scrubby.input[0], &input_r1, &input_r2)?;
+            scrubby.input = vec![input_r1, input_r2];
+            Some(temp_dir)
+        } else {
+            None
+        };
+
+        let preprocess_config = PreprocessConfig {
+            trim_quality: scrubby.config.trim_quality,
+            trim_adapter: scrubby.config.trim_adapter.clone(),
+            min_read_length: scrubby.config.min_read_length,
+            window: scrubby.config.preprocess_window,
+            merge_pairs: scrubby.config.merge_pairs,
+            exclude_unmerged: scrubby.config.exclude_unmerged,
+            min_merge_overlap: scrubby.config.min_merge_overlap,
+        };
+
+        let preprocess_tempdir = if preprocess_config.is_active() {
+            let temp_dir = match &scrubby.workdir {
+                Some(path) => Builder::new().tempdir_in(path)?,
+                None => TempDir::new()?,
+            };
+
+            if scrubby.config.paired_end {
+                let r1_output = temp_dir.path().join("preprocessed_R1.fastq");
+                let r2_output = temp_dir.path().join("preprocessed_R2.fastq");
+                if preprocess_config.merge_pairs {
+                    let merged_output = temp_dir.path().join("preprocessed_merged.fastq");
+                    preprocess_paired(&scrubby.input[0], &scrubby.input[1], &r1_output, &r2_output, Some(&merged_output), &preprocess_config)?;
+                    scrubby.input = vec![merged_output];
+                    scrubby.config.paired_end = false;
+                } else {
+                    preprocess_paired(&scrubby.input[0], &scrubby.input[1], &r1_output, &r2_output, None, &preprocess_config)?;
+                    scrubby.input = vec![r1_output, r2_output];
+                }
+            } else {
+                let output = temp_dir.path().join("preprocessed.fastq");
+                preprocess_single(&scrubby.input[0], &output, &preprocess_config)?;
+                scrubby.input = vec![output];
+            }
+
+            Some(temp_dir)
+        } else {
+            None
+        };
+
+        let ndjson = scrubby.ndjson.as_deref().map(ReportWriter::ndjson).transpose()?;
+
+        let checkpoint = match (&scrubby.workdir, scrubby.config.resume) {
+            (Some(workdir), true) => Some(Checkpoint::load(workdir)?),
+            _ => None,
+        };
+
+        let pipeline = Cleaner {
             samtools: SamtoolsConfig::from_scrubby(&scrubby),
+            scrubby,
+            audit: RefCell::new(ReadAudit::new()),
+            stats: RefCell::new(DepletionStats::new()),
+            abundance: RefCell::new(Vec::new()),
+            ensemble: RefCell::new(None),
+            ndjson: RefCell::new(ndjson),
+            _interleaved_tempdir: interleaved_tempdir,
+            _preprocess_tempdir: preprocess_tempdir,
+            checkpoint: RefCell::new(checkpoint),
+            provenance: RefCell::new(Provenance::default()),
         };
 
         if let Some(aligner) = &pipeline.scrubby.config.aligner {
@@ -115,8 +350,29 @@ impl Cleaner {
             pipeline.check_classifier_dependency(classifier)?;
         }
 
+        if pipeline.scrubby.config.provenance {
+            pipeline.capture_digest_provenance()?;
+        }
+
         Ok(pipeline)
     }
+    /// Digests every input file and the configured aligner/classifier index
+    /// into `self.provenance`, run once at construction so the digest
+    /// reflects the exact bytes this run was invoked against.
+    fn capture_digest_provenance(&self) -> Result<(), ScrubbyError> {
+        let mut provenance = self.provenance.borrow_mut();
+
+        for input in &self.scrubby.input {
+            provenance.inputs.push(FileDigest { sha256: Provenance::digest_file(input)?, path: input.clone() });
+        }
+
+        let index = self.scrubby.config.aligner_index.as_ref().or(self.scrubby.config.classifier_index.as_ref());
+        if let Some(index) = index {
+            provenance.databases.extend(Provenance::digest_path(index)?);
+        }
+
+        Ok(())
+    }
 
     /// Executes the aligner process.
     ///
@@ -130,15 +386,164 @@ impl Cleaner {
     /// cleaner.run_aligner().unwrap();
     /// ```
     pub fn run_aligner(&self) -> Result<(), ScrubbyError> {
-        match self.scrubby.config.aligner {
-            Some(Aligner::Minimap2) => self.run_minimap2()?,
-            Some(Aligner::Minigraph) => self.run_minigraph()?,
-            Some(Aligner::Bowtie2) => self.run_bowtie2()?,
-            Some(Aligner::Strobealign) => self.run_strobealign()?,
-            #[cfg(feature = "mm2")]
-            Some(Aligner::Minimap2Rs) => self.run_minimap2_rs()?,
-            None => return Err(ScrubbyError::MissingAligner),
+        self.clean_reads(&self.run_aligner_ids()?)?;
+        Ok(())
+    }
+    /// Runs the configured aligner and resolves its output to the set of
+    /// mapped read identifiers, without cleaning the input files itself. Used
+    /// directly by `run_aligner` and by `run_combined` when both an aligner
+    /// and a classifier are configured. `custom_aligner` takes precedence
+    /// over `aligner`, so a registered backend can be selected without
+    /// touching the built-in `Aligner` enum.
+    pub fn run_aligner_ids(&self) -> Result<HashSet<String>, ScrubbyError> {
+        let hash = hash_parts(&[
+            "aligner",
+            &format!("{:?}", self.scrubby.config.aligner),
+            self.scrubby.config.custom_aligner.as_deref().unwrap_or(""),
+            self.scrubby.config.aligner_args.as_deref().unwrap_or(""),
+            &self.scrubby.config.aligner_index.as_ref().map(|p| p.display().to_string()).unwrap_or_default(),
+            &self.scrubby.input.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(","),
+        ]);
+
+        let mut ids = self.run_checkpointed("aligner_ids", &hash, || {
+            if let Some(name) = &self.scrubby.config.custom_aligner {
+                return self.run_custom_aligner(name);
+            }
+            match self.scrubby.config.aligner {
+                Some(Aligner::Minimap2) => self.run_minimap2(),
+                Some(Aligner::Minigraph) => self.run_minigraph(),
+                Some(Aligner::Bowtie2) => self.run_bowtie2(),
+                Some(Aligner::Strobealign) => self.run_strobealign(),
+                #[cfg(feature = "mm2")]
+                Some(Aligner::Minimap2Rs) => self.run_minimap2_rs(),
+                None => Err(ScrubbyError::MissingAligner),
+            }
+        })?;
+
+        let extra_ids = resolve_indices(
+            &self.scrubby.config.additional_indices,
+            self.scrubby.config.index_concurrency,
+            |index| run_aligner_ids_with_index(&self.scrubby, index),
+        )?;
+        for (index, extra_ids) in self.scrubby.config.additional_indices.iter().zip(extra_ids) {
+            if self.scrubby.config.audit {
+                self.audit.borrow_mut().record(&extra_ids, "aligner", &index.display().to_string(), None);
+            }
+            ids.extend(extra_ids);
+        }
+
+        Ok(ids)
+    }
+    /// Returns `read_ids_fn`'s cached result from a prior checkpointed run of
+    /// `stage` if `hash` still matches and the cache is intact, otherwise
+    /// calls `read_ids_fn` and checkpoints its result. A no-op pass-through
+    /// when checkpointing is disabled (`resume`/`workdir` not both set).
+    fn run_checkpointed(
+        &self,
+        stage: &str,
+        hash: &str,
+        read_ids_fn: impl FnOnce() -> Result<HashSet<String>, ScrubbyError>,
+    ) -> Result<HashSet<String>, ScrubbyError> {
+        let Some(workdir) = &self.scrubby.workdir else {
+            return read_ids_fn();
+        };
+        if let Some(cached) = self.checkpoint.borrow().as_ref().and_then(|c| c.cached_read_ids(stage, hash)) {
+            log::info!("Resuming '{stage}' from checkpoint in {}", workdir.display());
+            return Ok(cached);
+        }
+
+        let read_ids = read_ids_fn()?;
+
+        if let Some(checkpoint) = self.checkpoint.borrow_mut().as_mut() {
+            checkpoint.mark_complete(workdir, stage, hash, &read_ids)?;
+        }
+
+        Ok(read_ids)
+    }
+    /// Runs a custom aligner backend registered with
+    /// `backend::register_aligner_backend`, reusing the generic SAM/BAM/PAF/GAF
+    /// parser (`ReadAlignment::from`) that the built-in aligners' output also
+    /// goes through via `run_aligner_output`.
+    fn run_custom_aligner(&self, name: &str) -> Result<HashSet<String>, ScrubbyError> {
+        let backend = crate::backend::get_aligner_backend(name)
+            .ok_or_else(|| ScrubbyError::UnknownAlignerBackend(name.to_string()))?;
+
+        let temp_dir = match &self.scrubby.workdir {
+            Some(path) => Builder::new().tempdir_in(path)?,
+            None => TempDir::new()?,
+        };
+        let output_path = temp_dir.path().join("custom_aligner.out");
+
+        let cmd = backend.command(&self.scrubby, &output_path)?;
+        self.run_command(&cmd)?;
+
+        let alignment = ReadAlignment::from(
+            &output_path,
+            self.scrubby.config.min_query_length,
+            self.scrubby.config.min_query_coverage,
+            self.scrubby.config.min_mapq,
+            Some(backend.output_format()),
+            &self.scrubby.config.paf_filter_mode,
+            self.scrubby.config.skip_secondary_alignments,
+            self.scrubby.config.require_proper_pair,
+            self.scrubby.config.min_identity,
+            self.scrubby.config.reference.clone(),
+        )?;
+
+        temp_dir.close()?;
+        Ok(alignment.aligned_reads)
+    }
+    /// Thread budget handed to a single classifier/aligner invocation. When
+    /// `--combine` is set, the aligner and classifier are two independent
+    /// jobs contending for the same machine, so the `--threads` budget is
+    /// halved between them instead of each claiming it in full; otherwise a
+    /// single configured tool still gets the whole budget.
+    fn job_threads(&self) -> usize {
+        match self.scrubby.config.combine {
+            Some(_) => (self.scrubby.threads / 2).max(1),
+            None => self.scrubby.threads,
+        }
+    }
+    /// Runs both the configured aligner and classifier, merges their mapped
+    /// read ID sets according to `mode`, and cleans the input files with the
+    /// combined set. Per-backend and combined counts are recorded in
+    /// `self.ensemble` for `create_report` to surface the aligner/classifier
+    /// agreement directly, rather than just the combined total.
+    ///
+    /// The two jobs are run one after another rather than concurrently:
+    /// `parse_classifier_output` records into `self.audit`/`self.stats`
+    /// (`RefCell`s, not thread-safe), so dispatching both at once would need
+    /// that bookkeeping made thread-safe across the whole tool-invocation
+    /// surface. `job_threads` still halves each job's own thread budget so
+    /// that, should a future change make concurrent dispatch safe, neither
+    /// job oversubscribes the machine by claiming the full budget for itself.
+    pub fn run_combined(&self, mode: &CombineMode) -> Result<(), ScrubbyError> {
+        let aligner_ids = self.run_aligner_ids()?;
+        let classifier_ids = self.run_classifier_ids()?;
+
+        let combined: HashSet<String> = match mode {
+            CombineMode::Union => aligner_ids.union(&classifier_ids).cloned().collect(),
+            CombineMode::Intersection | CombineMode::Majority => {
+                // With two backends, requiring more than half of the votes is
+                // the same as requiring both; see the `CombineMode::Majority` doc comment.
+                aligner_ids.intersection(&classifier_ids).cloned().collect()
+            },
+        };
+
+        if self.scrubby.config.audit {
+            self.audit.borrow_mut().record(&aligner_ids, "aligner", "", None);
+            self.audit.borrow_mut().record(&classifier_ids, "classifier", "", None);
         }
+
+        *self.ensemble.borrow_mut() = Some(EnsembleStat {
+            mode: mode.to_string(),
+            aligner_reads: aligner_ids.len() as u64,
+            classifier_reads: classifier_ids.len() as u64,
+            combined_reads: combined.len() as u64,
+        });
+
+        self.clean_reads(&combined)?;
+
         Ok(())
     }
 
@@ -154,13 +559,76 @@ impl Cleaner {
     /// cleaner.run_classifier().unwrap();
     /// ```
     pub fn run_classifier(&self) -> Result<(), ScrubbyError> {
-        match self.scrubby.config.classifier {
-            Some(Classifier::Kraken2) => self.run_kraken()?,
-            Some(Classifier::Metabuli) => self.run_metabuli()?,
-            None => return Err(ScrubbyError::MissingClassifier),
-        }
+        self.clean_reads(&self.run_classifier_ids()?)?;
         Ok(())
     }
+    /// Runs the configured classifier and resolves its output to the set of
+    /// read identifiers selected for depletion/extraction, without cleaning
+    /// the input files itself. Used directly by `run_classifier` and by
+    /// `run_combined` when both an aligner and a classifier are configured.
+    /// `custom_classifier` takes precedence over `classifier`, so a
+    /// registered backend can be selected without touching the built-in
+    /// `Classifier` enum.
+    pub fn run_classifier_ids(&self) -> Result<HashSet<String>, ScrubbyError> {
+        let hash = hash_parts(&[
+            "classifier",
+            &format!("{:?}", self.scrubby.config.classifier),
+            self.scrubby.config.custom_classifier.as_deref().unwrap_or(""),
+            self.scrubby.config.classifier_args.as_deref().unwrap_or(""),
+            &self.scrubby.config.classifier_index.as_ref().map(|p| p.display().to_string()).unwrap_or_default(),
+            &self.scrubby.input.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(","),
+        ]);
+
+        let mut ids = self.run_checkpointed("classifier_ids", &hash, || {
+            if let Some(name) = &self.scrubby.config.custom_classifier {
+                return self.run_custom_classifier(name);
+            }
+            match self.scrubby.config.classifier {
+                Some(Classifier::Kraken2) => self.run_kraken(),
+                Some(Classifier::Metabuli) => self.run_metabuli(),
+                Some(Classifier::KrakenUniq) => self.run_krakenuniq(),
+                Some(Classifier::Centrifuge) => self.run_centrifuge(),
+                None => Err(ScrubbyError::MissingClassifier),
+            }
+        })?;
+
+        let extra_ids = resolve_indices(
+            &self.scrubby.config.additional_indices,
+            self.scrubby.config.index_concurrency,
+            |index| run_classifier_ids_with_index(&self.scrubby, index),
+        )?;
+        for (index, extra_ids) in self.scrubby.config.additional_indices.iter().zip(extra_ids) {
+            if self.scrubby.config.audit {
+                self.audit.borrow_mut().record(&extra_ids, "classifier", &index.display().to_string(), None);
+            }
+            ids.extend(extra_ids);
+        }
+
+        Ok(ids)
+    }
+    /// Runs a custom classifier backend registered with
+    /// `backend::register_classifier_backend`, reusing the Kraken2-style
+    /// report/read-classification parser (`parse_classifier_output`) that the
+    /// built-in classifiers' output also goes through.
+    fn run_custom_classifier(&self, name: &str) -> Result<HashSet<String>, ScrubbyError> {
+        let backend = crate::backend::get_classifier_backend(name)
+            .ok_or_else(|| ScrubbyError::UnknownClassifierBackend(name.to_string()))?;
+
+        let temp_dir = match &self.scrubby.workdir {
+            Some(path) => Builder::new().tempdir_in(path)?,
+            None => TempDir::new()?,
+        };
+        let report_path = temp_dir.path().join("custom_classifier.report");
+        let reads_path = temp_dir.path().join("custom_classifier.reads");
+
+        let cmd = backend.command(&self.scrubby, &report_path, &reads_path)?;
+        self.run_command(&cmd)?;
+
+        let read_ids = self.parse_classifier_output(&report_path, &reads_path)?;
+
+        temp_dir.close()?;
+        Ok(read_ids)
+    }
     /// Executes the classifier output cleaning process.
     ///
     /// # Returns
@@ -173,21 +641,19 @@ impl Cleaner {
     /// cleaner.run_classifier_output().unwrap();
     /// ```
     pub fn run_classifier_output(&self) -> Result<(), ScrubbyError> {
-        match self.scrubby.config.classifier {
-            Some(Classifier::Kraken2) | Some(Classifier::Metabuli) => {
-                self.clean_reads(
-                    &self.parse_classifier_output(
-                        &self.scrubby.config.report
-                            .clone()
-                            .ok_or(ScrubbyError::MissingClassifierClassificationReport)?, 
-                        &self.scrubby.config.reads
-                            .clone()
-                            .ok_or(ScrubbyError::MissingClassifierReadClassfications)?
-                    )?
-                )?
-            },
-            None => return Err(ScrubbyError::MissingClassifier),
+        if self.scrubby.config.classifier.is_none() && self.scrubby.config.classifier_output.is_none() {
+            return Err(ScrubbyError::MissingClassifier);
         }
+        self.clean_reads(
+            &self.parse_classifier_output(
+                &self.scrubby.config.report
+                    .clone()
+                    .ok_or(ScrubbyError::MissingClassifierClassificationReport)?,
+                &self.scrubby.config.reads
+                    .clone()
+                    .ok_or(ScrubbyError::MissingClassifierReadClassfications)?
+            )?
+        )?;
         Ok(())
     }
     /// Executes the alignment output cleaning process.
@@ -208,15 +674,35 @@ impl Cleaner {
             self.scrubby.config.min_query_length,
             self.scrubby.config.min_query_coverage,
             self.scrubby.config.min_mapq,
-            self.scrubby.config.alignment_format.clone()
+            self.scrubby.config.alignment_format.clone(),
+            &self.scrubby.config.paf_filter_mode,
+            self.scrubby.config.skip_secondary_alignments,
+            self.scrubby.config.require_proper_pair,
+            self.scrubby.config.min_identity,
+            self.scrubby.config.reference.clone(),
         )?;
 
+        if self.scrubby.config.audit {
+            let db = self.scrubby.config.aligner_index.as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            self.audit.borrow_mut().record(&alignment.aligned_reads, "aligner", &db, None);
+        }
+
         self.clean_reads(&alignment.aligned_reads)?;
 
         Ok(())
     }
     /// Cleans reads based on the provided read IDs.
     ///
+    /// This is the single point in the pipeline that actually rewrites FASTX
+    /// files: every stage (aligner, classifier, combined, complexity, sketch)
+    /// only accumulates matching read IDs into an in-memory `HashSet`, so
+    /// chaining stages or additional reference indices never requires
+    /// rewriting the input to a workdir in between - `read_ids` is filtered
+    /// against `self.scrubby.input` (or the preprocessing stage's one-time
+    /// output) exactly once per run.
+    ///
     /// # Arguments
     ///
     /// * `read_ids` - A reference to a set of read IDs to be cleaned.
@@ -232,21 +718,49 @@ impl Cleaner {
     /// cleaner.clean_reads(&read_ids).unwrap();
     /// ```
     pub fn clean_reads(&self, read_ids: &HashSet<String>) -> Result<(), ScrubbyError> {
+        let normalizer = self.scrubby.config.strip_suffix.as_deref()
+            .map(ReadIdNormalizer::new)
+            .transpose()?;
+
+        let start = Instant::now();
+        let mut progress_closure = self.scrubby.ndjson.is_some().then(|| {
+            move |reads_in: u64, reads_removed: u64| self.emit_progress(reads_in, reads_removed, start)
+        });
+
         if self.scrubby.config.paired_end {
-            rayon::ThreadPoolBuilder::new()
-                .num_threads(if self.scrubby.config.needletail_parallel { 2 } else { 1 })
-                .build()?
-                .install(|| -> Result<(), ScrubbyError> {
-                    [0, 1].par_iter().map(|&i| {
-                        let fastq_cleaner = FastqCleaner::from(&self.scrubby.input[i], &self.scrubby.output[i]);
-                        fastq_cleaner.clean_reads(&read_ids, self.scrubby.extract)?;
-                        Ok(())
-                    }).collect::<Result<Vec<_>, ScrubbyError>>()?;
-                    Ok(())
-                })?;
+            // Mates are decided on jointly (by `PairedFastqCleaner`) so a hit on
+            // either R1 or R2 always removes/extracts both, rather than letting
+            // the two files be cleaned independently and risk desyncing.
+            let paired_cleaner = PairedFastqCleaner::from(
+                &self.scrubby.input[0], &self.scrubby.input[1],
+                &self.scrubby.output[0], &self.scrubby.output[1],
+                self.scrubby.removed.get(0), self.scrubby.removed.get(1),
+            ).compression(self.scrubby.config.compression_format, self.scrubby.config.compression_level)
+            .compression_threads(self.scrubby.config.compression_threads);
+
+            let progress = progress_closure.as_mut().map(|f| f as &mut dyn FnMut(u64, u64) -> Result<(), ScrubbyError>);
+            let counts = paired_cleaner.clean_reads(&read_ids, self.scrubby.extract, normalizer.as_ref(), progress)?;
+
+            let elapsed_secs = start.elapsed().as_secs_f64();
+            self.stats.borrow_mut().record_file(
+                &self.scrubby.input[0], &self.scrubby.output[0], self.scrubby.extract,
+                counts.reads_in, counts.reads_removed(), counts.bases_in, counts.bases_out, elapsed_secs,
+            );
+            self.stats.borrow_mut().record_file(
+                &self.scrubby.input[1], &self.scrubby.output[1], self.scrubby.extract,
+                counts.reads_in, counts.reads_removed(), counts.bases_in, counts.bases_out, elapsed_secs,
+            );
         } else {
-            let fastq_cleaner = FastqCleaner::from(&self.scrubby.input[0], &self.scrubby.output[0]);
-            fastq_cleaner.clean_reads(&read_ids, self.scrubby.extract)?;
+            let fastq_cleaner = FastqCleaner::from(
+                &self.scrubby.input[0], &self.scrubby.output[0], self.scrubby.removed.get(0)
+            ).compression(self.scrubby.config.compression_format, self.scrubby.config.compression_level)
+            .compression_threads(self.scrubby.config.compression_threads);
+            let progress = progress_closure.as_mut().map(|f| f as &mut dyn FnMut(u64, u64) -> Result<(), ScrubbyError>);
+            let counts = fastq_cleaner.clean_reads(&read_ids, self.scrubby.extract, normalizer.as_ref(), progress)?;
+            self.stats.borrow_mut().record_file(
+                &self.scrubby.input[0], &self.scrubby.output[0], self.scrubby.extract,
+                counts.reads_in, counts.reads_removed(), counts.bases_in, counts.bases_out, start.elapsed().as_secs_f64(),
+            );
         }
         Ok(())
     }
@@ -259,15 +773,23 @@ impl Cleaner {
             #[cfg(feature = "mm2")]
             Aligner::Minimap2Rs => return Ok(())
         };
-        self.run_version_command(command).map_err(|_| ScrubbyError::AlignerDependencyMissing(aligner.clone()))?;
+        let output = self.run_version_command(command).map_err(|_| ScrubbyError::AlignerDependencyMissing(aligner.clone()))?;
+        if self.scrubby.config.provenance {
+            self.provenance.borrow_mut().tools.push(ToolVersion { name: aligner.to_string(), version: parse_tool_version(&output) });
+        }
         Ok(())
     }
     fn check_classifier_dependency(&self, classifier: &Classifier) -> Result<(), ScrubbyError> {
         let command = match classifier {
             Classifier::Kraken2 => "kraken2 --version",
             Classifier::Metabuli => "metabuli",
+            Classifier::KrakenUniq => "krakenuniq --version",
+            Classifier::Centrifuge => "centrifuge --version",
         };
-        self.run_version_command(command).map_err(|_| ScrubbyError::ClassifierDependencyMissing(classifier.clone()))?;
+        let output = self.run_version_command(command).map_err(|_| ScrubbyError::ClassifierDependencyMissing(classifier.clone()))?;
+        if self.scrubby.config.provenance {
+            self.provenance.borrow_mut().tools.push(ToolVersion { name: classifier.to_string(), version: parse_tool_version(&output) });
+        }
         Ok(())
     }
     fn run_version_command(&self, command: &str) -> Result<Output, ScrubbyError> {
@@ -278,12 +800,17 @@ impl Cleaner {
             .map_err(|e| ScrubbyError::CommandExecutionFailed(command.to_string(), e.to_string()))?;
 
         if !output.status.success() {
-            return Err(ScrubbyError::CommandFailed(command.to_string(), output.status.code().unwrap_or(-1)));
+            return Err(ScrubbyError::CommandError {
+                program: "sh -c".to_string(),
+                args: vec![command.to_string()],
+                status: output.status.code().unwrap_or(-1),
+                stderr: bounded_stderr_tail(&output.stderr),
+            });
         }
 
         Ok(output)
     }
-    fn run_kraken(&self) -> Result<(), ScrubbyError> {
+    fn run_kraken(&self) -> Result<HashSet<String>, ScrubbyError> {
         let classifier_args = self.scrubby.config.classifier_args.as_deref().unwrap_or("");
         let classifier_index = self.scrubby.config.classifier_index.as_ref().ok_or(ScrubbyError::MissingClassifierIndex)?;
 
@@ -298,7 +825,7 @@ impl Cleaner {
         let cmd = if self.scrubby.config.paired_end {
             format!(
                 "kraken2 --threads {} --db {} {} --paired {} {} --output {} --report {}",
-                self.scrubby.threads,
+                self.job_threads(),
                 classifier_index.display(),
                 classifier_args,
                 self.scrubby.input[0].display(),
@@ -309,7 +836,7 @@ impl Cleaner {
         } else {
             format!(
                 "kraken2 --threads {} --db {} {} --single {} --output {} --report {}",
-                self.scrubby.threads,
+                self.job_threads(),
                 classifier_index.display(),
                 classifier_args,
                 self.scrubby.input[0].display(),
@@ -320,14 +847,54 @@ impl Cleaner {
 
         self.run_command(&cmd)?;
 
-        self.clean_reads(
-            &self.parse_classifier_output(&kraken_report, &kraken_reads)?
-        )?;
+        let read_ids = self.parse_classifier_output(&kraken_report, &kraken_reads)?;
 
         temp_dir.close()?;
-        Ok(())
+        Ok(read_ids)
+    }
+    fn run_krakenuniq(&self) -> Result<HashSet<String>, ScrubbyError> {
+        let classifier_args = self.scrubby.config.classifier_args.as_deref().unwrap_or("");
+        let classifier_index = self.scrubby.config.classifier_index.as_ref().ok_or(ScrubbyError::MissingClassifierIndex)?;
+
+        let temp_dir = match &self.scrubby.workdir {
+            Some(path) => Builder::new().tempdir_in(path)?,
+            None => TempDir::new()?,
+        };
+
+        let krakenuniq_reads = temp_dir.path().join("krakenuniq.reads");
+        let krakenuniq_report = temp_dir.path().join("krakenuniq.report");
+
+        let cmd = if self.scrubby.config.paired_end {
+            format!(
+                "krakenuniq --threads {} --db {} {} --paired {} {} --output {} --report-file {}",
+                self.job_threads(),
+                classifier_index.display(),
+                classifier_args,
+                self.scrubby.input[0].display(),
+                self.scrubby.input[1].display(),
+                krakenuniq_reads.display(),
+                krakenuniq_report.display(),
+            )
+        } else {
+            format!(
+                "krakenuniq --threads {} --db {} {} {} --output {} --report-file {}",
+                self.job_threads(),
+                classifier_index.display(),
+                classifier_args,
+                self.scrubby.input[0].display(),
+                krakenuniq_reads.display(),
+                krakenuniq_report.display(),
+            )
+        };
+
+        self.run_command(&cmd)?;
+
+        let read_ids = self.parse_classifier_output(&krakenuniq_report, &krakenuniq_reads)?;
+
+        temp_dir.close()?;
+        Ok(read_ids)
     }
-    fn run_metabuli(&self) -> Result<(), ScrubbyError> {
+    fn run_metabuli(&self) -> Result<HashSet<String>, ScrubbyError> {
         let classifier_args = self.scrubby.config.classifier_args.as_deref().unwrap_or("");
         let classifier_index = self.scrubby.config.classifier_index.as_ref().ok_or(ScrubbyError::MissingClassifierIndex)?;
 
@@ -339,7 +906,7 @@ impl Cleaner {
         let cmd = if self.scrubby.config.paired_end {
             format!(
                 "metabuli classify --seq-mode 2 --threads {} {} {} {} {} {} {}",
-                self.scrubby.threads,
+                self.job_threads(),
                 classifier_args,
                 self.scrubby.input[0].display(),
                 self.scrubby.input[1].display(),
@@ -350,7 +917,7 @@ impl Cleaner {
         } else {
             format!(
                 "metabuli classify --seq-mode 3 --threads {} {} {} {} {} {}",
-                self.scrubby.threads,
+                self.job_threads(),
                 classifier_args,
                 self.scrubby.input[0].display(),
                 classifier_index.display(),
@@ -361,55 +928,307 @@ impl Cleaner {
 
         self.run_command(&cmd)?;
 
-        self.clean_reads(
-            &self.parse_classifier_output(
-                &temp_dir.path().join("metabuli_report.tsv"), 
-                &temp_dir.path().join("metabuli_classifications.tsv")
-            )?
+        let read_ids = self.parse_classifier_output(
+            &temp_dir.path().join("metabuli_report.tsv"),
+            &temp_dir.path().join("metabuli_classifications.tsv")
         )?;
 
         temp_dir.close()?;
-        
-        Ok(())
+
+        Ok(read_ids)
+    }
+    fn run_centrifuge(&self) -> Result<HashSet<String>, ScrubbyError> {
+        let classifier_args = self.scrubby.config.classifier_args.as_deref().unwrap_or("");
+        let classifier_index = self.scrubby.config.classifier_index.as_ref().ok_or(ScrubbyError::MissingClassifierIndex)?;
+
+        let temp_dir = match &self.scrubby.workdir {
+            Some(path) => Builder::new().tempdir_in(path)?,
+            None => TempDir::new()?,
+        };
+
+        let centrifuge_reads = temp_dir.path().join("centrifuge.reads");
+        let centrifuge_summary = temp_dir.path().join("centrifuge.summary");
+        let centrifuge_report = temp_dir.path().join("centrifuge.kreport");
+
+        let cmd = if self.scrubby.config.paired_end {
+            format!(
+                "centrifuge -x {} -p {} {} -1 {} -2 {} -S {} --report-file {}",
+                classifier_index.display(),
+                self.job_threads(),
+                classifier_args,
+                self.scrubby.input[0].display(),
+                self.scrubby.input[1].display(),
+                centrifuge_reads.display(),
+                centrifuge_summary.display(),
+            )
+        } else {
+            format!(
+                "centrifuge -x {} -p {} {} -U {} -S {} --report-file {}",
+                classifier_index.display(),
+                self.job_threads(),
+                classifier_args,
+                self.scrubby.input[0].display(),
+                centrifuge_reads.display(),
+                centrifuge_summary.display(),
+            )
+        };
+
+        self.run_command(&cmd)?;
+
+        // `centrifuge` only emits its own summary table; the kraken-style report
+        // that `--taxa`/`--taxa-direct` sub-tree matching relies on is produced
+        // from the per-read output by the classifier's own `centrifuge-kreport`.
+        let kreport_cmd = format!(
+            "centrifuge-kreport -x {} {} > {}",
+            classifier_index.display(),
+            centrifuge_reads.display(),
+            centrifuge_report.display(),
+        );
+        self.run_command(&kreport_cmd)?;
+
+        let read_ids = self.parse_classifier_output(&centrifuge_report, &centrifuge_reads)?;
+
+        temp_dir.close()?;
+        Ok(read_ids)
     }
     fn parse_classifier_output(&self, report: &PathBuf, reads: &PathBuf) -> Result<HashSet<String>, ScrubbyError> {
-        let taxids = get_taxids_from_report(report, &self.scrubby.config.taxa, &self.scrubby.config.taxa_direct)?;
-        match &self.scrubby.config.classifier {
-            Some(Classifier::Kraken2) => Ok(get_taxid_reads_kraken(taxids, reads)?),
-            Some(Classifier::Metabuli) => Ok(get_taxid_reads_metabuli(taxids, reads)?),
-            None => Err(ScrubbyError::MissingClassifier),
+        let taxonomy = self.scrubby.config.taxonomy_directory.as_ref()
+            .map(|dir| Taxonomy::from_directory(dir))
+            .transpose()?;
+
+        // `classifier_output` is the dedicated format selector for this
+        // output-only cleaning path; `classifier` remains a fallback so
+        // callers that already set it (e.g. the `classifier` subcommand)
+        // keep working unchanged.
+        let classifier = self.scrubby.config.classifier_output.as_ref()
+            .map(ClassifierOutput::as_classifier)
+            .or_else(|| self.scrubby.config.classifier.clone());
+
+        let taxids = match &classifier {
+            Some(Classifier::KrakenUniq) => get_taxids_from_krakenuniq_report(
+                report,
+                &self.scrubby.config.taxa,
+                &self.scrubby.config.taxa_direct,
+                self.scrubby.config.min_unique_kmers,
+            )?,
+            _ => get_taxids_from_report(
+                report, &self.scrubby.config.taxa, &self.scrubby.config.taxa_direct, taxonomy.as_ref(),
+                self.scrubby.config.min_reads, self.scrubby.config.min_fraction,
+                self.scrubby.config.prune_rank.as_deref().map(parse_taxonomic_level),
+                self.scrubby.config.taxon_report.as_ref(),
+            )?,
+        };
+        let krona_taxids = self.scrubby.config.krona.is_some().then(|| taxids.clone());
+
+        let taxid_counts = match &classifier {
+            Some(Classifier::Metabuli) => get_taxid_counts_metabuli(&taxids, reads)?,
+            Some(Classifier::Centrifuge) => get_taxid_counts_centrifuge(&taxids, reads)?,
+            _ => get_taxid_counts_kraken(&taxids, reads)?,
+        };
+        self.stats.borrow_mut().record_taxid_counts(&taxid_counts);
+
+        let (read_ids, bracken_reads) = match &classifier {
+            Some(Classifier::Kraken2) => match &self.scrubby.config.bracken_db {
+                Some(bracken_db_path) => {
+                    let bracken_rank = parse_taxonomic_level(
+                        self.scrubby.config.bracken_rank.as_deref().unwrap_or("genus")
+                    );
+                    let db = BrackenDatabase::from_path(bracken_db_path)?;
+                    let (node_reads, species_reads) = get_bracken_node_counts(report, bracken_rank)?;
+                    let node_fractions = selected_fraction_per_node(
+                        &redistribute(&db, &node_reads, &species_reads), &node_reads, &taxids,
+                    );
+                    let direct_reads = get_taxid_reads_kraken(taxids.clone(), reads)?;
+                    let all_reads = get_taxid_reads_kraken_bracken(taxids, &node_fractions, reads)?;
+                    let bracken_reads: HashSet<String> = all_reads.difference(&direct_reads).cloned().collect();
+                    (all_reads, bracken_reads)
+                },
+                None => (get_taxid_reads_kraken(taxids, reads)?, HashSet::new()),
+            },
+            Some(Classifier::KrakenUniq) => (get_taxid_reads_kraken(taxids, reads)?, HashSet::new()),
+            Some(Classifier::Metabuli) => (
+                get_taxid_reads_metabuli(taxids, reads, self.scrubby.config.metabuli_min_score)?,
+                HashSet::new(),
+            ),
+            Some(Classifier::Centrifuge) => (get_taxid_reads_centrifuge(taxids, reads)?, HashSet::new()),
+            // Reached when cleaning via a custom classifier backend, which
+            // is required to produce Kraken2-style report/reads output (see
+            // `ClassifierBackend`) and so is parsed the same way.
+            None => (get_taxid_reads_kraken(taxids, reads)?, HashSet::new()),
+        };
+
+        if self.scrubby.config.audit {
+            let db = self.scrubby.config.classifier_index.as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            let directly_assigned: HashSet<String> = read_ids.difference(&bracken_reads).cloned().collect();
+            self.audit.borrow_mut().record(&directly_assigned, "classifier", &db, None);
+
+            if !bracken_reads.is_empty() {
+                let bracken_db = self.scrubby.config.bracken_db.as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default();
+                self.audit.borrow_mut().record(&bracken_reads, "bracken", &bracken_db, None);
+            }
+        }
+
+        if let (Some(krona_path), Some(krona_taxids)) = (&self.scrubby.config.krona, krona_taxids) {
+            if matches!(classifier, Some(Classifier::Kraken2)) {
+                let entries = build_krona_entries(report, &krona_taxids)?;
+                write_krona_report(&entries, krona_path)?;
+            }
         }
+
+        Ok(read_ids)
     }
-    fn run_minimap2(&self) -> Result<(), ScrubbyError> {
+    /// Executes the low-complexity (DUST) read filtering process.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), ScrubbyError>` - Ok if the filtering process completes successfully, otherwise an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// cleaner.run_complexity().unwrap();
+    /// ```
+    pub fn run_complexity(&self) -> Result<(), ScrubbyError> {
+        let method = self.scrubby.config.complexity_method.clone();
+        let threshold = match method {
+            ComplexityMethod::Dust => self.scrubby.config.max_dust
+                .unwrap_or_else(|| crate::complexity::entropy_to_dust_cutoff(self.scrubby.config.min_entropy)),
+            ComplexityMethod::Entropy => self.scrubby.config.min_entropy,
+        };
+        let filter = ComplexityFilter::with_method(
+            method.clone(),
+            threshold,
+            self.scrubby.config.complexity_window,
+        );
+        let read_ids = filter.low_complexity_reads(&self.scrubby.input)?;
+
+        if self.scrubby.config.audit {
+            let method_name = match method {
+                ComplexityMethod::Dust => "dust",
+                ComplexityMethod::Entropy => "entropy",
+            };
+            self.audit.borrow_mut().record(&read_ids, "complexity", method_name, None);
+        }
+
+        self.clean_reads(&read_ids)?;
+
+        Ok(())
+    }
+    /// Deplete/extract reads via FracMinHash sketch containment, mirroring
+    /// `run_complexity`'s shape: a read-level filter computed entirely
+    /// in-process, with no external aligner/classifier index required.
+    pub fn run_sketch(&self) -> Result<(), ScrubbyError> {
+        let index = self.scrubby.config.sketch_index.as_ref().ok_or(ScrubbyError::MissingSketchIndex)?;
+        let reference = FracMinHashSketch::from_json(index)?;
+        let filter = SketchFilter::new(
+            reference,
+            self.scrubby.config.min_containment,
+            self.scrubby.config.sketch_min_hashes,
+        );
+        let read_ids = filter.sketch_contained_reads(&self.scrubby.input)?;
+
+        if self.scrubby.config.audit {
+            self.audit.borrow_mut().record(&read_ids, "sketch", &index.display().to_string(), None);
+        }
+
+        self.clean_reads(&read_ids)?;
+
+        Ok(())
+    }
+    /// Writes the accumulated audit trail to the configured TSV/JSON paths, if any.
+    pub fn write_audit(&self) -> Result<(), ScrubbyError> {
+        let audit = self.audit.borrow();
+        if let Some(path) = &self.scrubby.config.audit_tsv {
+            audit.write_tsv(path)?;
+        }
+        if let Some(path) = &self.scrubby.config.audit_json {
+            audit.write_json(path)?;
+        }
+        Ok(())
+    }
+    /// Builds the summary `ScrubbyReport`, attaching the per-taxid removal
+    /// `breakdown` accumulated in `self.stats` and the abundance table
+    /// accumulated in `self.abundance` over the classifier path.
+    pub fn create_report(&self) -> Result<ScrubbyReport, ScrubbyError> {
+        ScrubbyReport::create(&self.scrubby, true, Some(&self.stats.borrow()), self.abundance.borrow().clone(), self.ensemble.borrow().clone(), self.provenance.borrow().clone())
+    }
+    /// Reads observed by the most recently completed depletion stage, used by
+    /// `Scrubby::clean_async` to report progress as each stage finishes.
+    pub fn reads_processed(&self) -> u64 {
+        self.stats.borrow().latest_reads_processed()
+    }
+    /// Re-estimates per-taxon abundance at `--bracken-level` directly from the
+    /// classifier report and writes it to `output` (`--bracken-report`),
+    /// caching the table in `self.abundance` so `create_report` can fold it
+    /// into the `--json` summary.
+    pub fn run_bracken_report(&self, output: &PathBuf) -> Result<(), ScrubbyError> {
+        let report = self.scrubby.config.report.clone().ok_or(ScrubbyError::MissingClassifierClassificationReport)?;
+        let level = self.scrubby.config.bracken_level.as_deref().unwrap_or("species");
+
+        let records = estimate_abundance(&report, level)?;
+        write_abundance_tsv(&records, output)?;
+        *self.abundance.borrow_mut() = records;
+
+        Ok(())
+    }
+    /// Writes the accumulated depletion statistics report to the configured TSV/JSON paths, if any.
+    pub fn write_stats(&self) -> Result<(), ScrubbyError> {
+        let stats = self.stats.borrow();
+        if let Some(path) = &self.scrubby.config.stats_tsv {
+            stats.write_tsv(path)?;
+        }
+        if let Some(path) = &self.scrubby.config.stats_json {
+            stats.write_json(path)?;
+        }
+        Ok(())
+    }
+    /// Writes the final `{"type":"summary",...}` record to the `--ndjson`
+    /// destination opened in `from_scrubby`, if one was configured.
+    pub fn write_ndjson_summary(&self, report: &ScrubbyReport) -> Result<(), ScrubbyError> {
+        if let Some(writer) = self.ndjson.borrow_mut().as_mut() {
+            writer.write_summary(report)?;
+        }
+        Ok(())
+    }
+    /// Writes a `{"type":"progress",...}` record to the `--ndjson` destination,
+    /// if one was configured. `start` anchors the elapsed time reported.
+    fn emit_progress(&self, reads_in: u64, reads_removed: u64, start: Instant) -> Result<(), ScrubbyError> {
+        if let Some(writer) = self.ndjson.borrow_mut().as_mut() {
+            writer.write_progress(reads_in, reads_removed, start.elapsed().as_millis())?;
+        }
+        Ok(())
+    }
+    fn run_minimap2(&self) -> Result<HashSet<String>, ScrubbyError> {
         let aligner_args = self.scrubby.config.aligner_args.as_deref().unwrap_or("");
         let alignment_index = self.scrubby.config.aligner_index.as_ref().ok_or(ScrubbyError::MissingAlignmentIndex)?;
         let aligner_preset = self.scrubby.config.preset.clone().ok_or(ScrubbyError::MissingMinimap2Preset)?;
 
         let cmd = if self.scrubby.config.paired_end {
             format!(
-                "minimap2 -ax {aligner_preset} --secondary=no -t {} {} '{}' '{}' '{}' | {}",
-                self.scrubby.threads,
+                "minimap2 -ax {aligner_preset} --secondary=no -t {} {} '{}' '{}' '{}'",
+                self.job_threads(),
                 aligner_args,
                 alignment_index.display(),
                 self.scrubby.input[0].display(),
                 self.scrubby.input[1].display(),
-                self.samtools.get_pipeline()
             )
         } else {
             format!(
-                "minimap2 -ax {aligner_preset} --secondary=no -t {} {} '{}' '{}' | {}",
-                self.scrubby.threads,
+                "minimap2 -ax {aligner_preset} --secondary=no -t {} {} '{}' '{}'",
+                self.job_threads(),
                 aligner_args,
                 alignment_index.display(),
                 self.scrubby.input[0].display(),
-                self.samtools.get_pipeline()
             )
         };
-        self.run_command(&cmd)?;
 
-        Ok(())
+        self.run_aligner_cmd(&cmd)
     }
-    fn run_minigraph(&self) -> Result<(), ScrubbyError> {
+    fn run_minigraph(&self) -> Result<HashSet<String>, ScrubbyError> {
         let aligner_args = self.scrubby.config.aligner_args.as_deref().unwrap_or("");
         let alignment_index = self.scrubby.config.aligner_index.as_ref().ok_or(ScrubbyError::MissingAlignmentIndex)?;
         let aligner_preset = self.scrubby.config.preset.clone().ok_or(ScrubbyError::MissingMinigraphPreset)?;
@@ -417,7 +1236,7 @@ impl Cleaner {
         let cmd = if self.scrubby.config.paired_end {
             format!(
                 "minigraph -x {aligner_preset} -N 0 -t {} {} '{}' '{}' '{}'",
-                self.scrubby.threads,
+                self.job_threads(),
                 aligner_args,
                 alignment_index.display(),
                 self.scrubby.input[0].display(),
@@ -426,29 +1245,25 @@ impl Cleaner {
         } else {
             format!(
                 "minigraph -x {aligner_preset} -N 0 -t {} {} '{}' '{}'",
-                self.scrubby.threads,
+                self.job_threads(),
                 aligner_args,
                 alignment_index.display(),
                 self.scrubby.input[0].display()
             )
         };
 
-        self.clean_reads(
-        &self.run_command_stdout_paf(&cmd)?
-        )?;
-
-        Ok(())
+        self.run_command_stdout_paf(&cmd)
     }
     #[cfg(feature = "mm2")]
-    fn run_minimap2_rs(&self) -> Result<(), ScrubbyError> {
-
-        // Implementation is not quite correct as we are essentially collecting the sequences first 
-        // and then push them into a multithreaded alignment step - ideally the alignment threads
-        // should continously read from the sequence reader queues?
+    fn run_minimap2_rs(&self) -> Result<HashSet<String>, ScrubbyError> {
 
+        // Sequences are streamed through a bounded channel so alignment workers
+        // can start mapping while the reader threads are still producing, rather
+        // than collecting every read into memory first. The bound applies
+        // back-pressure on the readers once the worker pool falls behind.
         let aligner_preset = self.scrubby.config.preset.clone().ok_or(ScrubbyError::MissingMinimap2Preset)?;
-        
-        let (sequence_sender, sequence_receiver) = channel::unbounded();
+
+        let (sequence_sender, sequence_receiver) = channel::bounded(self.job_threads() * 4);
 
         let aligner = minimap2::Aligner::builder();
 
@@ -471,7 +1286,7 @@ impl Cleaner {
         
         let aligner = aligner
             .with_cigar()
-            .with_index_threads(self.scrubby.threads)
+            .with_index_threads(self.job_threads())
             .with_index(
                 self.scrubby.config.aligner_index.clone().ok_or(
                     ScrubbyError::MissingAlignmentIndex
@@ -481,12 +1296,34 @@ impl Cleaner {
             ScrubbyError::Minimap2RustAlignerBuilderFailed(err.to_string())
         })?;
 
-        let sequence_sender = Arc::new(Mutex::new(sequence_sender));
+        let (hit_sender, hit_receiver) = channel::unbounded();
+
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(self.job_threads()).build()?;
+
+        pool.scope(|s| {
+
+            // Worker pool: each worker loops on `recv()` for as long as any reader
+            // still holds a sender clone, mapping sequences as they arrive instead
+            // of waiting for the readers to finish first.
+            for _ in 0..self.job_threads() {
+                let sequence_receiver = sequence_receiver.clone();
+                let hit_sender = hit_sender.clone();
+                let aligner = &aligner;
+                s.spawn(move |_| {
+                    while let Ok((id, sequence)) = sequence_receiver.recv() {
+                        let hit = aligner.map(&sequence, false, false, None, None)
+                            .map_err(|err| ScrubbyError::Minimap2RustAlignmentFailed(err.to_string()))
+                            .map(|mappings| (!mappings.is_empty()).then_some(id));
+                        hit_sender.send(hit).expect("Failed to send alignment result");
+                    }
+                });
+            }
+            // Drop our own clones so the channels disconnect once the readers
+            // (below) and this function's local clones are the only ones left.
+            drop(hit_sender);
 
-        rayon::scope(|s| {
-            
             let reads_1 = self.scrubby.input[0].clone();
-            let sequence_sender_clone = Arc::clone(&sequence_sender);
+            let sequence_sender_clone = sequence_sender.clone();
 
             s.spawn(move |_| {
 
@@ -496,7 +1333,7 @@ impl Cleaner {
                     if let Some(mut reader) = reader {
                         while let Some(rec) = reader.next() {
                             let record = rec?;
-                            sequence_sender_clone.lock().unwrap().send(
+                            sequence_sender_clone.send(
                                 (get_id(record.id())?, record.seq().to_vec())
                             ).expect("Failed to send sequence (R1)");
                         }
@@ -512,9 +1349,9 @@ impl Cleaner {
             });
 
             if self.scrubby.config.paired_end {
-                
+
                 let reads_2 = self.scrubby.input[1].clone();
-                let sequence_sender_clone = Arc::clone(&sequence_sender);
+                let sequence_sender_clone = sequence_sender.clone();
 
                 s.spawn(move |_| {
 
@@ -524,14 +1361,14 @@ impl Cleaner {
                         if let Some(mut reader) = reader {
                             while let Some(rec) = reader.next() {
                                 let record = rec?;
-                                sequence_sender_clone.lock().unwrap().send(
+                                sequence_sender_clone.send(
                                     (get_id(record.id())?, record.seq().to_vec())
                                 ).expect("Failed to send sequence (R2)");
                             }
                         } else {
                             log::warn!("Input file is empty: {}", reads_2.display())
                         }
-                        
+
                         Ok(())
 
                     })() {
@@ -539,113 +1376,151 @@ impl Cleaner {
                     }
                 });
             }
-        });
 
-        drop(sequence_sender);
-       
-        let results = rayon::ThreadPoolBuilder::new().num_threads(self.scrubby.threads).build()?.scope(|_| -> Result<_, ScrubbyError> {
-            let results = sequence_receiver
-                .iter()
-                .collect::<Vec<_>>()
-                .into_par_iter()
-                .map(|(id, sequence)| -> Result<_, ScrubbyError> {
-                    let mappings = aligner.map(&sequence, false, false, None, None).map_err(|err| ScrubbyError::Minimap2RustAlignmentFailed(err.to_string()))?;
-                    if mappings.len() > 0 {
-                        Ok(Some(id))
-                    } else {
-                        Ok(None)
-                    }
-                })
-                .collect::<Vec<_>>();
-                
-            Ok(results)
-        })?;
+            // Drop the sender template itself, so the channel only stays open
+            // for as long as the reader tasks above hold their own clones.
+            drop(sequence_sender);
+        });
 
         let mut read_ids = HashSet::new();
-        for result in results {
-            let result = result?;
-            if let Some(id) = result {
+        for hit in hit_receiver.iter() {
+            if let Some(id) = hit? {
                 read_ids.insert(id);
             }
         }
 
-        self.clean_reads(&read_ids)?;
-
-        Ok(())
+        Ok(read_ids)
     }
-    fn run_bowtie2(&self) -> Result<(), ScrubbyError> {
+    fn run_bowtie2(&self) -> Result<HashSet<String>, ScrubbyError> {
         let aligner_args = self.scrubby.config.aligner_args.as_deref().unwrap_or("");
         let alignment_index = self.scrubby.config.aligner_index.as_ref().ok_or(ScrubbyError::MissingAlignmentIndex)?;
 
         let cmd = if self.scrubby.config.paired_end {
             format!(
-                "bowtie2 -x '{}' -1 '{}' -2 '{}' -k 1 --mm -p {} {} | {}",
+                "bowtie2 -x '{}' -1 '{}' -2 '{}' -k 1 --mm -p {} {}",
                 alignment_index.display(),
                 self.scrubby.input[0].display(),
                 self.scrubby.input[1].display(),
-                self.scrubby.threads,
+                self.job_threads(),
                 aligner_args,
-                self.samtools.get_pipeline()
             )
         } else {
             format!(
-                "bowtie2 -x '{}' -U '{}' -k 1 --mm -p {} {} | {} ",
+                "bowtie2 -x '{}' -U '{}' -k 1 --mm -p {} {}",
                 alignment_index.display(),
                 self.scrubby.input[0].display(),
-                self.scrubby.threads,
+                self.job_threads(),
                 aligner_args,
-                self.samtools.get_pipeline()
             )
         };
-        self.run_command(&cmd)?;
-
-        Ok(())
 
+        self.run_aligner_cmd(&cmd)
     }
-    fn run_strobealign(&self) -> Result<(), ScrubbyError> {
+    fn run_strobealign(&self) -> Result<HashSet<String>, ScrubbyError> {
         let aligner_args = self.scrubby.config.aligner_args.as_deref().unwrap_or("");
         let alignment_index = self.scrubby.config.aligner_index.as_ref().ok_or(ScrubbyError::MissingAlignmentIndex)?;
 
-
         let cmd = if self.scrubby.config.paired_end {
             format!(
-                "strobealign -t {} {} '{}' '{}' '{}' | {}",
-                self.scrubby.threads,
+                "strobealign -t {} {} '{}' '{}' '{}'",
+                self.job_threads(),
                 aligner_args,
                 alignment_index.display(),
                 self.scrubby.input[0].display(),
                 self.scrubby.input[1].display(),
-                self.samtools.get_pipeline()
             )
         } else {
             format!(
-                "strobealign -t {} {} '{}' '{}' | {}",
-                self.scrubby.threads,
+                "strobealign -t {} {} '{}' '{}'",
+                self.job_threads(),
                 aligner_args,
                 alignment_index.display(),
                 self.scrubby.input[0].display(),
-                self.samtools.get_pipeline(),
             )
         };
-        self.run_command(&cmd)?;
 
-        Ok(())
+        self.run_aligner_cmd(&cmd)
+    }
+    /// Runs a bare aligner command (no `samtools` suffix) and resolves its
+    /// SAM/BAM output to the set of mapped read identifiers, either through
+    /// an in-process `rust_htslib` parser (when `native_bam` is configured
+    /// and the `htslib` feature is compiled in) or by piping the aligner
+    /// through the `samtools` pre-filter and parsing its SAM stdout.
+    fn run_aligner_cmd(&self, aligner_cmd: &str) -> Result<HashSet<String>, ScrubbyError> {
+        #[cfg(feature = "htslib")]
+        if self.scrubby.config.native_bam {
+            return self.run_aligner_native_bam(aligner_cmd);
+        }
+
+        self.run_command_stdout_sam(&format!("{} | samtools view -hF 12 -", aligner_cmd))
+    }
+    /// Redirects `aligner_cmd`'s SAM output to a temporary file and filters it
+    /// in-process, bypassing the `samtools` shell pipeline entirely.
+    #[cfg(feature = "htslib")]
+    fn run_aligner_native_bam(&self, aligner_cmd: &str) -> Result<HashSet<String>, ScrubbyError> {
+        let temp_dir = match &self.scrubby.workdir {
+            Some(path) => Builder::new().tempdir_in(path)?,
+            None => TempDir::new()?,
+        };
+
+        let sam_path = temp_dir.path().join("aligned.sam");
+
+        self.run_command(&format!("{} > '{}'", aligner_cmd, sam_path.display()))?;
+
+        let mapped_reads = self.read_mapped_from_sam(&sam_path)?;
+
+        temp_dir.close()?;
+
+        Ok(mapped_reads)
     }
+    /// Reads a SAM file and collects the identifiers of reads considered
+    /// "mapped", mirroring the flag bits `samtools view -hF 12` tests: a read
+    /// is mapped only if it is not itself unmapped and, for paired-end input,
+    /// its mate is not unmapped either.
+    #[cfg(feature = "htslib")]
+    fn read_mapped_from_sam(&self, path: &PathBuf) -> Result<HashSet<String>, ScrubbyError> {
+        let mut reader = bam::Reader::from_path(path)?;
+        let mut mapped_reads = HashSet::new();
+
+        for result in reader.records() {
+            let record = result?;
+            let unmapped = record.is_unmapped()
+                || (self.scrubby.config.paired_end && record.is_mate_unmapped());
+            if !unmapped {
+                mapped_reads.insert(from_utf8(record.qname())?.to_string());
+            }
+        }
+
+        Ok(mapped_reads)
+    }
+    /// Runs `cmd`, streaming its stderr line-by-line into the debug log as it
+    /// arrives rather than buffering the whole thing until the tool exits, so
+    /// a long `run_kraken`/`run_minimap2`/etc. invocation surfaces its own
+    /// progress output live instead of going silent until it finishes.
     fn run_command(&self, cmd: &str) -> Result<(), ScrubbyError> {
         log::debug!("Running command: {}", cmd);
 
-        let status = Command::new("sh")
+        let mut child = Command::new("sh")
             .arg("-c")
             .arg(cmd)
-            .stderr(Stdio::null())
-            .status()
+            .stderr(Stdio::piped())
+            .spawn()
             .map_err(|e| ScrubbyError::CommandExecutionFailed(cmd.to_string(), e.to_string()))?;
 
-        if !status.success() {
-            return Err(ScrubbyError::CommandFailed(cmd.to_string(), status.code().unwrap_or(-1)));
-        }
+        let stderr = child.stderr.take().ok_or_else(|| {
+            ScrubbyError::CommandExecutionFailed(cmd.to_string(), "Failed to capture stderr".to_string())
+        })?;
+        let stderr_handle = std::thread::spawn(move || -> Vec<u8> {
+            let mut buf = Vec::new();
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                log::debug!("{line}");
+                buf.extend_from_slice(line.as_bytes());
+                buf.push(b'\n');
+            }
+            buf
+        });
 
-        Ok(())
+        self.reap_piped_child(child, stderr_handle, cmd)
     }
 
     fn run_command_stdout_paf(&self, cmd: &str) -> Result<HashSet<String>, ScrubbyError> {
@@ -655,60 +1530,258 @@ impl Cleaner {
             .arg("-c")
             .arg(cmd)
             .stdout(Stdio::piped())
-            .stderr(Stdio::null())
+            .stderr(Stdio::piped())
             .spawn()
             .map_err(|e| ScrubbyError::CommandExecutionFailed(cmd.to_string(), e.to_string()))?;
 
         let stdout = child.stdout.take().ok_or_else(|| {
             ScrubbyError::CommandExecutionFailed(cmd.to_string(), "Failed to capture stdout".to_string())
         })?;
+        let stderr = child.stderr.take().ok_or_else(|| {
+            ScrubbyError::CommandExecutionFailed(cmd.to_string(), "Failed to capture stderr".to_string())
+        })?;
+        let stderr_handle = std::thread::spawn(move || -> Vec<u8> {
+            let mut buf = Vec::new();
+            let _ = std::io::Read::read_to_end(&mut BufReader::new(stderr), &mut buf);
+            buf
+        });
 
-        let reader = BufReader::new(stdout);
+        let mut reads: std::collections::HashMap<String, PafReadAccumulator> = std::collections::HashMap::new();
+        let parse_result = (|| -> Result<(), ScrubbyError> {
+            let reader = BufReader::new(stdout);
+            for (index, line) in reader.lines().enumerate() {
+                let line = line.map_err(|e| ScrubbyError::CommandExecutionFailed(cmd.to_string(), e.to_string()))?;
+                let context = ParseContext::new(PathBuf::from(format!("<stdout: {cmd}>")), (index + 1) as u64);
+                let record = PafRecord::from_str(&line, &context)?;
+                reads
+                    .entry(record.qname.clone())
+                    .and_modify(|accumulator| accumulator.add(&record))
+                    .or_insert_with(|| PafReadAccumulator::new(&record));
+            }
+            Ok(())
+        })();
+
+        // The reader above is dropped here, closing our end of the stdout pipe
+        // before we wait on the child, so it is always reaped - even if parsing
+        // returned early on an error - rather than leaking a zombie process.
+        self.reap_piped_child(child, stderr_handle, cmd)?;
+        parse_result?;
+
+        let mapped_reads = reads
+            .into_iter()
+            .filter(|(_, accumulator)| {
+                accumulator.passes_filters(
+                    self.scrubby.config.min_query_length,
+                    self.scrubby.config.min_query_coverage,
+                    self.scrubby.config.min_mapq,
+                    &self.scrubby.config.paf_filter_mode,
+                    self.scrubby.config.min_identity,
+                )
+            })
+            .map(|(qname, _)| qname)
+            .collect();
 
-        let mut mapped_reads = HashSet::new();
-        for line in reader.lines() {
-            let line = line.map_err(|e| ScrubbyError::CommandExecutionFailed(cmd.to_string(), e.to_string()))?;
-            let record = PafRecord::from_str(&line)?;
-            if (record.query_aligned_length() >= self.scrubby.config.min_query_length
-                || record.query_coverage() >= self.scrubby.config.min_query_coverage)
-                && record.mapq >= self.scrubby.config.min_mapq
-            {
-                mapped_reads.insert(record.qname);
+        Ok(mapped_reads)
+    }
+
+    /// Runs `cmd` (expected to emit SAM on stdout, e.g. an aligner piped
+    /// through the `samtools` pre-filter) and collects the identifiers of
+    /// reads present in the output, skipping `@`-prefixed header lines.
+    /// Mirrors `run_command_stdout_paf`, but for the SAM output of the
+    /// `samtools`-based aligner path rather than `minigraph`'s PAF output.
+    fn run_command_stdout_sam(&self, cmd: &str) -> Result<HashSet<String>, ScrubbyError> {
+        log::debug!("Running command: {}", cmd);
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(cmd)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ScrubbyError::CommandExecutionFailed(cmd.to_string(), e.to_string()))?;
+
+        let stdout = child.stdout.take().ok_or_else(|| {
+            ScrubbyError::CommandExecutionFailed(cmd.to_string(), "Failed to capture stdout".to_string())
+        })?;
+        let stderr = child.stderr.take().ok_or_else(|| {
+            ScrubbyError::CommandExecutionFailed(cmd.to_string(), "Failed to capture stderr".to_string())
+        })?;
+        let stderr_handle = std::thread::spawn(move || -> Vec<u8> {
+            let mut buf = Vec::new();
+            let _ = std::io::Read::read_to_end(&mut BufReader::new(stderr), &mut buf);
+            buf
+        });
+
+        let mut read_ids = HashSet::new();
+        let parse_result = (|| -> Result<(), ScrubbyError> {
+            let reader = BufReader::new(stdout);
+            for line in reader.lines() {
+                let line = line.map_err(|e| ScrubbyError::CommandExecutionFailed(cmd.to_string(), e.to_string()))?;
+                if line.starts_with('@') {
+                    continue;
+                }
+                if let Some(qname) = line.split('\t').next() {
+                    read_ids.insert(qname.to_string());
+                }
             }
-        }
+            Ok(())
+        })();
 
-        let status = child.wait().map_err(|e| ScrubbyError::CommandExecutionFailed(cmd.to_string(), e.to_string()))?;
+        // The reader above is dropped here, closing our end of the stdout pipe
+        // before we wait on the child, so it is always reaped - even if parsing
+        // returned early on an error - rather than leaking a zombie process.
+        self.reap_piped_child(child, stderr_handle, cmd)?;
+        parse_result?;
 
-        if !status.success() {
-            return Err(ScrubbyError::CommandFailed(cmd.to_string(), status.code().unwrap_or(-1)));
+        Ok(read_ids)
+    }
+
+    /// Waits for a child spawned by `run_command_stdout_paf` / `run_command_stdout_sam`
+    /// to exit and turns a non-zero status into a `ScrubbyError::CommandError` carrying
+    /// the captured stderr tail - unless the non-zero status is the result of us having
+    /// already closed our end of the stdout pipe (a broken pipe / SIGPIPE), in which case
+    /// the child is considered successfully reaped rather than failed.
+    fn reap_piped_child(&self, mut child: Child, stderr_handle: std::thread::JoinHandle<Vec<u8>>, cmd: &str) -> Result<(), ScrubbyError> {
+        let status = child.wait().map_err(|e| ScrubbyError::CommandExecutionFailed(cmd.to_string(), e.to_string()))?;
+        let stderr_buf = stderr_handle.join().unwrap_or_default();
+
+        if !status.success() && !Self::is_broken_pipe_exit(&status) {
+            return Err(ScrubbyError::CommandError {
+                program: "sh -c".to_string(),
+                args: vec![cmd.to_string()],
+                status: status.code().unwrap_or(-1),
+                stderr: bounded_stderr_tail(&stderr_buf),
+            });
         }
 
-        Ok(mapped_reads)
+        Ok(())
+    }
+
+    /// Returns true if `status` is the conventional `sh -c` exit code for a child
+    /// killed by `SIGPIPE` (128 + 13), which happens when we stop reading stdout
+    /// before the process has finished writing to it - not a genuine tool failure.
+    fn is_broken_pipe_exit(status: &std::process::ExitStatus) -> bool {
+        status.code() == Some(141)
+    }
+}
+
+/// Read and base counts accumulated while streaming through a FASTQ file (or
+/// pair) in `FastqCleaner::clean_reads` / `PairedFastqCleaner::clean_reads`.
+/// `reads_in`/`bases_in` cover everything seen; `reads_out`/`bases_out` cover
+/// only what was written to the primary output (depleted or extracted,
+/// depending on the mode the caller ran with).
+#[derive(Default, Clone, Copy, Debug)]
+pub struct CleanCounts {
+    pub reads_in: u64,
+    pub reads_out: u64,
+    pub bases_in: u64,
+    pub bases_out: u64,
+}
+
+impl CleanCounts {
+    /// Number of reads not written to the primary output.
+    pub fn reads_removed(&self) -> u64 {
+        self.reads_in - self.reads_out
     }
 }
 
+/// Splits a single interleaved FASTQ (alternating R1/R2 records) at `input`
+/// into separate `output_r1`/`output_r2` streams, so the rest of the pipeline
+/// can treat it as an ordinary paired-end pair. Both halves are written
+/// uncompressed, since they are intermediate files immediately consumed by
+/// the configured aligner/classifier.
+fn deinterleave_fastq(input: &PathBuf, output_r1: &PathBuf, output_r2: &PathBuf) -> Result<(), ScrubbyError> {
+    let uncompressed = Compression::new(CompressionAlgorithm::Uncompressed, None)?;
+    let mut writer_r1 = build_output_writer(output_r1, uncompressed, 1)?;
+    let mut writer_r2 = build_output_writer(output_r2, uncompressed, 1)?;
+
+    if let Some(mut reader) = parse_fastx_file_with_check(input)? {
+        let mut mate = 0u8;
+        while let Some(rec) = reader.next() {
+            let record = rec?;
+            if mate == 0 {
+                record.write(&mut writer_r1, None)?;
+            } else {
+                record.write(&mut writer_r2, None)?;
+            }
+            mate = 1 - mate;
+        }
+    } else {
+        log::warn!("Interleaved input file is empty: {}", input.display())
+    }
+
+    Ok(())
+}
+
 /// Structure for cleaning FASTQ files based on read IDs.
 pub struct FastqCleaner {
     input: PathBuf,
     output: PathBuf,
+    removed: Option<PathBuf>,
+    compression_format: Option<CompressionAlgorithm>,
+    compression_level: Option<u32>,
+    compression_threads: Option<usize>,
 }
 
 impl FastqCleaner {
-    /// Constructs a new `FastqCleaner` from the provided input and output paths.
+    /// Constructs a new `FastqCleaner` from the provided input, output and
+    /// optional removed-reads output paths.
     ///
     /// # Arguments
     ///
     /// * `input` - A reference to the input file path.
     /// * `output` - A reference to the output file path.
+    /// * `removed` - An optional reference to a path for the removed reads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::FastqCleaner;
+    /// let cleaner = FastqCleaner::from(&input_path, &output_path, None);
+    /// ```
+    pub fn from(input: &PathBuf, output: &PathBuf, removed: Option<&PathBuf>) -> Self {
+        Self {
+            input: input.to_owned(),
+            output: output.to_owned(),
+            removed: removed.cloned(),
+            compression_format: None,
+            compression_level: None,
+            compression_threads: None,
+        }
+    }
+    /// Overrides the inferred-from-extension output compression algorithm and level.
     ///
     /// # Example
     ///
     /// ```
     /// use scrubby::FastqCleaner;
-    /// let cleaner = FastqCleaner::from(&input_path, &output_path);
+    /// let cleaner = FastqCleaner::from(&input_path, &output_path, None).compression(None, None);
     /// ```
-    pub fn from(input: &PathBuf, output: &PathBuf) -> Self {
-        Self { input: input.to_owned(), output: output.to_owned() }
+    pub fn compression(mut self, format: Option<CompressionAlgorithm>, level: Option<u32>) -> Self {
+        self.compression_format = format;
+        self.compression_level = level;
+        self
+    }
+    /// Sets the number of worker threads used to compress output, enabling a
+    /// multithreaded BGZF writer when the output format is gzip.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::FastqCleaner;
+    /// let cleaner = FastqCleaner::from(&input_path, &output_path, None).compression_threads(Some(4));
+    /// ```
+    pub fn compression_threads(mut self, threads: Option<usize>) -> Self {
+        self.compression_threads = threads;
+        self
+    }
+    /// Resolves the compression algorithm and level to use for `path`, falling
+    /// back to inferring the algorithm from `path`'s extension when not overridden.
+    fn resolve_compression(&self, path: &PathBuf) -> Result<Compression, ScrubbyError> {
+        let algorithm = self.compression_format.unwrap_or_else(|| {
+            CompressionAlgorithm::from_extension(path.extension().and_then(|ext| ext.to_str()))
+        });
+        Compression::new(algorithm, self.compression_level)
     }
 
     /// Cleans reads from the input file and writes to the output file based on the provided read IDs.
@@ -717,45 +1790,283 @@ impl FastqCleaner {
     ///
     /// * `read_ids` - A reference to a set of read IDs to be cleaned.
     /// * `reverse` - A boolean indicating whether to reverse the cleaning process.
+    /// * `progress` - An optional callback invoked every `PROGRESS_INTERVAL` reads
+    ///   with `(reads_in, reads_removed)`, used to emit `--ndjson` progress records.
     ///
     /// # Returns
     ///
-    /// * `Result<(), ScrubbyError>` - Ok if the cleaning process completes successfully, otherwise an error.
+    /// * `Result<CleanCounts, ScrubbyError>` - The read and base counts seen and
+    ///   written to the primary `output` file.
     ///
     /// # Example
     ///
     /// ```
     /// let read_ids = HashSet::new();
-    /// cleaner.clean_reads(&read_ids, false).unwrap();
+    /// cleaner.clean_reads(&read_ids, false, None, None).unwrap();
     /// ```
-    pub fn clean_reads(&self, read_ids: &HashSet<String>, reverse: bool) -> Result<(), ScrubbyError> {
-        
+    pub fn clean_reads(
+        &self,
+        read_ids: &HashSet<String>,
+        reverse: bool,
+        normalizer: Option<&ReadIdNormalizer>,
+        mut progress: Option<&mut dyn FnMut(u64, u64) -> Result<(), ScrubbyError>>,
+    ) -> Result<CleanCounts, ScrubbyError> {
+
+        let normalized_read_ids = normalizer.map(|n| n.normalize_set(read_ids));
+        let read_ids = normalized_read_ids.as_ref().unwrap_or(read_ids);
+
         let reader = parse_fastx_file_with_check(&self.input)?;
 
+        let mut counts = CleanCounts::default();
+
         if let Some(mut reader) = reader {
-            let mut writer = get_fastx_writer(
-                &self.output, 
-                niffler::compression::Level::Six, 
-                None
-            )?;
-    
+            let threads = self.compression_threads.unwrap_or(1);
+            let output_compression = self.resolve_compression(&self.output)?;
+            let mut writer = build_output_writer(&self.output, output_compression, threads)?;
+            let mut removed_writer = self.removed.as_ref().map(|path| -> Result<_, ScrubbyError> {
+                let removed_compression = self.resolve_compression(path)?;
+                build_output_writer(path, removed_compression, threads)
+            }).transpose()?;
+
             while let Some(rec) = reader.next() {
                 let record = rec?;
-                let id = get_id(record.id())?;
-    
-                // Depletion 
+                counts.reads_in += 1;
+                counts.bases_in += record.seq().len() as u64;
+                let raw_id = get_id(record.id())?;
+                let id = match normalizer {
+                    Some(n) => n.normalize(&raw_id),
+                    None => raw_id,
+                };
+
+                // Depletion
                 if !reverse && !read_ids.contains(&id) {
                     record.write(&mut writer, None)?;
+                    counts.reads_out += 1;
+                    counts.bases_out += record.seq().len() as u64;
+                } else if !reverse {
+                    if let Some(ref mut removed_writer) = removed_writer {
+                        record.write(removed_writer, None)?;
+                    }
                 }
-                // Extraction 
+                // Extraction
                 if reverse && read_ids.contains(&id) {
                     record.write(&mut writer, None)?;
+                    counts.reads_out += 1;
+                    counts.bases_out += record.seq().len() as u64;
+                } else if reverse {
+                    if let Some(ref mut removed_writer) = removed_writer {
+                        record.write(removed_writer, None)?;
+                    }
+                }
+
+                if counts.reads_in % PROGRESS_INTERVAL == 0 {
+                    if let Some(ref mut callback) = progress {
+                        callback(counts.reads_in, counts.reads_removed())?;
+                    }
                 }
             };
         } else {
             log::warn!("Input file is empty: {}", self.input.display())
         }
-        
-        Ok(())
+
+        Ok(counts)
+    }
+}
+
+/// Structure for cleaning paired-end FASTQ files based on read IDs, making a
+/// single joint decision per read pair so the R1/R2 outputs never desync
+/// (for example when only one mate's identifier ends up in `read_ids`).
+pub struct PairedFastqCleaner {
+    input_r1: PathBuf,
+    input_r2: PathBuf,
+    output_r1: PathBuf,
+    output_r2: PathBuf,
+    removed_r1: Option<PathBuf>,
+    removed_r2: Option<PathBuf>,
+    compression_format: Option<CompressionAlgorithm>,
+    compression_level: Option<u32>,
+    compression_threads: Option<usize>,
+}
+
+impl PairedFastqCleaner {
+    /// Constructs a new `PairedFastqCleaner` from the provided R1/R2 input,
+    /// output and optional removed-reads output paths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::PairedFastqCleaner;
+    /// let cleaner = PairedFastqCleaner::from(
+    ///     &input_r1, &input_r2, &output_r1, &output_r2, None, None
+    /// );
+    /// ```
+    pub fn from(
+        input_r1: &PathBuf,
+        input_r2: &PathBuf,
+        output_r1: &PathBuf,
+        output_r2: &PathBuf,
+        removed_r1: Option<&PathBuf>,
+        removed_r2: Option<&PathBuf>,
+    ) -> Self {
+        Self {
+            input_r1: input_r1.to_owned(),
+            input_r2: input_r2.to_owned(),
+            output_r1: output_r1.to_owned(),
+            output_r2: output_r2.to_owned(),
+            removed_r1: removed_r1.cloned(),
+            removed_r2: removed_r2.cloned(),
+            compression_format: None,
+            compression_level: None,
+            compression_threads: None,
+        }
+    }
+    /// Overrides the inferred-from-extension output compression algorithm and level.
+    pub fn compression(mut self, format: Option<CompressionAlgorithm>, level: Option<u32>) -> Self {
+        self.compression_format = format;
+        self.compression_level = level;
+        self
+    }
+    /// Sets the number of worker threads used to compress output, enabling a
+    /// multithreaded BGZF writer when the output format is gzip.
+    pub fn compression_threads(mut self, threads: Option<usize>) -> Self {
+        self.compression_threads = threads;
+        self
+    }
+    /// Resolves the compression algorithm and level to use for `path`, falling
+    /// back to inferring the algorithm from `path`'s extension when not overridden.
+    fn resolve_compression(&self, path: &PathBuf) -> Result<Compression, ScrubbyError> {
+        let algorithm = self.compression_format.unwrap_or_else(|| {
+            CompressionAlgorithm::from_extension(path.extension().and_then(|ext| ext.to_str()))
+        });
+        Compression::new(algorithm, self.compression_level)
+    }
+
+    /// Cleans read pairs from the R1/R2 input files and writes them to the
+    /// R1/R2 output files based on the provided read IDs, deciding jointly
+    /// per pair so the two outputs stay in register.
+    ///
+    /// # Arguments
+    ///
+    /// * `read_ids` - A reference to a set of read IDs to be cleaned.
+    /// * `reverse` - A boolean indicating whether to reverse the cleaning process.
+    /// * `normalizer` - An optional normalizer stripping mate-specific suffixes
+    ///   (e.g. `/1`, `/2`, Illumina ` 1:N:0:...` comments) before membership
+    ///   in `read_ids` is tested, so the same pair is matched consistently
+    ///   regardless of which mate's suffix it originally carried.
+    /// * `progress` - An optional callback invoked every `PROGRESS_INTERVAL`
+    ///   pairs with `(reads_in, reads_removed)`, used to emit `--ndjson`
+    ///   progress records.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CleanCounts, ScrubbyError>` - The read-pair and base counts
+    ///   seen and written to the primary R1/R2 outputs (`reads_in`/`reads_out`
+    ///   count pairs, `bases_in`/`bases_out` sum both mates).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let read_ids = HashSet::new();
+    /// cleaner.clean_reads(&read_ids, false, None, None).unwrap();
+    /// ```
+    pub fn clean_reads(
+        &self,
+        read_ids: &HashSet<String>,
+        reverse: bool,
+        normalizer: Option<&ReadIdNormalizer>,
+        mut progress: Option<&mut dyn FnMut(u64, u64) -> Result<(), ScrubbyError>>,
+    ) -> Result<CleanCounts, ScrubbyError> {
+
+        let normalized_read_ids = normalizer.map(|n| n.normalize_set(read_ids));
+        let read_ids = normalized_read_ids.as_ref().unwrap_or(read_ids);
+
+        // Always stripped (independent of `--strip-suffix`) to check that the
+        // record read from R1 and R2 at each position are actually mates,
+        // catching a desynchronized pair of input files even when neither
+        // has simply run out of records.
+        let pair_normalizer = ReadIdNormalizer::new(crate::readid::DEFAULT_SUFFIX_PATTERN)
+            .expect("DEFAULT_SUFFIX_PATTERN is a valid static regex");
+
+        let reader_r1 = parse_fastx_file_with_check(&self.input_r1)?;
+        let reader_r2 = parse_fastx_file_with_check(&self.input_r2)?;
+
+        let mut counts = CleanCounts::default();
+
+        if let (Some(mut reader_r1), Some(mut reader_r2)) = (reader_r1, reader_r2) {
+            let threads = self.compression_threads.unwrap_or(1);
+
+            let output_compression_r1 = self.resolve_compression(&self.output_r1)?;
+            let output_compression_r2 = self.resolve_compression(&self.output_r2)?;
+            let mut writer_r1 = build_output_writer(&self.output_r1, output_compression_r1, threads)?;
+            let mut writer_r2 = build_output_writer(&self.output_r2, output_compression_r2, threads)?;
+
+            let mut removed_writer_r1 = self.removed_r1.as_ref().map(|path| -> Result<_, ScrubbyError> {
+                let removed_compression = self.resolve_compression(path)?;
+                build_output_writer(path, removed_compression, threads)
+            }).transpose()?;
+            let mut removed_writer_r2 = self.removed_r2.as_ref().map(|path| -> Result<_, ScrubbyError> {
+                let removed_compression = self.resolve_compression(path)?;
+                build_output_writer(path, removed_compression, threads)
+            }).transpose()?;
+
+            loop {
+                let rec_r1 = reader_r1.next();
+                let rec_r2 = reader_r2.next();
+
+                let (rec_r1, rec_r2) = match (rec_r1, rec_r2) {
+                    (Some(r1), Some(r2)) => (r1?, r2?),
+                    (None, None) => break,
+                    (Some(_), None) => return Err(ScrubbyError::MismatchedPairedReadCount(counts.reads_in + 1, counts.reads_in)),
+                    (None, Some(_)) => return Err(ScrubbyError::MismatchedPairedReadCount(counts.reads_in, counts.reads_in + 1)),
+                };
+
+                counts.reads_in += 1;
+                counts.bases_in += (rec_r1.seq().len() + rec_r2.seq().len()) as u64;
+
+                let raw_id_r1 = get_id(rec_r1.id())?;
+                let raw_id_r2 = get_id(rec_r2.id())?;
+
+                if pair_normalizer.normalize(&raw_id_r1) != pair_normalizer.normalize(&raw_id_r2) {
+                    return Err(ScrubbyError::MismatchedReadPair(counts.reads_in, raw_id_r1, raw_id_r2));
+                }
+
+                let (id_r1, id_r2) = match normalizer {
+                    Some(n) => (n.normalize(&raw_id_r1), n.normalize(&raw_id_r2)),
+                    None => (raw_id_r1, raw_id_r2),
+                };
+
+                let flagged = read_ids.contains(&id_r1) || read_ids.contains(&id_r2);
+
+                // Depletion: keep the pair only if neither mate is flagged.
+                // Extraction: keep the pair only if either mate is flagged.
+                if reverse == flagged {
+                    rec_r1.write(&mut writer_r1, None)?;
+                    rec_r2.write(&mut writer_r2, None)?;
+                    counts.reads_out += 1;
+                    counts.bases_out += (rec_r1.seq().len() + rec_r2.seq().len()) as u64;
+                } else {
+                    if let Some(ref mut removed_writer_r1) = removed_writer_r1 {
+                        rec_r1.write(removed_writer_r1, None)?;
+                    }
+                    if let Some(ref mut removed_writer_r2) = removed_writer_r2 {
+                        rec_r2.write(removed_writer_r2, None)?;
+                    }
+                }
+
+                if counts.reads_in % PROGRESS_INTERVAL == 0 {
+                    if let Some(ref mut callback) = progress {
+                        callback(counts.reads_in, counts.reads_removed())?;
+                    }
+                }
+            }
+        } else {
+            log::warn!(
+                "Input file is empty: {} or {}",
+                self.input_r1.display(),
+                self.input_r2.display()
+            )
+        }
+
+        Ok(counts)
     }
 }
@@ -0,0 +1,159 @@
+//! This module provides a low-complexity read filter used to identify and deplete
+//! reads dominated by short repeats (poly-A runs, microsatellites, homopolymers)
+//! before they reach a classifier or aligner, where they are a common source of
+//! spurious hits. It implements the symmetric-DUST algorithm used by `bbduk` and
+//! `prinseq`, exposed through a `--min-entropy` threshold so it stays consistent
+//! with the vocabulary those tools already use.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ScrubbyError;
+use crate::utils::{get_id, parse_fastx_file_with_check};
+
+/// Default sliding window size (bases) for the symmetric-DUST score.
+pub const DEFAULT_COMPLEXITY_WINDOW: usize = 64;
+/// Default minimum entropy threshold, matching `bbduk`'s `entropy=0.9` default.
+pub const DEFAULT_MIN_ENTROPY: f64 = 0.9;
+
+/// Scoring method backing the low-complexity filter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, clap::ValueEnum, schemars::JsonSchema)]
+pub enum ComplexityMethod {
+    /// Symmetric-DUST score over overlapping 3-mers, mapped from `--min-entropy`.
+    #[serde(rename="dust")]
+    Dust,
+    /// Shannon entropy over mono-nucleotide frequencies, compared directly to `--min-entropy`.
+    #[serde(rename="entropy")]
+    Entropy,
+}
+
+/// Computes the maximum symmetric-DUST score over sliding windows of `window` bases.
+///
+/// Within each window, counts the occurrences of every overlapping 3-mer and scores
+/// the window as `sum(c_t * (c_t - 1) / 2) / (window - 3)`. Reads shorter than
+/// `window` are scored as a single window covering the whole read.
+pub fn dust_score(seq: &[u8], window: usize) -> f64 {
+    if seq.len() < 4 {
+        return 0.0;
+    }
+
+    let window = window.min(seq.len());
+    let mut max_score: f64 = 0.0;
+    let mut start = 0;
+
+    loop {
+        let end = (start + window).min(seq.len());
+        let score = window_dust_score(&seq[start..end]);
+        if score > max_score {
+            max_score = score;
+        }
+        if end == seq.len() {
+            break;
+        }
+        start += 1;
+    }
+
+    max_score
+}
+
+fn window_dust_score(window: &[u8]) -> f64 {
+    if window.len() < 4 {
+        return 0.0;
+    }
+
+    let mut counts: HashMap<[u8; 3], u64> = HashMap::new();
+    for triplet in window.windows(3) {
+        let key = [
+            triplet[0].to_ascii_uppercase(),
+            triplet[1].to_ascii_uppercase(),
+            triplet[2].to_ascii_uppercase(),
+        ];
+        *counts.entry(key).or_insert(0) += 1;
+    }
+
+    let sum: u64 = counts.values().map(|&c| c * c.saturating_sub(1) / 2).sum();
+
+    sum as f64 / (window.len() as f64 - 3.0)
+}
+
+/// Maps a `bbduk`-style `--min-entropy` threshold (Shannon-style entropy normalised
+/// to `[0, 1]`, default `0.9`) onto an equivalent symmetric-DUST cutoff, so a single
+/// user-facing knob drives the DUST scorer: lower entropy thresholds tolerate
+/// higher DUST scores (more repetitive reads allowed through).
+pub fn entropy_to_dust_cutoff(min_entropy: f64) -> f64 {
+    (1.0 - min_entropy.clamp(0.0, 1.0)) * 100.0
+}
+
+/// Computes the Shannon entropy of `seq` over mono-nucleotide frequencies,
+/// normalised to `[0, 1]` (`1.0` is maximally diverse, `0.0` is a homopolymer).
+pub fn shannon_entropy(seq: &[u8]) -> f64 {
+    if seq.is_empty() {
+        return 1.0;
+    }
+
+    let mut counts: HashMap<u8, u64> = HashMap::new();
+    for base in seq {
+        *counts.entry(base.to_ascii_uppercase()).or_insert(0) += 1;
+    }
+
+    let len = seq.len() as f64;
+    let entropy: f64 = counts
+        .values()
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+
+    // Normalise by log2(4), the maximum entropy over the 4-letter DNA alphabet.
+    (entropy / 2.0).min(1.0)
+}
+
+/// Low-complexity read filter backed by the symmetric-DUST score or Shannon entropy.
+pub struct ComplexityFilter {
+    pub window: usize,
+    pub cutoff: f64,
+    pub method: ComplexityMethod,
+}
+
+impl ComplexityFilter {
+    /// Constructs a DUST filter from a `bbduk`-style minimum entropy threshold and window size.
+    pub fn new(min_entropy: f64, window: usize) -> Self {
+        Self { window, cutoff: entropy_to_dust_cutoff(min_entropy), method: ComplexityMethod::Dust }
+    }
+
+    /// Constructs a filter using the given `method`, where `threshold` is either a raw
+    /// DUST cutoff (`--max-dust`, method `Dust`) or a minimum Shannon entropy in `[0, 1]`
+    /// (`--min-entropy`, method `Entropy`) compared directly without DUST conversion.
+    pub fn with_method(method: ComplexityMethod, threshold: f64, window: usize) -> Self {
+        Self { window, cutoff: threshold, method }
+    }
+
+    /// Returns `true` if `seq` is flagged low-complexity under the configured method.
+    pub fn is_low_complexity(&self, seq: &[u8]) -> bool {
+        match self.method {
+            ComplexityMethod::Dust => dust_score(seq, self.window) > self.cutoff,
+            ComplexityMethod::Entropy => shannon_entropy(seq) < self.cutoff,
+        }
+    }
+
+    /// Scans the provided input read file(s) and returns the set of read identifiers
+    /// flagged as low-complexity, so they can be depleted or extracted through the
+    /// same `clean_reads` path used for classifier and aligner sources.
+    pub fn low_complexity_reads(&self, input: &[PathBuf]) -> Result<HashSet<String>, ScrubbyError> {
+        let mut flagged = HashSet::new();
+        for path in input {
+            if let Some(mut reader) = parse_fastx_file_with_check(path)? {
+                while let Some(rec) = reader.next() {
+                    let record = rec?;
+                    if self.is_low_complexity(&record.seq()) {
+                        flagged.insert(get_id(record.id())?);
+                    }
+                }
+            }
+        }
+        Ok(flagged)
+    }
+}
@@ -0,0 +1,375 @@
+//! Resolves the output compression algorithm and level for FASTQ writers, and
+//! provides a single transparent-decompression entry point for readers.
+//!
+//! Keeps the algorithm and level as two decoupled values - each algorithm has
+//! its own sensible default level and its own valid range, rather than a
+//! single hard-coded 1-9 scale applied to every format. Zstandard in
+//! particular supports a much wider range than the `bzip2`/`gzip`/`lzma`/`lz4`
+//! backends `niffler` otherwise wraps.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+use gzp::deflate::Bgzf;
+use gzp::par::compress::{ParCompress, ParCompressBuilder};
+use gzp::ZWriter;
+use needletail::FastxReader;
+use serde::{Serialize, Deserialize};
+
+use crate::error::ScrubbyError;
+
+/// Aliases accepted by `CompressionAlgorithm::from_str`, also used to build
+/// the "supported formats" hint in `ScrubbyError::InvalidCompressionFormat`.
+const SUPPORTED_ALIASES: &str = "g/gz/gzip, b/bz/bz2/bzip/bzip2, l/xz/lzma, z/zst/zstd/zstandard, 4/lz4, u/none/uncompressed";
+
+/// Output compression algorithm, a superset of `niffler::compression::Format`
+/// that additionally supports Zstandard.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    #[serde(rename = "gzip")]
+    Gzip,
+    #[serde(rename = "bzip")]
+    Bzip,
+    #[serde(rename = "lzma")]
+    Lzma,
+    #[serde(rename = "zstd")]
+    Zstd,
+    #[serde(rename = "lz4")]
+    Lz4,
+    #[serde(rename = "uncompressed")]
+    Uncompressed,
+}
+
+impl CompressionAlgorithm {
+    /// Sensible default compression level for this algorithm.
+    pub fn default_level(&self) -> u32 {
+        match self {
+            Self::Gzip => 6,
+            Self::Bzip => 6,
+            Self::Lzma => 6,
+            Self::Zstd => 3,
+            Self::Lz4 => 1,
+            Self::Uncompressed => 0,
+        }
+    }
+    /// Highest level this algorithm accepts.
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Self::Zstd => 19,
+            Self::Uncompressed => 0,
+            _ => 9,
+        }
+    }
+    /// Maps to the corresponding `niffler::compression::Format`.
+    pub fn niffler_format(&self) -> niffler::compression::Format {
+        match self {
+            Self::Gzip => niffler::compression::Format::Gzip,
+            Self::Bzip => niffler::compression::Format::Bzip,
+            Self::Lzma => niffler::compression::Format::Lzma,
+            Self::Zstd => niffler::compression::Format::Zstd,
+            Self::Lz4 => niffler::compression::Format::Lz4,
+            Self::Uncompressed => niffler::compression::Format::No,
+        }
+    }
+    /// Infers a format from a file extension, defaulting to uncompressed.
+    ///
+    /// Following the extension-sniffing approach common to archive tools like
+    /// `ouch`, this lets `--output cleaned.fq.zst` pick zstd without an
+    /// explicit `--compression-format`.
+    pub fn from_extension(ext: Option<&str>) -> Self {
+        match ext.map(|ext| ext.to_lowercase()).as_deref() {
+            Some("gz") => Self::Gzip,
+            Some("bz") | Some("bz2") => Self::Bzip,
+            Some("xz") | Some("lzma") => Self::Lzma,
+            Some("zst") | Some("zstd") => Self::Zstd,
+            Some("lz4") => Self::Lz4,
+            _ => Self::Uncompressed,
+        }
+    }
+}
+impl FromStr for CompressionAlgorithm {
+    type Err = ScrubbyError;
+
+    /// Parses a compression format from a short letter or a full extension
+    /// alias, case-insensitively (e.g. `"g"`, `"gz"` and `"gzip"` all resolve
+    /// to `Gzip`). An explicit `--compression-format` parsed this way always
+    /// overrides the extension inferred from the output path.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "g" | "gz" | "gzip" => Ok(Self::Gzip),
+            "b" | "bz" | "bz2" | "bzip" | "bzip2" => Ok(Self::Bzip),
+            "l" | "xz" | "lzma" => Ok(Self::Lzma),
+            "z" | "zst" | "zstd" | "zstandard" => Ok(Self::Zstd),
+            "4" | "lz4" => Ok(Self::Lz4),
+            "u" | "none" | "uncompressed" => Ok(Self::Uncompressed),
+            _ => Err(ScrubbyError::InvalidCompressionFormat(s.to_string(), SUPPORTED_ALIASES)),
+        }
+    }
+}
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gzip => write!(f, "gzip"),
+            Self::Bzip => write!(f, "bzip"),
+            Self::Lzma => write!(f, "lzma"),
+            Self::Zstd => write!(f, "zstd"),
+            Self::Lz4 => write!(f, "lz4"),
+            Self::Uncompressed => write!(f, "uncompressed"),
+        }
+    }
+}
+
+/// A validated `(algorithm, level)` pair used to configure an output writer.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Compression {
+    pub algorithm: CompressionAlgorithm,
+    pub level: u32,
+}
+
+impl Compression {
+    /// Builds a `Compression`, filling in the algorithm's default level when
+    /// none is given and rejecting a level outside the algorithm's range.
+    pub fn new(algorithm: CompressionAlgorithm, level: Option<u32>) -> Result<Self, ScrubbyError> {
+        let level = level.unwrap_or_else(|| algorithm.default_level());
+        if level > algorithm.max_level() {
+            return Err(ScrubbyError::InvalidCompressionLevel(level, algorithm.max_level(), algorithm));
+        }
+        Ok(Self { algorithm, level })
+    }
+
+    /// Converts to the `niffler::compression::Level` used by the `niffler`-backed
+    /// writer path in `build_output_writer`.
+    ///
+    /// `niffler` itself only exposes a `One`..`Nine` scale, so any level above 9
+    /// is clamped to `Nine` here - the `Compression::level` field (and the
+    /// validation in `new`) still preserves and enforces the user's requested
+    /// value for anything reading it back (e.g. the JSON summary). This only
+    /// matters for bzip/lzma/lz4, since `build_output_writer` routes Zstandard
+    /// through the `zstd` crate's own encoder instead, which honours the full
+    /// 1-19 range directly.
+    pub fn niffler_level(&self) -> niffler::compression::Level {
+        match self.level.min(9) {
+            1 => niffler::compression::Level::One,
+            2 => niffler::compression::Level::Two,
+            3 => niffler::compression::Level::Three,
+            4 => niffler::compression::Level::Four,
+            5 => niffler::compression::Level::Five,
+            6 => niffler::compression::Level::Six,
+            7 => niffler::compression::Level::Seven,
+            8 => niffler::compression::Level::Eight,
+            _ => niffler::compression::Level::Nine,
+        }
+    }
+}
+
+/// Output writer returned by `build_output_writer`.
+///
+/// Hides whether the underlying encoder is `niffler`'s single-threaded path
+/// or a parallel BGZF stream (via `gzp`), the latter of which must be
+/// explicitly finished (flushing any buffered blocks) rather than relying on
+/// `Drop` to do the right thing - so `Drop` here calls `finish` itself.
+pub enum OutputWriter {
+    Niffler(Box<dyn Write>),
+    Bgzf(Box<ParCompress<Bgzf>>),
+    Zstd(Option<zstd::Encoder<'static, std::fs::File>>),
+}
+
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Niffler(writer) => writer.write(buf),
+            Self::Bgzf(writer) => writer.write(buf),
+            Self::Zstd(writer) => writer.as_mut().expect("zstd writer already finished").write(buf),
+        }
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Niffler(writer) => writer.flush(),
+            Self::Bgzf(writer) => writer.flush(),
+            Self::Zstd(writer) => writer.as_mut().expect("zstd writer already finished").flush(),
+        }
+    }
+}
+
+impl Drop for OutputWriter {
+    fn drop(&mut self) {
+        match self {
+            Self::Bgzf(writer) => {
+                if let Err(e) = writer.finish() {
+                    log::error!("failed to finalize BGZF output: {}", e);
+                }
+            }
+            Self::Zstd(writer) => {
+                // `zstd::Encoder::finish` writes the frame epilogue (content
+                // checksum, end-of-frame marker) that a plain `flush`/`Drop`
+                // of the inner `File` would not - without it the file is a
+                // truncated, unterminated zstd stream that most decoders
+                // refuse to read.
+                if let Some(encoder) = writer.take() {
+                    if let Err(e) = encoder.finish() {
+                        log::error!("failed to finalize zstd output: {}", e);
+                    }
+                }
+            }
+            Self::Niffler(_) => {}
+        }
+    }
+}
+
+/// Builds the output writer for `path`.
+///
+/// Writes a multithreaded BGZF stream (64 KiB input blocks, via `gzp`'s
+/// `ParCompress`) when `threads` is greater than one and `compression`'s
+/// algorithm is gzip, which distributes compression across worker threads
+/// instead of `niffler`'s serial encoder - BGZF output remains readable by
+/// any plain gzip decoder, with the bonus of being bgzip-index compatible
+/// for downstream tools.
+///
+/// Zstandard output bypasses `niffler` entirely and goes through the `zstd`
+/// crate's own streaming `Encoder` directly: `niffler::compression::Level`
+/// only spans `One`..`Nine`, silently clamping any `--compression-level`
+/// above 9 even though Zstandard itself accepts up to 19 (see
+/// `CompressionAlgorithm::max_level`), and enabling long-distance matching -
+/// which needs the `zstd` crate's own API - is what makes zstd's output
+/// worth picking over gzip for big depleted FASTQ
+/// sets in the first place.
+///
+/// Falls back to the existing `niffler`-backed writer for a single thread or
+/// any other format (bzip/lzma/lz4/uncompressed).
+pub fn build_output_writer(path: &Path, compression: Compression, threads: usize) -> Result<OutputWriter, ScrubbyError> {
+    if threads > 1 && compression.algorithm == CompressionAlgorithm::Gzip {
+        let file = std::fs::File::create(path)?;
+        let writer = ParCompressBuilder::<Bgzf>::new()
+            .num_threads(threads)
+            .map_err(|e| ScrubbyError::CommandExecutionFailed("gzp thread pool".to_string(), e.to_string()))?
+            .compression_level(gzp::Compression::new(compression.level))
+            .from_writer(file);
+        Ok(OutputWriter::Bgzf(Box::new(writer)))
+    } else if compression.algorithm == CompressionAlgorithm::Zstd {
+        let file = std::fs::File::create(path)?;
+        let mut encoder = zstd::Encoder::new(file, compression.level as i32)?;
+        encoder.long_distance_matching(true)?;
+        Ok(OutputWriter::Zstd(Some(encoder)))
+    } else {
+        Ok(OutputWriter::Niffler(crate::utils::get_fastx_writer(
+            path, compression.niffler_level(), Some(compression.algorithm.niffler_format())
+        )?))
+    }
+}
+
+/// Opens `path` for reading, transparently decompressing gzip, bzip2, xz/lzma
+/// or zstd input.
+///
+/// Detection is content-based (`niffler` sniffs the leading magic bytes)
+/// rather than extension-based, so a compressed report or database file is
+/// read correctly regardless of what it is named - unlike
+/// `CompressionAlgorithm::from_extension`, which only ever applies to an
+/// output path that does not exist yet and so has nothing to sniff.
+pub fn open_reader(path: &Path) -> Result<Box<dyn BufRead>, ScrubbyError> {
+    let (reader, _) = niffler::from_path(path)?;
+    Ok(Box::new(BufReader::new(reader)))
+}
+
+/// An external decompressor command registered for a file extension
+/// niffler/needletail can't open natively (e.g. `.sra`, a long-range-mode
+/// `.zst`). `args` are passed before the input path, which is always
+/// appended as the final argument; the command must write the decompressed
+/// stream to stdout.
+#[derive(Clone, Debug)]
+pub struct ExternalDecompressor {
+    pub cmd: String,
+    pub args: Vec<String>,
+}
+
+type DecompressorRegistry = Mutex<HashMap<String, ExternalDecompressor>>;
+static EXTERNAL_DECOMPRESSORS: OnceLock<DecompressorRegistry> = OnceLock::new();
+
+/// Registers an external decompressor command for `extension` (matched
+/// case-insensitively against a path's extension, without the leading dot),
+/// tried when `needletail::parse_fastx_file` fails to open a file with that
+/// extension. Lets a library user cover a format scrubby has no built-in
+/// support for without forking the crate.
+pub fn register_external_decompressor(extension: &str, cmd: &str, args: &[&str]) {
+    decompressor_registry().lock()
+        .expect("external decompressor registry poisoned")
+        .insert(extension.to_lowercase(), ExternalDecompressor {
+            cmd: cmd.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        });
+}
+
+/// Returns the shared decompressor registry, seeding it on first access with
+/// a built-in entry for `.sra` (via `sra-tools`' `fasterq-dump`), the most
+/// common format niffler/needletail cannot open natively.
+fn decompressor_registry() -> &'static DecompressorRegistry {
+    EXTERNAL_DECOMPRESSORS.get_or_init(|| {
+        let mut builtins = HashMap::new();
+        builtins.insert("sra".to_string(), ExternalDecompressor {
+            cmd: "fasterq-dump".to_string(),
+            args: vec!["--stdout".to_string()],
+        });
+        Mutex::new(builtins)
+    })
+}
+
+/// Looks up the external decompressor registered for `path`'s extension, if any.
+pub(crate) fn get_external_decompressor(path: &Path) -> Option<ExternalDecompressor> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    decompressor_registry().lock().expect("external decompressor registry poisoned").get(&extension).cloned()
+}
+
+/// Reaps (`wait`s on) a spawned external decompressor's child process when
+/// its output stream is dropped, instead of leaving a zombie process behind
+/// for the remainder of the run.
+struct ReapOnDrop(Child);
+
+impl Drop for ReapOnDrop {
+    fn drop(&mut self) {
+        if let Err(e) = self.0.wait() {
+            log::warn!("failed to reap external decompressor process: {e}");
+        }
+    }
+}
+
+/// The piped stdout of a spawned external decompressor, bundled with a
+/// `ReapOnDrop` guard so the child is waited on once `needletail` finishes
+/// reading from it.
+struct ExternalDecompressorStream {
+    stdout: ChildStdout,
+    _child: ReapOnDrop,
+}
+
+impl Read for ExternalDecompressorStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.stdout.read(buf)
+    }
+}
+
+/// Spawns `decompressor` against `path` and hands its stdout to
+/// `needletail::parse_fastx_reader`, since `path`'s format is outside what
+/// `needletail::parse_fastx_file` can open natively. A spawn failure (e.g.
+/// the command is not installed) surfaces as
+/// `ScrubbyError::ExternalDecompressorSpawnFailed` instead of silently
+/// producing an empty output file.
+pub(crate) fn parse_with_external_decompressor(
+    decompressor: &ExternalDecompressor, path: &Path,
+) -> Result<Box<dyn FastxReader>, ScrubbyError> {
+    let mut child = Command::new(&decompressor.cmd)
+        .args(&decompressor.args)
+        .arg(path)
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| ScrubbyError::ExternalDecompressorSpawnFailed(decompressor.cmd.clone(), e.to_string()))?;
+
+    let stdout = child.stdout.take().ok_or_else(|| {
+        ScrubbyError::ExternalDecompressorSpawnFailed(decompressor.cmd.clone(), "failed to capture stdout".to_string())
+    })?;
+
+    let stream = ExternalDecompressorStream { stdout, _child: ReapOnDrop(child) };
+    Ok(needletail::parse_fastx_reader(BufReader::new(stream))?)
+}
@@ -1,72 +1,154 @@
-use core::fmt;
-use std::fs::{create_dir_all, remove_file, File};
-use std::io::{BufReader, BufWriter};
+use std::cell::RefCell;
+use std::fs::{create_dir_all, remove_file, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::PathBuf;
-use std::time::Duration;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 use reqwest::blocking::Client;
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tar::Archive;
 
 use crate::error::ScrubbyError;
 use crate::scrubby::{Aligner, Classifier};
 
-/// Represents different indices available for Scrubby.
-#[derive(Serialize, Deserialize, Clone, Debug, clap::ValueEnum)]
-pub enum ScrubbyIndex {
-    Chm13v2
+/// A single published file (alignment or classifier index) for a catalog
+/// entry, as described by the remote `index.json` manifest.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScrubbyCatalogFile {
+    /// The aligner this file was built for, if any.
+    pub aligner: Option<Aligner>,
+    /// The classifier this file was built for, if any.
+    pub classifier: Option<Classifier>,
+    /// File name relative to the catalog's `base_url`.
+    pub file_name: String,
+    /// Size of the file in bytes, for progress reporting before download starts.
+    pub size: u64,
+    /// Expected hex-encoded SHA-256 digest of the file.
+    pub sha256: String,
 }
 
-impl ScrubbyIndex {
-    /// Returns the aligner name formatted for the specified index.
-    ///
-    /// # Arguments
-    ///
-    /// * `aligner` - A reference to an `Aligner`.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let index = ScrubbyIndex::Chm13v2;
-    /// let aligner = Aligner::new();
-    /// let name = index.aligner_name(&aligner);
-    /// ```
-    pub fn aligner_name(&self, aligner: &Aligner) -> String {
-        format!("{}.{}.tar.xz", self, aligner.short_name())
+/// One reference index described by the remote catalog (e.g. a host genome
+/// or taxonomic database), published under a stable string id rather than a
+/// compile-time enum variant.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ScrubbyCatalogEntry {
+    /// Stable identifier used to select this index, e.g. `"chm13v2"`.
+    pub id: String,
+    /// Human-readable description shown by `list()`.
+    pub description: String,
+    /// Files published for this index, one per supported aligner/classifier.
+    pub files: Vec<ScrubbyCatalogFile>,
+}
+
+impl ScrubbyCatalogEntry {
+    /// Returns the published file for `aligner`, if this index supports it.
+    pub fn file_for_aligner(&self, aligner: &Aligner) -> Option<&ScrubbyCatalogFile> {
+        self.files.iter().find(|file| file.aligner.as_ref() == Some(aligner))
     }
-    /// Returns the classifier name formatted for the specified index.
-    ///
-    /// # Arguments
-    ///
-    /// * `classifier` - A reference to a `Classifier`.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let index = ScrubbyIndex::Chm13v2;
-    /// let classifier = Classifier::new();
-    /// let name = index.classifier_name(&classifier);
-    /// ```
-    pub fn classifier_name(&self, classifier: &Classifier) -> String {
-        format!("{}.{}.tar.xz", self, classifier.short_name())
+    /// Returns the published file for `classifier`, if this index supports it.
+    pub fn file_for_classifier(&self, classifier: &Classifier) -> Option<&ScrubbyCatalogFile> {
+        self.files.iter().find(|file| file.classifier.as_ref() == Some(classifier))
     }
 }
 
-impl fmt::Display for ScrubbyIndex {
-    /// Formats the ScrubbyIndex for display.
-    ///
-    /// # Arguments
-    ///
-    /// * `f` - A mutable reference to a `fmt::Formatter`.
+/// The remote index catalog (`index.json` at `base_url`), describing every
+/// reference index currently published. New indices can ship by updating
+/// this manifest server-side, without a crate release.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ScrubbyCatalog {
+    pub indices: Vec<ScrubbyCatalogEntry>,
+}
+
+impl ScrubbyCatalog {
+    /// Looks up a catalog entry by its string id.
+    pub fn entry(&self, id: &str) -> Option<&ScrubbyCatalogEntry> {
+        self.indices.iter().find(|entry| entry.id == id)
+    }
+    /// The catalog bundled with this crate, used as a fallback when the
+    /// remote `index.json` manifest cannot be fetched (for example when
+    /// working offline). Carries no checksum, since the bundled entry cannot
+    /// know what the server will actually publish.
+    pub fn bundled() -> Self {
+        Self {
+            indices: vec![ScrubbyCatalogEntry {
+                id: "chm13v2".to_string(),
+                description: "Human T2T Reference (CHM13v2)".to_string(),
+                files: vec![ScrubbyCatalogFile {
+                    aligner: Some(Aligner::Bowtie2),
+                    classifier: None,
+                    file_name: "chm13v2.bt.tar.xz".to_string(),
+                    size: 0,
+                    sha256: String::new(),
+                }],
+            }],
+        }
+    }
+}
+
+/// Fetches and deserializes the `index.json` catalog manifest at `base_url`.
+fn fetch_catalog(client: &Client, base_url: &str, username: &str, password: &str, timeout: u64) -> Result<ScrubbyCatalog, ScrubbyError> {
+    let url = format!("{base_url}/index.json");
+
+    let response = client.get(&url)
+        .basic_auth(username, Some(password))
+        .timeout(Duration::from_secs(timeout*60))
+        .send()?;
+
+    if !response.status().is_success() {
+        return Err(ScrubbyError::DownloadFailedRequest(response.status()));
+    }
+
+    let body = response.text()?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// Container format of a downloaded index, detected from its leading magic
+/// bytes rather than its file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// A zip archive (`PK\x03\x04` magic).
+    Zip,
+    /// A tar archive, optionally niffler-compressed.
+    Tar,
+    /// A single file, optionally niffler-compressed, that is not an archive.
+    Raw,
+}
+
+impl ArchiveFormat {
+    /// Sniffs the container format of the file at `path`.
     ///
     /// # Example
     ///
     /// ```
-    /// let index = ScrubbyIndex::Chm13v2;
-    /// println!("{}", index);
+    /// let format = ArchiveFormat::sniff(&download_path)?;
     /// ```
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ScrubbyIndex::Chm13v2 => write!(f, "chm13v2"),
+    pub fn sniff(path: &PathBuf) -> Result<Self, ScrubbyError> {
+        let mut zip_magic = [0u8; 4];
+        File::open(path)?.read(&mut zip_magic)?;
+        if zip_magic == *b"PK\x03\x04" {
+            return Ok(ArchiveFormat::Zip);
+        }
+
+        // Tar has no magic at the start of the stream (`ustar` sits at offset
+        // 257 of the first header block), so peek past a decompressing reader
+        // far enough to check for it before falling back to a raw passthrough.
+        let (mut reader, _compression) = niffler::get_reader(Box::new(BufReader::new(File::open(path)?)))?;
+        let mut probe = [0u8; 262];
+        let mut filled = 0;
+        while filled < probe.len() {
+            match reader.read(&mut probe[filled..])? {
+                0 => break,
+                read => filled += read,
+            }
+        }
+
+        if filled == probe.len() && &probe[257..262] == b"ustar" {
+            Ok(ArchiveFormat::Tar)
+        } else {
+            Ok(ArchiveFormat::Raw)
         }
     }
 }
@@ -79,9 +161,14 @@ pub struct ScrubbyDownloader {
     pub password: String,
     pub client: Client,
     pub timeout: u64,
-    pub indices: Vec<ScrubbyIndex>,
+    pub indices: Vec<String>,
     pub aligners: Vec<Aligner>,
-    pub classifiers: Vec<Classifier>
+    pub classifiers: Vec<Classifier>,
+    pub verify: bool,
+    pub max_retries: u32,
+    pub backoff: u64,
+    pub stream_unpack: bool,
+    pub catalog: ScrubbyCatalog
 }
 
 impl ScrubbyDownloader {
@@ -90,7 +177,7 @@ impl ScrubbyDownloader {
     /// # Arguments
     ///
     /// * `outdir` - Output directory for downloaded files.
-    /// * `indices` - A list of `ScrubbyIndex` to download.
+    /// * `indices` - A list of catalog index ids to download.
     ///
     /// # Errors
     ///
@@ -100,10 +187,10 @@ impl ScrubbyDownloader {
     ///
     /// ```
     /// let outdir = PathBuf::from("/path/to/output");
-    /// let indices = vec![ScrubbyIndex::Chm13v2];
+    /// let indices = vec!["chm13v2".to_string()];
     /// let downloader = ScrubbyDownloader::new(outdir, indices);
     /// ```
-    pub fn new(outdir: PathBuf, indices: Vec<ScrubbyIndex>) -> Result<Self, ScrubbyError> {
+    pub fn new(outdir: PathBuf, indices: Vec<String>) -> Result<Self, ScrubbyError> {
         ScrubbyDownloaderBuilder::new(outdir, indices).build()
     }
     /// Creates a new instance of ScrubbyDownloaderBuilder.
@@ -111,19 +198,19 @@ impl ScrubbyDownloader {
     /// # Arguments
     ///
     /// * `outdir` - Output directory for downloaded files.
-    /// * `indices` - A list of `ScrubbyIndex` to download.
+    /// * `indices` - A list of catalog index ids to download.
     ///
     /// # Example
     ///
     /// ```
     /// let outdir = PathBuf::from("/path/to/output");
-    /// let indices = vec![ScrubbyIndex::Chm13v2];
+    /// let indices = vec!["chm13v2".to_string()];
     /// let builder = ScrubbyDownloader::builder(outdir, indices);
     /// ```
-    pub fn builder(outdir: PathBuf, indices: Vec<ScrubbyIndex>) -> ScrubbyDownloaderBuilder {
+    pub fn builder(outdir: PathBuf, indices: Vec<String>) -> ScrubbyDownloaderBuilder {
         ScrubbyDownloaderBuilder::new(outdir, indices)
     }
-    /// Lists the available index names for download.
+    /// Lists the indices available for download in the current catalog.
     ///
     /// # Example
     ///
@@ -144,7 +231,9 @@ impl ScrubbyDownloader {
         log::info!("Available index names for download   ");
         log::info!("=====================================");
         log::info!("                                     ");
-        log::info!("{:<16} Human T2T Reference (CHM13v2)", ScrubbyIndex::Chm13v2);
+        for entry in &self.catalog.indices {
+            log::info!("{:<16} {}", entry.id, entry.description);
+        }
     }
     /// Downloads the specified indices.
     ///
@@ -163,31 +252,65 @@ impl ScrubbyDownloader {
         if self.indices.is_empty() {
             log::warn!("No index names provided for download")
         }
-        
-        for index in &self.indices {
+
+        for id in &self.indices {
+            let entry = self.catalog.entry(id)
+                .ok_or_else(|| ScrubbyError::UnknownCatalogIndex(id.clone()))?;
+
             for aligner in &self.aligners {
-                let file_path = self.outdir.join(index.aligner_name(&aligner));
-                log::info!("Downloading alignment index to file: {}", file_path.display());
-                self.download(&index.aligner_name(aligner), &file_path)?;
-                log::info!("Unpacking alignment index to directory: {}", self.outdir.display());
-                self.unpack(&file_path, &self.outdir)?;
-                log::info!("Removing download: {}", file_path.display());
-                remove_file(&file_path)?;
+                let Some(file) = entry.file_for_aligner(aligner) else {
+                    log::warn!("Index `{}` does not publish a file for aligner `{}` - skipping", id, aligner);
+                    continue;
+                };
+                let checksum = self.checksum_for(file)?;
+                self.download_and_unpack_file(file, checksum.as_deref())?;
             }
             for classifier in &self.classifiers {
-                let file_path = self.outdir.join(index.classifier_name(&classifier));
-                log::info!("Downloading classifier index to file: {}", file_path.display());
-                self.download(&index.classifier_name(classifier), &file_path)?;
-                log::info!("Unpacking classifier index to directory: {}", self.outdir.display());
-                self.unpack(&file_path, &self.outdir)?;
-                log::info!("Removing download: {}", file_path.display());
-                remove_file(&file_path)?;
+                let Some(file) = entry.file_for_classifier(classifier) else {
+                    log::warn!("Index `{}` does not publish a file for classifier `{}` - skipping", id, classifier);
+                    continue;
+                };
+                let checksum = self.checksum_for(file)?;
+                self.download_and_unpack_file(file, checksum.as_deref())?;
             }
         }
 
         Ok(())
     }
-     /// Unpacks the downloaded file to the specified output directory.
+    /// Resolves the expected checksum to verify a catalog file against: the
+    /// digest published in the catalog itself if there is one, falling back
+    /// to the sidecar/`SHA256SUMS` manifest lookup for catalogs (such as the
+    /// bundled one) that don't carry checksums. Returns `None` if `verify` is
+    /// disabled or no checksum can be found anywhere.
+    fn checksum_for(&self, file: &ScrubbyCatalogFile) -> Result<Option<String>, ScrubbyError> {
+        if !self.verify {
+            return Ok(None);
+        }
+        if !file.sha256.is_empty() {
+            return Ok(Some(file.sha256.clone()));
+        }
+        self.fetch_checksum(&file.file_name)
+    }
+    /// Downloads and unpacks a single catalog file, choosing between the
+    /// streaming and buffered paths according to `stream_unpack` and whether
+    /// the file is tar-packaged (see [`Self::download_and_unpack`]).
+    fn download_and_unpack_file(&self, file: &ScrubbyCatalogFile, checksum: Option<&str>) -> Result<(), ScrubbyError> {
+        if self.stream_unpack && is_tar_file_name(&file.file_name) {
+            log::info!("Downloading and unpacking index in one pass: {}", file.file_name);
+            self.download_and_unpack(&file.file_name, &self.outdir, checksum)
+        } else {
+            let file_path = self.outdir.join(&file.file_name);
+            log::info!("Downloading index to file: {}", file_path.display());
+            self.download(&file.file_name, &file_path, checksum)?;
+            log::info!("Unpacking index to directory: {}", self.outdir.display());
+            self.unpack(&file_path, &self.outdir)?;
+            log::info!("Removing download: {}", file_path.display());
+            remove_file(&file_path)?;
+            Ok(())
+        }
+    }
+     /// Unpacks the downloaded file to the specified output directory, detecting
+    /// the container format from its leading magic bytes (see [`ArchiveFormat::sniff`]).
     ///
     /// # Arguments
     ///
@@ -205,6 +328,14 @@ impl ScrubbyDownloader {
     /// downloader.unpack(&download_path, &outdir);
     /// ```
     pub fn unpack(&self, download: &PathBuf, outdir: &PathBuf) -> Result<(), ScrubbyError> {
+        match ArchiveFormat::sniff(download)? {
+            ArchiveFormat::Zip => self.unpack_zip(download, outdir),
+            ArchiveFormat::Tar => self.unpack_tar(download, outdir),
+            ArchiveFormat::Raw => self.unpack_raw(download, outdir),
+        }
+    }
+    /// Unpacks a (optionally compressed) tar archive.
+    fn unpack_tar(&self, download: &PathBuf, outdir: &PathBuf) -> Result<(), ScrubbyError> {
         let file = File::open(download)?;
         let buf_reader = BufReader::new(file);
         let (reader, _compression) = niffler::get_reader(Box::new(buf_reader))?;
@@ -218,52 +349,382 @@ impl ScrubbyDownloader {
 
         Ok(())
     }
-    /// Downloads a file from the specified URL to the given path.
+    /// Unpacks a zip archive, sanitizing entry paths against directory
+    /// traversal the same way `tar::Entry::unpack_in` does for tar entries.
+    fn unpack_zip(&self, download: &PathBuf, outdir: &PathBuf) -> Result<(), ScrubbyError> {
+        let file = File::open(download)?;
+        let mut archive = zip::ZipArchive::new(file)?;
+
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            let Some(enclosed) = entry.enclosed_name() else {
+                log::warn!("Skipping zip entry with unsafe path: {}", entry.name());
+                continue;
+            };
+            let out_path = outdir.join(enclosed);
+
+            if entry.is_dir() {
+                create_dir_all(&out_path)?;
+                continue;
+            }
+            if let Some(parent) = out_path.parent() {
+                create_dir_all(parent)?;
+            }
+            let mut out_file = File::create(&out_path)?;
+            std::io::copy(&mut entry, &mut out_file)?;
+        }
+
+        Ok(())
+    }
+    /// Writes a single decompressed file straight to `outdir`, for indices
+    /// published as a bare compressed FASTA/index file rather than an archive.
+    fn unpack_raw(&self, download: &PathBuf, outdir: &PathBuf) -> Result<(), ScrubbyError> {
+        let file = File::open(download)?;
+        let (mut reader, _compression) = niffler::get_reader(Box::new(BufReader::new(file)))?;
+
+        let file_name = download.file_stem()
+            .ok_or_else(|| ScrubbyError::UnsupportedArchive(download.display().to_string()))?;
+        let mut out_file = File::create(outdir.join(file_name))?;
+        std::io::copy(&mut reader, &mut out_file)?;
+
+        Ok(())
+    }
+    /// Downloads a file from the specified URL to the given path, verifying its
+    /// SHA-256 digest against `expected_checksum` (from [`fetch_checksum`](Self::fetch_checksum))
+    /// if one was found and `verify` is enabled.
+    ///
+    /// A partial file already present at `path` (for example from a previously
+    /// interrupted attempt) is resumed with a `Range` request rather than
+    /// re-downloaded from scratch. The transfer is retried up to `max_retries`
+    /// times with exponential backoff on connection/timeout errors and `5xx`
+    /// responses, resuming from the current file length on each retry.
     ///
     /// # Arguments
     ///
     /// * `file_name` - The name of the file to download.
     /// * `path` - The path where the file should be saved.
+    /// * `expected_checksum` - The expected hex-encoded SHA-256 digest, if known.
     ///
     /// # Errors
     ///
-    /// Returns a `ScrubbyError` if the download operation fails.
+    /// Returns a `ScrubbyError` if the download operation fails after exhausting
+    /// retries, or the downloaded file's digest does not match `expected_checksum`.
     ///
     /// # Example
     ///
     /// ```
     /// let downloader = ScrubbyDownloader::new(outdir, indices);
-    /// downloader.download("file_name.tar.xz", &path);
+    /// downloader.download("file_name.tar.xz", &path, Some("abc123..."));
     /// ```
-    pub fn download(&self, file_name: &str, path: &PathBuf) -> Result<(), ScrubbyError> {
+    pub fn download(&self, file_name: &str, path: &PathBuf, expected_checksum: Option<&str>) -> Result<(), ScrubbyError> {
+        let url = format!("{}/{}", self.base_url, file_name);
+
+        let mut attempt = 0;
+        let actual = loop {
+            match self.download_attempt(&url, file_name, path) {
+                Ok(digest) => break digest,
+                Err(error) if attempt < self.max_retries && is_retryable(&error) => {
+                    let delay = self.backoff.saturating_mul(1 << attempt);
+                    attempt += 1;
+                    log::warn!(
+                        "Download of {} failed ({}), retrying in {}s (attempt {}/{})",
+                        file_name, error, delay, attempt, self.max_retries
+                    );
+                    std::thread::sleep(Duration::from_secs(delay));
+                }
+                Err(error) => return Err(error),
+            }
+        };
+
+        log::info!("Computed SHA-256 digest for {}: {}", file_name, actual);
+
+        if let Some(expected) = expected_checksum {
+            if !actual.eq_ignore_ascii_case(expected) {
+                remove_file(path)?;
+                return Err(ScrubbyError::ChecksumMismatch { expected: expected.to_string(), actual });
+            }
+        }
+
+        Ok(())
+    }
+    /// Performs a single download attempt, resuming from the length of any
+    /// partial file already present at `path`, and returns the hex-encoded
+    /// SHA-256 digest of the complete file on success.
+    fn download_attempt(&self, url: &str, file_name: &str, path: &PathBuf) -> Result<String, ScrubbyError> {
+        let mut hasher = Sha256::new();
+        let mut resume_from = 0u64;
+
+        if path.exists() {
+            let mut existing = File::open(path)?;
+            resume_from = existing.metadata()?.len();
+            std::io::copy(&mut existing, &mut hasher)?;
+        }
+
+        let mut request = self.client.get(url)
+            .basic_auth(&self.username, Some(&self.password))
+            .timeout(Duration::from_secs(self.timeout*60));
+
+        if resume_from > 0 {
+            request = request.header(RANGE, format!("bytes={resume_from}-"));
+        }
+
+        let mut response = request.send()?;
+        let status = response.status();
+
+        let resuming = match status {
+            StatusCode::PARTIAL_CONTENT if resume_from > 0 => true,
+            status if status.is_success() => {
+                resume_from = 0;
+                hasher = Sha256::new();
+                false
+            }
+            status => return Err(ScrubbyError::DownloadFailedRequest(status)),
+        };
+
+        let total_size = response.content_length().map(|len| len + resume_from);
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resuming)
+            .truncate(!resuming)
+            .open(path)?;
+        let mut writer = HashingWriter::with_hasher(BufWriter::new(file), hasher);
+
+        let mut downloaded = resume_from;
+        let mut transferred = 0u64;
+        let mut buffer = [0u8; 65536];
+        let started = Instant::now();
+        let mut last_logged = started;
+
+        loop {
+            let read = response.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..read])?;
+            downloaded += read as u64;
+            transferred += read as u64;
+
+            if last_logged.elapsed() >= Duration::from_secs(2) {
+                log_progress(file_name, downloaded, total_size, transferred, started.elapsed());
+                last_logged = Instant::now();
+            }
+        }
+        writer.flush()?;
+        log_progress(file_name, downloaded, total_size, transferred, started.elapsed());
+
+        Ok(writer.digest())
+    }
+    /// Downloads `file_name` and extracts its tar entries into `outdir` as
+    /// bytes arrive off the network, without ever materializing the
+    /// (potentially large) compressed archive on disk. Unlike zip, a tar
+    /// archive needs no random access to a trailing central directory, so it
+    /// can be decompressed and unpacked in a single streaming pass.
+    ///
+    /// This mode does not support resuming a partial transfer (there is no
+    /// file on disk to resume from) and does not retry on failure; verifying
+    /// the checksum happens against bytes as they stream past, so a mismatch
+    /// is only detected after entries have already been written to `outdir`.
+    /// Use [`Self::download`] followed by [`Self::unpack`] instead if the
+    /// whole archive must be verified before anything is extracted.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ScrubbyError` if the request fails, the stream is not a
+    /// valid (optionally compressed) tar archive, or the digest does not
+    /// match `expected_checksum`.
+    fn download_and_unpack(&self, file_name: &str, outdir: &PathBuf, expected_checksum: Option<&str>) -> Result<(), ScrubbyError> {
         let url = format!("{}/{}", self.base_url, file_name);
 
-        let mut response = self.client.get(&url)
+        let response = self.client.get(&url)
             .basic_auth(&self.username, Some(&self.password))
             .timeout(Duration::from_secs(self.timeout*60))
             .send()?;
 
-        if !response.status().is_success() {
-            return Err(ScrubbyError::DownloadFailedRequest(response.status()));
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ScrubbyError::DownloadFailedRequest(status));
         }
 
-        let mut writer = BufWriter::new(File::create(path)?);
-        response.copy_to(&mut writer)?;
+        let (hashing, hasher) = HashingReader::new(response);
+        let (reader, _compression) = niffler::get_reader(Box::new(hashing))?;
+        let mut archive = Archive::new(reader);
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            entry.unpack_in(outdir)?;
+        }
+
+        let actual = format!("{:x}", hasher.borrow().clone().finalize());
+        log::info!("Computed SHA-256 digest for {}: {}", file_name, actual);
+
+        if let Some(expected) = expected_checksum {
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(ScrubbyError::ChecksumMismatch { expected: expected.to_string(), actual });
+            }
+        }
 
         Ok(())
     }
+    /// Fetches the expected SHA-256 digest for `file_name`, checking a per-file
+    /// sidecar manifest (`<file_name>.sha256`) first and falling back to a shared
+    /// `SHA256SUMS` manifest at `base_url` if no sidecar is published. Returns
+    /// `None` if neither manifest is available, in which case the download proceeds
+    /// unverified.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file to look up a checksum for.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ScrubbyError` if a manifest request fails outright (as opposed
+    /// to simply not being found).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let downloader = ScrubbyDownloader::new(outdir, indices);
+    /// let checksum = downloader.fetch_checksum("file_name.tar.xz");
+    /// ```
+    pub fn fetch_checksum(&self, file_name: &str) -> Result<Option<String>, ScrubbyError> {
+        let sidecar_url = format!("{}/{}.sha256", self.base_url, file_name);
+        let response = self.client.get(&sidecar_url)
+            .basic_auth(&self.username, Some(&self.password))
+            .timeout(Duration::from_secs(self.timeout*60))
+            .send()?;
+
+        if response.status().is_success() {
+            let body = response.text()?;
+            return Ok(body.split_whitespace().next().map(str::to_lowercase));
+        }
+
+        let manifest_url = format!("{}/SHA256SUMS", self.base_url);
+        let response = self.client.get(&manifest_url)
+            .basic_auth(&self.username, Some(&self.password))
+            .timeout(Duration::from_secs(self.timeout*60))
+            .send()?;
+
+        if !response.status().is_success() {
+            log::warn!("No checksum manifest found for {} - downloading unverified", file_name);
+            return Ok(None);
+        }
+
+        let body = response.text()?;
+        Ok(body.lines().find_map(|line| {
+            let mut fields = line.split_whitespace();
+            let digest = fields.next()?;
+            let name = fields.next()?.trim_start_matches('*');
+            (name == file_name).then(|| digest.to_lowercase())
+        }))
+    }
+}
+
+/// Wraps a writer, incrementally hashing every byte written to it with SHA-256
+/// so a download can be verified without a second pass over the file on disk.
+struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self::with_hasher(inner, Sha256::new())
+    }
+    /// Wraps `inner`, seeding the digest with a hasher that may already have
+    /// consumed bytes from a resumed partial download.
+    fn with_hasher(inner: W, hasher: Sha256) -> Self {
+        Self { inner, hasher }
+    }
+    /// Returns the hex-encoded digest of all bytes written so far.
+    fn digest(&self) -> String {
+        format!("{:x}", self.hasher.clone().finalize())
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Wraps a reader, incrementally hashing every byte read from it with SHA-256.
+/// The hasher is shared via `Rc<RefCell<_>>` rather than owned outright, since
+/// the reader is typically moved into a `Box<dyn Read>` (e.g. by
+/// `niffler::get_reader`) before the caller is done with it.
+struct HashingReader<R: Read> {
+    inner: R,
+    hasher: Rc<RefCell<Sha256>>,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wraps `inner`, returning the reader alongside a handle to its hasher
+    /// that remains valid after `inner` itself is moved away.
+    fn new(inner: R) -> (Self, Rc<RefCell<Sha256>>) {
+        let hasher = Rc::new(RefCell::new(Sha256::new()));
+        (Self { inner, hasher: hasher.clone() }, hasher)
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.borrow_mut().update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Returns whether `file_name` is published as a tar archive, the only
+/// container format [`ScrubbyDownloader::download_and_unpack`] can stream
+/// directly into `outdir` without first buffering it to disk.
+fn is_tar_file_name(file_name: &str) -> bool {
+    [".tar", ".tar.gz", ".tar.xz", ".tar.bz2", ".tgz"]
+        .iter()
+        .any(|suffix| file_name.ends_with(suffix))
+}
+
+/// Returns whether a download error is transient and worth retrying: a
+/// connection or timeout failure, or a `5xx` server response.
+fn is_retryable(error: &ScrubbyError) -> bool {
+    match error {
+        ScrubbyError::DownloadFailedRequest(status) => status.is_server_error(),
+        ScrubbyError::ReqwestError(source) => source.is_timeout() || source.is_connect(),
+        _ => false,
+    }
+}
+
+/// Logs download progress as bytes downloaded so far (including any resumed
+/// prefix) against the total reported via `Content-Length`/`Content-Range`,
+/// along with throughput for the bytes transferred in this attempt.
+fn log_progress(file_name: &str, downloaded: u64, total: Option<u64>, transferred: u64, elapsed: Duration) {
+    let throughput = transferred as f64 / elapsed.as_secs_f64().max(0.001) / 1_048_576.0;
+    match total {
+        Some(total) => log::info!(
+            "{}: {}/{} bytes ({:.1} MiB/s)", file_name, downloaded, total, throughput
+        ),
+        None => log::info!("{}: {} bytes ({:.1} MiB/s)", file_name, downloaded, throughput),
+    }
 }
 
 /// Builder for creating an instance of `ScrubbyDownloader`.
 pub struct ScrubbyDownloaderBuilder {
-    indices: Vec<ScrubbyIndex>,
+    indices: Vec<String>,
     outdir: PathBuf,
     base_url: Option<String>,
     timeout: Option<u64>,
     username: Option<String>,
     password: Option<String>,
     aligners: Option<Vec<Aligner>>,
-    classifiers: Option<Vec<Classifier>>
+    classifiers: Option<Vec<Classifier>>,
+    verify: Option<bool>,
+    max_retries: Option<u32>,
+    backoff: Option<u64>,
+    stream_unpack: Option<bool>
 }
 
 impl ScrubbyDownloaderBuilder {
@@ -272,16 +733,16 @@ impl ScrubbyDownloaderBuilder {
     /// # Arguments
     ///
     /// * `outdir` - Output directory for downloaded files.
-    /// * `indices` - A list of `ScrubbyIndex` to download.
+    /// * `indices` - A list of catalog index ids to download.
     ///
     /// # Example
     ///
     /// ```
     /// let outdir = PathBuf::from("/path/to/output");
-    /// let indices = vec![ScrubbyIndex::Chm13v2];
+    /// let indices = vec!["chm13v2".to_string()];
     /// let builder = ScrubbyDownloaderBuilder::new(outdir, indices);
     /// ```
-    pub fn new(outdir: PathBuf, indices: Vec<ScrubbyIndex>) -> Self {
+    pub fn new(outdir: PathBuf, indices: Vec<String>) -> Self {
         Self {
             outdir,
             indices,
@@ -290,7 +751,11 @@ impl ScrubbyDownloaderBuilder {
             password: None,
             aligners: None,
             classifiers: None,
-            timeout: None
+            timeout: None,
+            verify: None,
+            max_retries: None,
+            backoff: None,
+            stream_unpack: None
         }
     }
     /// Sets the aligners for the builder.
@@ -383,6 +848,74 @@ impl ScrubbyDownloaderBuilder {
         self.password = password.into();
         self
     }
+    /// Sets whether downloaded files are verified against a published SHA-256
+    /// checksum manifest. Enabled by default. With `stream_unpack` enabled,
+    /// tar archives are extracted while they are hashed, so a mismatch is only
+    /// detected after unpacking; otherwise verification precedes unpacking.
+    ///
+    /// # Arguments
+    ///
+    /// * `verify` - Whether to verify downloads against a checksum manifest.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = ScrubbyDownloaderBuilder::new(outdir, indices).verify(false);
+    /// ```
+    pub fn verify<T: Into<Option<bool>>>(mut self, verify: T) -> Self {
+        self.verify = verify.into();
+        self
+    }
+    /// Sets the maximum number of retries for a download attempt that fails
+    /// with a connection/timeout error or a `5xx` response.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_retries` - Maximum number of retries per file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = ScrubbyDownloaderBuilder::new(outdir, indices).max_retries(5);
+    /// ```
+    pub fn max_retries<T: Into<Option<u32>>>(mut self, max_retries: T) -> Self {
+        self.max_retries = max_retries.into();
+        self
+    }
+    /// Sets the base delay in seconds for exponential backoff between retries
+    /// (doubled after each attempt).
+    ///
+    /// # Arguments
+    ///
+    /// * `backoff` - Base backoff delay in seconds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = ScrubbyDownloaderBuilder::new(outdir, indices).backoff(5);
+    /// ```
+    pub fn backoff<T: Into<Option<u64>>>(mut self, backoff: T) -> Self {
+        self.backoff = backoff.into();
+        self
+    }
+    /// Sets whether tar-packaged indices are extracted directly from the
+    /// download stream instead of being buffered to disk first. Enabled by
+    /// default, roughly halving peak disk usage for large indices; disable it
+    /// to verify a downloaded archive's checksum before anything is unpacked.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream_unpack` - Whether to stream-unpack tar-packaged indices.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = ScrubbyDownloaderBuilder::new(outdir, indices).stream_unpack(false);
+    /// ```
+    pub fn stream_unpack<T: Into<Option<bool>>>(mut self, stream_unpack: T) -> Self {
+        self.stream_unpack = stream_unpack.into();
+        self
+    }
     /// Builds the `ScrubbyDownloader` instance.
     ///
     /// # Errors
@@ -413,17 +946,42 @@ impl ScrubbyDownloaderBuilder {
             .unwrap_or(Vec::new());
         let timeout = self.timeout
             .unwrap_or(30);
+        let verify = self.verify
+            .unwrap_or(true);
+        let max_retries = self.max_retries
+            .unwrap_or(3);
+        let backoff = self.backoff
+            .unwrap_or(5);
+        let stream_unpack = self.stream_unpack
+            .unwrap_or(true);
+
+        let client = Client::new();
+        let catalog = fetch_catalog(&client, &base_url, &username, &password, timeout).unwrap_or_else(|error| {
+            log::warn!("Failed to fetch remote index catalog ({error}) - falling back to the bundled catalog");
+            ScrubbyCatalog::bundled()
+        });
+
+        for id in &self.indices {
+            if catalog.entry(id).is_none() {
+                return Err(ScrubbyError::UnknownCatalogIndex(id.clone()));
+            }
+        }
 
         Ok(ScrubbyDownloader {
             outdir: self.outdir.to_owned(),
             base_url,
             username,
             password,
-            client: Client::new(),
+            client,
             timeout,
             indices: self.indices.clone(),
             aligners,
-            classifiers
+            classifiers,
+            verify,
+            max_retries,
+            backoff,
+            stream_unpack,
+            catalog
         })
     }
 }
@@ -1,168 +1,653 @@
 use thiserror::Error;
 use std::path::PathBuf;
 use crate::scrubby::{Aligner, Classifier, Preset};
+use crate::compression::CompressionAlgorithm;
+
+/// Pinpoints where a streaming parser failed: the source file, the 1-based
+/// record (line) number within it, and optionally the named field being
+/// converted. Readers build one of these per record as they iterate, so a
+/// malformed row in a multi-million-line report can be located directly
+/// instead of only knowing that *some* row failed to parse.
+#[derive(Debug, Clone)]
+pub struct ParseContext {
+    pub path: PathBuf,
+    pub record: u64,
+    pub field: Option<String>,
+}
+
+impl ParseContext {
+    /// Creates a context for the given record number, with no field set.
+    pub fn new(path: PathBuf, record: u64) -> Self {
+        Self { path, record, field: None }
+    }
+    /// Returns a copy of this context naming the field being converted.
+    pub fn with_field(&self, field: &str) -> Self {
+        Self { field: Some(field.to_string()), ..self.clone() }
+    }
+}
+
+impl std::fmt::Display for ParseContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{}:{} (field: {})", self.path.display(), self.record, field),
+            None => write!(f, "{}:{}", self.path.display(), self.record),
+        }
+    }
+}
 
 /// Represents all possible errors that can occur in the Scrubby application.
+///
+/// When the optional `miette` feature is enabled, every variant also derives
+/// `miette::Diagnostic` with a stable `scrubby::...` code and a `--help` line,
+/// so the CLI can render actionable, boxed diagnostics instead of a bare
+/// one-line message.
 #[derive(Error, Debug)]
+#[cfg_attr(feature = "miette", derive(miette::Diagnostic))]
 pub enum ScrubbyError {
     #[cfg(feature = "htslib")]
     /// Indicates failure to parse a BAM file
     #[error("failed to parse records from BAM")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::htslib),
+        help("the BAM file may be truncated or corrupt - try re-generating it with the aligner that produced it")
+    ))]
     HtslibError(#[from] rust_htslib::errors::Error),
     /// Represents all other cases of `std::io::Error`.
     #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::io),
+        help("check that the path exists and that you have permission to read or write it")
+    ))]
     IoError(#[from] std::io::Error),
     /// Represents errors from building a Rayon thread pool.
     #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::rayon_thread_pool),
+        help("lower `--threads` or leave it unset to use the default thread pool size")
+    ))]
     RayonThreadPoolError(#[from] rayon::ThreadPoolBuildError),
     /// Represents all other cases of `niffler::Error`.
     #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::niffler),
+        help("the input may not actually be compressed in the format its extension implies")
+    ))]
     NifflerError(#[from] niffler::Error),
     /// Represents all other cases of `needletail::errors::ParseError`.
     #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::needletail_parse),
+        help("check that the input is well-formed FASTA/FASTQ")
+    ))]
     NeedletailParseError(#[from] needletail::errors::ParseError),
     /// Represents all other cases of `reqwest::Error`.
     #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::reqwest),
+        help("check your network connection and that the download URL is still valid")
+    ))]
     ReqwestError(#[from] reqwest::Error),
     /// Represents all other cases of `csv::Error`.
     #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::csv),
+        help("check that the file is delimited and column-aligned as expected")
+    ))]
     CsvError(#[from] csv::Error),
     /// Represents all other cases of `serde_json::Error`.
     #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::serde_json),
+        help("check that the file contains well-formed JSON matching the expected schema")
+    ))]
     SerdeJsonError(#[from] serde_json::Error),
+    /// Represents a failure to parse a `*.toml` run-settings config file (workspace `scrubby.toml` or `--config`).
+    #[error("failed to parse TOML config file {0}: {1}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::toml_config_parse_failed),
+        help("check that the file contains well-formed TOML matching the `scrubby config --emit-schema` schema")
+    ))]
+    TomlConfigParseFailed(PathBuf, String),
+    /// Represents an error when a registered aligner backend with no dedicated
+    /// preset-error variant rejects the configured preset as unsupported.
+    #[error("Preset `{1}` is not supported by aligner backend `{0}`")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::aligner_backend_preset_not_supported),
+        help("pass one of the presets returned by this backend's `supported_presets`")
+    ))]
+    AlignerBackendPresetNotSupported(String, String),
     /// Failed to make the download request
     #[error("failed to execute request: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::download_failed_request),
+        help("the remote server returned a non-success status - check the index name and your network connection")
+    ))]
     DownloadFailedRequest(reqwest::StatusCode),
     /// Failed to configure the downloader through the builder pattern due to missing field
     #[error("failed to configure the output directory field for the downloader")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::downloader_missing_outdir),
+        help("set an output directory on the downloader builder before calling `build`")
+    ))]
     DownloaderMissingOutdir,
+    /// Downloaded file's SHA-256 digest did not match the published checksum manifest
+    #[error("checksum mismatch for downloaded file: expected {expected}, got {actual}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::checksum_mismatch),
+        help("the download is corrupted or truncated - delete it and try again, or disable verification with `verify(false)` if you trust the source")
+    ))]
+    ChecksumMismatch { expected: String, actual: String },
+    /// Represents all other cases of `zip::result::ZipError`.
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::zip),
+        help("check that the downloaded file is a well-formed zip archive and is not truncated")
+    ))]
+    ZipError(#[from] zip::result::ZipError),
+    /// Downloaded file's container format could not be determined or handled
+    #[error("could not determine how to unpack downloaded file: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::unsupported_archive),
+        help("supported formats are zip, (optionally compressed) tar, and a single compressed file")
+    ))]
+    UnsupportedArchive(String),
+    /// Requested index id is not present in the fetched (or bundled) catalog
+    #[error("index `{0}` was not found in the index catalog")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::unknown_catalog_index),
+        help("run the download command with `--list` to see the index ids available in the current catalog")
+    ))]
+    UnknownCatalogIndex(String),
     /// Indicates failure to parse a record name from BAM file
-    #[error("failed to parse record name from BAM")]
-    RecordNameUtf8Error(#[from] std::str::Utf8Error),
+    #[error("failed to parse record name from BAM at {0}: {1}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::record_name_utf8),
+        help("the BAM file contains a non-UTF8 read name, which is not supported")
+    ))]
+    RecordNameUtf8Error(ParseContext, #[source] std::str::Utf8Error),
     /// Indicates failure to parse a target name from BAM file
     #[error("failed to parse a valid record target name from BAM")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::record_target_id),
+        help("the BAM header's reference index is out of range - the file may be corrupt")
+    ))]
     RecordTargetIdError(#[from] std::num::TryFromIntError),
     /// Indicates failure to parse an u64 from PAF
-    #[error("failed to parse a valid integer from PAF")]
-    PafRecordIntegerError(#[from] std::num::ParseIntError),
+    #[error("failed to parse a valid integer from PAF at {0}: {1}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::paf_record_integer),
+        help("check that the PAF file has not been truncated or manually edited")
+    ))]
+    PafRecordIntegerError(ParseContext, #[source] std::num::ParseIntError),
     /// Represents an error when failing to extract a sequence record header.
     #[error("failed to extract sequence record header")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::needletail_header),
+        help("the read header contains invalid UTF-8 - check the input file encoding")
+    ))]
     NeedletailHeader(#[source] std::str::Utf8Error),
     /// Represents an error when failing to extract a valid header of a read.
     #[error("failed to extract a valid header of read")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::needletail_fastq_header),
+        help("the read is missing a header line - check that the FASTQ is not corrupted")
+    ))]
     NeedletailFastqHeader,
+    /// Represents a failure to spawn a registered external decompressor
+    /// command (see `compression::register_external_decompressor`) for an
+    /// input format niffler/needletail could not open natively.
+    #[error("failed to run external decompressor `{0}`: {1}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::external_decompressor_spawn_failed),
+        help("is `{0}` installed and on your `PATH`?")
+    ))]
+    ExternalDecompressorSpawnFailed(String, String),
     /// Represents an error when both aligner and classifier are configured simultaneously.
     #[error("Unable to specify both aligner and classifier.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::aligner_and_classifier_configured),
+        help("choose either `--aligner` or `--classifier`, not both")
+    ))]
     AlignerAndClassifierConfigured,
+    /// Represents an error when a `clean_async` run's background thread
+    /// dropped its report sender (e.g. it panicked) before `CleanHandle::wait` could receive a result.
+    #[error("clean_async background thread ended without returning a result")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::clean_async_channel_closed),
+        help("the background thread likely panicked - check for a prior log message")
+    ))]
+    CleanAsyncChannelClosed,
+    /// Represents an error when a `clean_async` run is stopped early via `CleanHandle::cancel`.
+    #[error("clean_async run was cancelled")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::clean_cancelled),
+        help("the run was stopped via `CleanHandle::cancel` before it finished")
+    ))]
+    CleanCancelled,
+    /// Represents a failure to compute the content digest of an input or
+    /// reference database/index file for the run's provenance manifest.
+    #[error("failed to compute digest of {0}: {1}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::digest_failed),
+        help("check that the file exists and is readable")
+    ))]
+    DigestFailed(PathBuf, String),
     /// Represents an error when both aligner and classifier indices are specified simultaneously.
     #[error("Unable to specify both aligner and classifier indices.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::aligner_and_classifier_index_configured),
+        help("pass `--index` for whichever of `--aligner`/`--classifier` you selected, not both")
+    ))]
     AlignerAndClassifierIndexConfigured,
     /// Represents an error when the alignment format is not explicitly set and not recognized from extension
     #[error("Unable to recognize alignment input format from extension.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::alignment_input_format_not_recognized),
+        help("rename the file with a `.bam`, `.paf`, or `.sam` extension, or pass `--format` explicitly")
+    ))]
     AlignmentInputFormatNotRecognized,
     /// Represents an error when the alignment format is explicitly set and not recognized
     #[error("Unable to recognize alignment input format - is this version compiled with 'htslib'?")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::alignment_input_format_invalid),
+        help("BAM/SAM input requires the `htslib` feature - rebuild with `--features htslib` or provide PAF instead")
+    ))]
     AlignmentInputFormatInvalid,
+    #[cfg(feature = "htslib")]
+    /// Represents an error when a CRAM alignment file is given without a reference FASTA
+    #[error("CRAM input requires a reference FASTA: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::cram_reference_required),
+        help("pass the reference FASTA used to align this CRAM with `--reference`")
+    ))]
+    CramReferenceRequired(PathBuf),
     /// Represents an error when input and output lengths do not match.
     #[error("Input and output must be of the same length.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::mismatched_input_output_length),
+        help("pass the same number of `--output` paths as `--input` paths (one for single-end, two for paired-end)")
+    ))]
     MismatchedInputOutputLength,
     /// Represents an error when classifier is set but `taxa` or `taxa_direct` is empty.
     #[error("If classifier is set, `taxa` or `taxa_direct` must not be empty.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_taxa),
+        help("pass at least one taxon with `--taxa` or `--taxa-direct` when using `--classifier`")
+    ))]
     MissingTaxa,
     /// Represents an error when classifier index is not set while classifier is configured.
     #[error("Classifier index must be set when classifier is configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_classifier_index),
+        help("pass `--index` pointing at the classifier's database directory")
+    ))]
     MissingClassifierIndex,
     /// Represents an error when classifier read classfication file is not set while classifier cleaning procedure is configured.
     #[error("Classifier read classification input must be set when classifier cleaning procedure is configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_classifier_read_classifications),
+        help("pass the classifier's read classification output with the appropriate `--reads`-style argument")
+    ))]
     MissingClassifierReadClassfications,
     /// Represents an error when classifier read classfication report is not set while classifier cleaning procedure is configured.
     #[error("Classifier read classification report input must be set when classifier cleaning procedure is configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_classifier_classification_report),
+        help("pass the classifier's summary report with `--report`")
+    ))]
     MissingClassifierClassificationReport,
     /// Represents an error when alignment index is not set while aligner is configured.
     #[error("Alignment index must be set when aligner is configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_alignment_index),
+        help("pass `--index` pointing at the aligner's reference index")
+    ))]
     MissingAlignmentIndex,
     /// Represents an error when alignment output is not set while alignment is configured.
     #[error("Alignment output must be set when alignment is configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_alignment),
+        help("pass `--alignment` to specify where the alignment should be written")
+    ))]
     MissingAlignment,
+    /// Represents an error when a sketch reference is not set while sketch depletion is configured.
+    #[error("Sketch index must be set when sketch depletion is configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_sketch_index),
+        help("pass `--sketch` pointing at a reference sketch built with `scrubby sketch`")
+    ))]
+    MissingSketchIndex,
     /// Represents an error when neither classifier nor aligner is set.
     #[error("Either classifier or aligner must be set.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_classifier_or_aligner),
+        help("pass `--aligner <NAME>` or `--classifier <NAME>` to select a depletion method")
+    ))]
     MissingClassifierOrAligner,
     /// Represents an error when input and output vectors are empty.
     #[error("Input and output vectors must not be empty.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::empty_input_output),
+        help("pass at least one `--input` and matching `--output` file")
+    ))]
     EmptyInputOutput,
     /// Represents an error when input and output vectors contain more than two elements.
     #[error("Input and output vectors must not contain more than two elements.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::input_output_length_exceeded),
+        help("scrubby currently supports at most one (single-end) or two (paired-end) files per run")
+    ))]
     InputOutputLengthExceeded,
+    /// Represents an error when `--interleaved` is set but `--input` is not a single file.
+    #[error("Interleaved input requires exactly one `--input` file.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::interleaved_input_not_single_file),
+        help("pass the single interleaved FASTQ as `--input` and both split R1/R2 paths as `--output`, or drop `--interleaved` and pass R1/R2 directly as two `--input` files")
+    ))]
+    InterleavedInputNotSingleFile,
+    /// Represents an error when `--merge-pairs` is set without exactly two `--input`
+    /// files and a single `--output` file to hold the merged/unmerged read stream.
+    #[error("Merging paired reads requires exactly two `--input` files and one `--output` file.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::merge_pairs_requires_paired_single_output),
+        help("pass the R1/R2 FASTQ files as two `--input` arguments and a single merged `--output` file")
+    ))]
+    MergePairsRequiresPairedSingleOutput,
     /// Represents an error when a command execution fails.
     #[error("Failed to execute command '{0}': {1}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::command_execution_failed),
+        help("check that the program is installed and on your `PATH`")
+    ))]
     CommandExecutionFailed(String, String),
     /// Represents an error when a command exits with a non-zero status code.
     #[error("Command '{0}' exited with status code: {1}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::command_failed),
+        help("re-run with `-vv` to capture the program's own error output")
+    ))]
     CommandFailed(String, i32),
+    /// Represents a failure of an external tool invocation, carrying the program,
+    /// the full argument vector that was run, the exit status and a bounded tail
+    /// of the captured stderr so the underlying tool diagnostic is not lost.
+    #[error("command `{program}` ({}) exited with status {status}\n{stderr}", args.join(" "))]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::command_error),
+        help("the tool's own stderr is included above - it usually points at the exact input problem")
+    ))]
+    CommandError { program: String, args: Vec<String>, status: i32, stderr: String },
     /// Represents an error when no aligner is configured.
     #[error("No aligner configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_aligner),
+        help("pass `--aligner <NAME>` to select one of the supported aligners")
+    ))]
     MissingAligner,
     /// Represents an error when no classifier is configured.
     #[error("No classifier configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_classifier),
+        help("pass `--classifier <NAME>` to select one of the supported classifiers")
+    ))]
     MissingClassifier,
     /// Represents an error when no preset is configured.
     #[error("Minimap2 was set as aligner but no preset was configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_minimap2_preset),
+        help("pass `--preset` with one of minimap2's supported presets, e.g. `sr` or `map-ont`")
+    ))]
     MissingMinimap2Preset,
     /// Represents an error when no preset is configured.
     #[error("Minigraph was set as aligner but no preset was configured.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_minigraph_preset),
+        help("pass `--preset` with one of minigraph's supported presets")
+    ))]
     MissingMinigraphPreset,
     /// Represents an error when the strobealign index base file is not found.
     #[error("Strobealign index file provided but matching base file was not found in the same directory (required): {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_strobealign_index_base_file),
+        help("strobealign indices require the matching reference FASTA next to the `.sti` file - copy it into the same directory")
+    ))]
     MissingStrobealignIndexBaseFile(PathBuf),
     /// Represents an error when the input read file is not found.
     #[error("Read input file was not found: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_input_read_file),
+        help("check the path for typos and that the file has not been moved or deleted")
+    ))]
     MissingInputReadFile(PathBuf),
     /// Represents an error when the alignment index file is not found.
     #[error("Alignment index file was not found: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_alignment_index_file),
+        help("build the aligner index first, or point `--index` at an existing one")
+    ))]
     MissingAlignmentIndexFile(PathBuf),
     /// Represents an error when neither small nor large index files for Bowtie2 are found with the specified base path.
     #[error("Neither small nor large index files for Bowtie2 were found with base path: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_bowtie2_index_files),
+        help("Bowtie2 expects `<base>.1.bt2`/`.bt2l` etc. alongside the base path - check it was built with `bowtie2-build`")
+    ))]
     MissingBowtie2IndexFiles(PathBuf),
     /// Represents an error when the classifier index directory is not found.
     #[error("Classifier index directory was not found: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::missing_classifier_index_directory),
+        help("check the path for typos and that the classifier database has been downloaded or built")
+    ))]
     MissingClassifierIndexDirectory(PathBuf),
     /// Represents an error when the specified aligner cannot be executed, possibly due to it not being installed.
     #[error("Aligner `{0}` cannot be executed - is it installed?")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::aligner_dependency_missing),
+        help("install the aligner, e.g. `conda install -c bioconda {0}` or `cargo install {0}` if it ships a Rust binary, and ensure it is on `PATH`")
+    ))]
     AlignerDependencyMissing(Aligner),
     /// Represents an error when the specified classifier cannot be executed, possibly due to it not being installed.
     #[error("Classifier `{0}` cannot be executed - is it installed?")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::classifier_dependency_missing),
+        help("install the classifier, e.g. `conda install -c bioconda {0}`, and ensure it is on `PATH`")
+    ))]
     ClassifierDependencyMissing(Classifier),
+    /// Represents an error when `custom_aligner` names a backend that was never registered.
+    #[error("Custom aligner backend `{0}` is not registered")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::unknown_aligner_backend),
+        help("call `scrubby::backend::register_aligner_backend` with a matching `short_name` before running, or check for a typo")
+    ))]
+    UnknownAlignerBackend(String),
+    /// Represents an error when `custom_classifier` names a backend that was never registered.
+    #[error("Custom classifier backend `{0}` is not registered")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::unknown_classifier_backend),
+        help("call `scrubby::backend::register_classifier_backend` with a matching `short_name` before running, or check for a typo")
+    ))]
+    UnknownClassifierBackend(String),
     /// Represents a failure to count a taxonomic parent during report parsing from `Kraken2`.
-    #[error("failed to provide a parent taxon while parsing report from `Kraken2`")]
-    KrakenReportTaxonParent,
+    #[error("failed to provide a parent taxon while parsing report from `Kraken2` at {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::kraken_report_taxon_parent),
+        help("the Kraken2 report is missing an expected ancestor row - check it was not filtered or truncated")
+    ))]
+    KrakenReportTaxonParent(ParseContext),
     /// Represents a failure to convert the read field from string to numeric field in the report file from `Kraken2`.
-    #[error("failed to convert the read field in the report from `Kraken2`")]
-    KrakenReportReadFieldConversion,
+    #[error("failed to convert the read field in the report from `Kraken2` at {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::kraken_report_read_field_conversion),
+        help("check that the Kraken2 report has not been manually edited or re-delimited")
+    ))]
+    KrakenReportReadFieldConversion(ParseContext),
     /// Represents a failure to convert the direct read field from string to numeric field in the report file from `Kraken2`.
-    #[error("failed to convert the direct read field in the report from `Kraken2`")]
-    KrakenReportDirectReadFieldConversion,
+    #[error("failed to convert the direct read field in the report from `Kraken2` at {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::kraken_report_direct_read_field_conversion),
+        help("check that the Kraken2 report has not been manually edited or re-delimited")
+    ))]
+    KrakenReportDirectReadFieldConversion(ParseContext),
     /// Represents an error when the aligner builder fails for `minimap2-rs`
     #[error("Failed to build aligner with `minimap2-rs`: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::minimap2_rust_aligner_builder_failed),
+        help("check that the reference index path is valid and readable")
+    ))]
     Minimap2RustAlignerBuilderFailed(String),
     /// Represents an error when the aligner builder fails for `minimap2-rs`
     #[error("Failed to align read with `minimap2-rs`: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::minimap2_rust_alignment_failed),
+        help("the read may be malformed, or too short/long for the selected preset")
+    ))]
     Minimap2RustAlignmentFailed(String),
     /// Represents an error when an unsupported preset is set for `minimap2`
     #[error("Preset not supported for `minimap2` or `minimap2-rs`: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::minimap2_preset_not_supported),
+        help("choose one of minimap2's supported presets, e.g. `sr`, `map-ont`, or `map-hifi`")
+    ))]
     Minimap2PresetNotSupported(Preset),
     /// Represents an error when an unsupported preset is set for `minigraph`
     #[error("Preset not supported for `minigraph`: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::minigraph_preset_not_supported),
+        help("choose one of minigraph's supported presets")
+    ))]
     MinigraphPresetNotSupported(Preset),
     /// Represents an error when a model save operation fails
     #[error("failed to save neural network model")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::save_neural_network_model),
+        help("check that the output directory exists and is writable")
+    ))]
     SaveNeuralNetworkModel,
     /// Represents an error when a model read operation fails
     #[error("failed to read neural network model")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::read_neural_network_model),
+        help("check that `--model-weights` points at a file saved by `scrubby nn --train`")
+    ))]
     ReadNeuralNetworkModel,
     /// Represents an error in label extraction function
     #[error("failed to read label from training data file; this should be a numeric suffix to the filename without extensions")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::read_neural_network_model_label),
+        help("name training files like `sample__0.fastq`/`sample__1.fastq`, where the number after the last `__` is the class label")
+    ))]
     ReadNeuralNetworkModelLabel,
     /// Represents an error when a model read operation fails
     #[error("failed to read input sequence file: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::read_neural_network_fastq),
+        help("check that the file exists and is a valid FASTQ/FASTA")
+    ))]
     ReadNeuralNetworkFastq(PathBuf),
+    /// Represents an error when the requested CUDA device is not reachable
+    #[error("requested CUDA device {0} is not available")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::neural_network_cuda_device_unavailable),
+        help("run `scrubby nn --check --device <N>` to find a reachable device, or pass `--device cpu`")
+    ))]
+    NeuralNetworkCudaDeviceUnavailable(usize),
+    /// Represents an error when a `--device` value cannot be parsed
+    #[error("invalid device `{0}`, expected `cpu`, `auto`, or a CUDA device index")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::invalid_compute_device),
+        help("pass `--device cpu`, `--device auto`, or a numeric CUDA device index like `--device 0`")
+    ))]
+    InvalidComputeDevice(String),
+    /// Represents an error when a custom adapter has no argument template for the given read layout
+    #[error("adapter `{0}` does not support the `{1}` read layout")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::adapter_layout_not_supported),
+        help("choose an adapter that supports this read layout, or switch to single/paired-end as required")
+    ))]
+    AdapterLayoutNotSupported(String, String),
+    /// Represents an error when the user-supplied `--strip-suffix` pattern fails to compile.
+    #[error("invalid read identifier suffix pattern: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::invalid_suffix_pattern),
+        help("check the regular expression syntax passed to `--strip-suffix`")
+    ))]
+    InvalidSuffixPattern(#[from] regex::Error),
+    /// Represents an error when `--removed` output paths are set but their count
+    /// does not match the number of `--output` paths.
+    #[error("Removed read output files must be of the same length as the output files.")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::mismatched_removed_output_length),
+        help("pass the same number of `--removed` paths as `--output` paths")
+    ))]
+    MismatchedRemovedOutputLength,
+    /// Represents an error when a `--compression-level` is set above the maximum
+    /// supported by the chosen `--compression-format`.
+    #[error("Compression level {0} exceeds the maximum of {1} supported by '{2}'")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::invalid_compression_level),
+        help("lower `--compression-level` to within the range supported by this format")
+    ))]
+    InvalidCompressionLevel(u32, u32, CompressionAlgorithm),
+    /// Represents an error when `--compression-format` is not one of the supported
+    /// short letters or extension aliases.
+    #[error("Unrecognized compression format '{0}' - supported formats are: {1}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::invalid_compression_format),
+        help("use one of the listed compression formats or extension aliases")
+    ))]
+    InvalidCompressionFormat(String, &'static str),
+    /// Represents an error when a required input path does not exist on disk.
+    ///
+    /// Carries the path as given on the command line, unresolved - paths are
+    /// checked for existence but intentionally not canonicalized, so symlinked
+    /// references in pipelines are not silently resolved.
+    #[error("Path does not exist: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::path_does_not_exist),
+        help("check the path for typos relative to your current working directory")
+    ))]
+    PathDoesNotExist(camino::Utf8PathBuf),
+    /// Represents an error when the R1 and R2 input files of a paired-end
+    /// cleaning run do not contain the same number of records, which would
+    /// otherwise silently desynchronize the two output files.
+    #[error("Paired-end input files are out of sync: R1 has {0} record(s), R2 has {1}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::mismatched_paired_read_count),
+        help("R1 and R2 must contain the same reads in the same order - re-sync or re-interleave them before running scrubby")
+    ))]
+    MismatchedPairedReadCount(u64, u64),
+    /// Represents an error when the R1 and R2 records read at the same
+    /// position have different (normalized) identifiers, meaning the two
+    /// files are out of register even though neither has run out of records yet.
+    #[error("Paired-end input files are out of sync at record {0}: R1 id `{1}` does not match R2 id `{2}`")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::mismatched_read_pair),
+        help("R1 and R2 must contain the same reads in the same order - re-sync or re-interleave them before running scrubby")
+    ))]
+    MismatchedReadPair(u64, String, String),
+    /// Represents an error when `--merge-pairs` or quality/adapter preprocessing
+    /// is requested for paired-end input but the R1 or R2 file is missing or empty.
+    #[error("Paired-end preprocessing input file is missing or empty: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::preprocess_input_missing),
+        help("check that both `--input` files exist and are non-empty FASTQ files")
+    ))]
+    PreprocessInputMissing(PathBuf),
+    /// Represents an error when a `batch --sheet` file has a header row but no
+    /// sample rows beneath it.
+    #[error("Sample sheet is empty: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::empty_sample_sheet),
+        help("add at least one row with 'sample', 'fastq_1' and optionally 'run'/'fastq_2' columns")
+    ))]
+    EmptySampleSheet(PathBuf),
+    /// Represents an error when `--merge-runs` groups sample sheet rows for
+    /// the same sample where only some rows set `fastq_2`, so there is no
+    /// consistent way to concatenate a paired-end R2 stream for the sample.
+    #[error("Sample '{0}' mixes paired-end and single-end runs in the sample sheet")]
+    #[cfg_attr(feature = "miette", diagnostic(
+        code(scrubby::batch_mixed_pairing),
+        help("set 'fastq_2' on every run of a sample, or on none of them")
+    ))]
+    BatchMixedPairing(String),
 }
@@ -1,20 +1,145 @@
-use tch::{nn, nn::Module, nn::OptimizerConfig, Device, Tensor, Kind, no_grad};
-use needletail::{parse_fastx_file, Sequence};
+use tch::{nn, nn::Module, nn::ModuleT, nn::OptimizerConfig, Device, Tensor, Kind, no_grad};
+use needletail::{parse_fastx_file, FastxReader, Sequence};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
-use std::cmp;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::hash::{Hash, Hasher};
 use tch::nn::RNN;
+use serde::{Deserialize, Serialize};
 
 use crate::error::ScrubbyError;
 
 const INPUT_SIZE: i64 = 150; // Length of the DNA sequence
 const NUM_CLASSES: i64 = 5; // Number of classes
-const NUM_CHROMOSOMES: usize = 25; // Assuming chromosomes 1-22, X, Y, and MT
+/// Assuming chromosomes 1-22, X, Y, and MT; exposed so callers can derive the
+/// auxiliary feature width (`NUM_CHROMOSOMES + 2`) without duplicating it.
+pub const NUM_CHROMOSOMES: usize = 25;
 const DROPOUT_PROB: f64 = 0.5; // Dropout probability
+const NUM_BASE_CHANNELS: i64 = 5; // One-hot channels: A, C, G, T, N/other
+const EARLY_STOPPING_PATIENCE: i64 = 5; // Epochs without macro-F1 improvement before stopping
+const DEFAULT_LEARNING_RATE: f64 = 1e-4;
+
+/// User-requested compute device for training and inference, resolved to a
+/// concrete `tch::Device` via [`ComputeDevice::resolve`] so every
+/// tensor-allocating call downstream shares the same source of truth instead
+/// of each hardcoding `Device::Cuda(_)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComputeDevice {
+    Cpu,
+    Cuda(usize),
+    Auto,
+}
+
+impl ComputeDevice {
+    /// Resolves this request to a concrete `tch::Device`, validating a requested
+    /// `Cuda` index with [`check_gpu_connectivity`] and falling back to whatever
+    /// `Device::cuda_if_available` picks for `Auto`.
+    pub fn resolve(&self) -> Result<Device, ScrubbyError> {
+        match self {
+            ComputeDevice::Cpu => Ok(Device::Cpu),
+            ComputeDevice::Cuda(index) => {
+                if check_gpu_connectivity(*index) {
+                    Ok(Device::Cuda(*index))
+                } else {
+                    Err(ScrubbyError::NeuralNetworkCudaDeviceUnavailable(*index))
+                }
+            }
+            ComputeDevice::Auto => Ok(Device::cuda_if_available()),
+        }
+    }
+}
+
+impl std::str::FromStr for ComputeDevice {
+    type Err = ScrubbyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cpu" => Ok(ComputeDevice::Cpu),
+            "auto" => Ok(ComputeDevice::Auto),
+            lowered => {
+                // Accept a bare index ("2") as well as the more explicit
+                // "cuda:2" form, so `--device cuda:2` and `--device 2` agree.
+                let index = lowered.strip_prefix("cuda:").unwrap_or(lowered);
+                index.parse::<usize>()
+                    .map(ComputeDevice::Cuda)
+                    .map_err(|_| ScrubbyError::InvalidComputeDevice(s.to_string()))
+            }
+        }
+    }
+}
+
+/// One CUDA accelerator visible to this process: its index, the name and
+/// memory `nvidia-smi` reports for it. `tch`'s safe API only exposes
+/// [`tch::Cuda::device_count`], not per-device properties, so name and
+/// memory are filled in from `nvidia-smi` on a best-effort basis and zeroed
+/// out (with `name` left as `"unknown"`) when it isn't on `PATH`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuDevice {
+    pub index: usize,
+    pub name: String,
+    pub total_mem_mb: u64,
+    pub free_mem_mb: u64,
+}
+
+/// Enumerates every CUDA device `tch` can bind to, for `--list-devices` and
+/// for reporting which card `--device` actually bound to after `--check`.
+pub fn list_gpu_devices() -> Vec<GpuDevice> {
+    let count = tch::Cuda::device_count().max(0) as usize;
+    let reported = query_nvidia_smi().unwrap_or_default();
+
+    (0..count).map(|index| {
+        reported.iter().find(|device| device.index == index).cloned().unwrap_or(GpuDevice {
+            index,
+            name: "unknown".to_string(),
+            total_mem_mb: 0,
+            free_mem_mb: 0,
+        })
+    }).collect()
+}
+
+/// Prints a formatted table of [`list_gpu_devices`] to stdout for `--list-devices`.
+pub fn print_gpu_devices_table() {
+    let devices = list_gpu_devices();
+    if devices.is_empty() {
+        println!("No CUDA devices detected.");
+        return;
+    }
+    println!("{:<6} {:<30} {:>12} {:>12}", "DEVICE", "NAME", "FREE (MB)", "TOTAL (MB)");
+    for device in devices {
+        println!("{:<6} {:<30} {:>12} {:>12}", format!("cuda:{}", device.index), device.name, device.free_mem_mb, device.total_mem_mb);
+    }
+}
+
+/// Shells out to `nvidia-smi` for per-device name and memory, since `tch`
+/// does not expose these through its safe CUDA bindings. Returns `None` if
+/// `nvidia-smi` is missing or exits with an error, so callers can fall back
+/// to reporting device indices alone.
+fn query_nvidia_smi() -> Option<Vec<GpuDevice>> {
+    let output = std::process::Command::new("nvidia-smi")
+        .args(["--query-gpu=index,name,memory.total,memory.free", "--format=csv,noheader,nounits"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let devices = String::from_utf8_lossy(&output.stdout).lines().filter_map(|line| {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [index, name, total_mem_mb, free_mem_mb] = fields[..] else { return None };
+        Some(GpuDevice {
+            index: index.parse().ok()?,
+            name: name.to_string(),
+            total_mem_mb: total_mem_mb.parse().ok()?,
+            free_mem_mb: free_mem_mb.parse().ok()?,
+        })
+    }).collect();
+
+    Some(devices)
+}
 
 enum AuxDataOption {
     Include,
@@ -24,7 +149,7 @@ enum AuxDataOption {
 
 #[derive(Debug)]
 struct HybridModel {
-    cnn: nn::Sequential,
+    cnn: nn::SequentialT,
     _cnn_layers: Vec<String>,
     lstm: Option<nn::LSTM>,
     fc: nn::Linear,
@@ -34,6 +159,7 @@ struct HybridModel {
 impl HybridModel {
     fn new(
         vs: &nn::Path,
+        device: Device,
         input_size: i64,
         hidden_size: i64,
         num_classes: i64,
@@ -43,27 +169,31 @@ impl HybridModel {
     ) -> Self {
 
         let _cnn_layers = Vec::from([
-            "Conv1D(1, 32, 3)".to_string(),
+            format!("Conv1D({}, 32, 3)", NUM_BASE_CHANNELS),
+            "BatchNorm1D(32)".to_string(),
             "ReLU".to_string(),
             "MaxPool1D(2)".to_string(),
             "Conv1D(32, 64, 3)".to_string(),
+            "BatchNorm1D(64)".to_string(),
             "ReLU".to_string(),
             "MaxPool1D(2)".to_string()
         ]);
 
-        let cnn = nn::seq()
-            .add(nn::conv1d(vs / "cnn1", 1, 32, 3, Default::default()))
+        let cnn = nn::seq_t()
+            .add(nn::conv1d(vs / "cnn1", NUM_BASE_CHANNELS, 32, 3, Default::default()))
+            .add(nn::batch_norm1d(vs / "bn1", 32, Default::default()))
             .add_fn(|x| x.relu())
             .add_fn(|x| x.max_pool1d(2, 2, 0, 1, false))
             .add(nn::conv1d(vs / "cnn2", 32, 64, 3, Default::default()))
+            .add(nn::batch_norm1d(vs / "bn2", 64, Default::default()))
             .add_fn(|x| x.relu())
             .add_fn(|x| x.max_pool1d(2, 2, 0, 1, false));
 
         // Get the output length after the CNN layers
         let cnn_output_size = {
-            let input = Tensor::zeros(&[1, 1, input_size], (tch::Kind::Float, Device::cuda_if_available()));
-            let output = cnn.forward(&input);
-            output.size()[1]  
+            let input = Tensor::zeros(&[1, NUM_BASE_CHANNELS, input_size], (tch::Kind::Float, device));
+            let output = cnn.forward_t(&input, false);
+            output.size()[1]
         };
 
         log::info!("CNN output size for input dimension for LSTM: {}", cnn_output_size);
@@ -103,8 +233,8 @@ impl HybridModel {
             aux_fc,
         }
     }
-    fn forward(&self, xs: &Tensor, aux_input: Option<&Tensor>) -> Tensor {
-        let cnn_out = self.cnn.forward(&xs.view([xs.size()[0], 1, INPUT_SIZE]));
+    fn forward(&self, xs: &Tensor, aux_input: Option<&Tensor>, train: bool) -> Tensor {
+        let cnn_out = self.cnn.forward_t(&xs.view([xs.size()[0], NUM_BASE_CHANNELS, INPUT_SIZE]), train);
 
         // Print the shape of cnn_out
         // log::info!("CNN output shape before view: {:?}", cnn_out.size());
@@ -138,9 +268,18 @@ impl HybridModel {
         return logits
     }
 
-    fn forward_with_softmax(&self, xs: &Tensor, aux_input: Option<&Tensor>) -> Tensor {
-        let logits = self.forward(xs, aux_input);
-        logits.softmax(-1, Kind::Float)
+    /// Runs `forward` and converts logits to class probabilities. When
+    /// `quiet` is set, uses [`quiet_softmax`] instead of the ordinary
+    /// softmax so a read that matches none of the trained classes can
+    /// receive near-zero probability across the board, rather than having
+    /// probability mass forced to sum to 1 regardless of fit.
+    fn forward_with_softmax(&self, xs: &Tensor, aux_input: Option<&Tensor>, quiet: bool) -> Tensor {
+        let logits = self.forward(xs, aux_input, false);
+        if quiet {
+            quiet_softmax(&logits, -1)
+        } else {
+            logits.softmax(-1, Kind::Float)
+        }
     }
 }
 
@@ -178,6 +317,30 @@ fn get_label_from_filename(file_path: &PathBuf) -> Result<i64, ScrubbyError> {
     }
 }
 
+/// One-hot encodes the first `INPUT_SIZE` bases of `seq` into a
+/// `[1, NUM_BASE_CHANNELS, INPUT_SIZE]` tensor, channel 0-3 for A/C/G/T and
+/// channel 4 for N or any other base, so the CNN sees a biologically
+/// meaningful input instead of raw ASCII byte values.
+fn one_hot_encode_bases(seq: &[u8], device: Device) -> Tensor {
+    let mut data = vec![0f32; (NUM_BASE_CHANNELS * INPUT_SIZE) as usize];
+
+    for (i, &base) in seq.iter().take(INPUT_SIZE as usize).enumerate() {
+        let channel = match base.to_ascii_uppercase() {
+            b'A' => 0,
+            b'C' => 1,
+            b'G' => 2,
+            b'T' => 3,
+            _ => 4,
+        };
+        data[channel * INPUT_SIZE as usize + i] = 1.0;
+    }
+
+    Tensor::from_slice(&data)
+        .to_device(device)
+        .view([NUM_BASE_CHANNELS, INPUT_SIZE])
+        .unsqueeze(0)
+}
+
 fn load_sequences(device: Device, file_path: &PathBuf, alignment_info: Option<&HashMap<String, (i64, i64, i64)>>, num_chromosomes: usize) -> Result<(Vec<Tensor>, Vec<Tensor>, Option<Vec<Tensor>>), ScrubbyError> {
     
     let mut seqs = Vec::new();
@@ -199,11 +362,7 @@ fn load_sequences(device: Device, file_path: &PathBuf, alignment_info: Option<&H
             continue;
         }
             
-        let seq_tensor = Tensor::from_slice(&seq)
-            .to_device(device)
-            .to_kind(tch::Kind::Float)
-            .unsqueeze(0)
-            .unsqueeze(0);
+        let seq_tensor = one_hot_encode_bases(&seq, device);
         
         if let Some(alignment_info) = alignment_info {
             let read_id = std::str::from_utf8(record.id())?.to_string();
@@ -243,26 +402,246 @@ fn load_sequences(device: Device, file_path: &PathBuf, alignment_info: Option<&H
     }
 }
 
-fn predict(model: &HybridModel, seqs: Vec<Tensor>, aux_inputs: Option<Vec<Tensor>>) -> i64 {
+/// Reverse-complements `seq`, reversing the base order and mapping A<->T,
+/// C<->G, with N (and any other ambiguity code) left as N, so a strand's
+/// mirror image can be trained on as its own example.
+fn reverse_complement(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&base| match base.to_ascii_uppercase() {
+        b'A' => b'T',
+        b'T' => b'A',
+        b'C' => b'G',
+        b'G' => b'C',
+        _ => b'N',
+    }).collect()
+}
+
+/// Tiles `seq` into overlapping `window`-sized slices spaced `stride` bases
+/// apart, instead of truncating a long read down to its first `window`
+/// bases. Reads no longer than `window` pass through as their own single window.
+fn sliding_windows(seq: &[u8], window: usize, stride: usize) -> Vec<Vec<u8>> {
+    if seq.len() <= window {
+        return vec![seq.to_vec()];
+    }
+
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while start + window <= seq.len() {
+        windows.push(seq[start..start + window].to_vec());
+        start += stride;
+    }
+    windows
+}
+
+/// Streams one-hot-encoded batches from a fixed list of labelled FASTQ files
+/// without holding the full dataset in memory, opening and advancing through
+/// files lazily so a batch can span a file boundary. Used in place of
+/// eagerly `load_sequences`-ing every training file up front, which scales
+/// memory with the total dataset size.
+struct DataLoader {
+    files: Vec<PathBuf>,
+    alignment_info: Option<HashMap<String, (i64, i64, i64)>>,
+    device: Device,
+    batch_size: usize,
+    file_pos: usize,
+    reader: Option<Box<dyn FastxReader>>,
+    current_label: i64,
+    /// When set, each read is expanded into overlapping `INPUT_SIZE` windows
+    /// (tiling reads longer than `INPUT_SIZE` instead of truncating them) and
+    /// every window's reverse complement is emitted alongside it. Intended
+    /// for the training split only - test/val loaders leave this unset so
+    /// evaluation sees one, untransformed example per read.
+    augment: bool,
+    window_stride: usize,
+    pending: std::collections::VecDeque<(Vec<u8>, String, i64)>,
+}
+
+impl DataLoader {
+    fn new(
+        files: Vec<PathBuf>,
+        alignment_info: Option<HashMap<String, (i64, i64, i64)>>,
+        device: Device,
+        batch_size: usize,
+        augment: bool,
+    ) -> Self {
+        DataLoader {
+            files,
+            alignment_info,
+            device,
+            batch_size,
+            file_pos: 0,
+            reader: None,
+            current_label: 0,
+            augment,
+            window_stride: (INPUT_SIZE / 2) as usize,
+            pending: std::collections::VecDeque::new(),
+        }
+    }
+
+    /// Shuffles the file order and resets the read position, for per-epoch reshuffling.
+    fn shuffle(&mut self) {
+        self.files.shuffle(&mut thread_rng());
+        self.reset();
+    }
+
+    /// Rewinds to the first file without changing file order.
+    fn reset(&mut self) {
+        self.file_pos = 0;
+        self.reader = None;
+        self.pending.clear();
+    }
+
+    fn next_record(&mut self) -> Result<Option<(Vec<u8>, String, i64)>, ScrubbyError> {
+        loop {
+            if let Some(example) = self.pending.pop_front() {
+                return Ok(Some(example));
+            }
+
+            if self.reader.is_none() {
+                if self.file_pos >= self.files.len() {
+                    return Ok(None);
+                }
+                let file_path = &self.files[self.file_pos];
+                self.current_label = get_label_from_filename(file_path)?;
+                self.reader = Some(parse_fastx_file(file_path).map_err(
+                    |_| ScrubbyError::ReadNeuralNetworkFastq(file_path.to_path_buf())
+                )?);
+            }
+
+            let reader = self.reader.as_mut().expect("reader set above");
+
+            match reader.next() {
+                Some(record) => {
+                    let record = record?;
+                    if record.num_bases() < INPUT_SIZE as usize {
+                        log::warn!("Read is smaller with {} bp than expected input size of {} bp", record.num_bases(), INPUT_SIZE);
+                        continue;
+                    }
+                    let seq = record.normalize(false).into_owned();
+                    let read_id = std::str::from_utf8(record.id())?.to_string();
+
+                    if self.augment {
+                        for window in sliding_windows(&seq, INPUT_SIZE as usize, self.window_stride) {
+                            let rc = reverse_complement(&window);
+                            self.pending.push_back((window, read_id.clone(), self.current_label));
+                            self.pending.push_back((rc, read_id.clone(), self.current_label));
+                        }
+                    } else {
+                        self.pending.push_back((seq, read_id, self.current_label));
+                    }
+                }
+                None => {
+                    self.file_pos += 1;
+                    self.reader = None;
+                }
+            }
+        }
+    }
+
+    /// Lazily parses and one-hot-encodes just enough reads, spanning file
+    /// boundaries if necessary, to fill one batch. Returns `None` once every
+    /// file has been exhausted.
+    fn next_batch(&mut self) -> Option<(Tensor, Tensor, Option<Tensor>)> {
+        let mut seqs = Vec::new();
+        let mut labels = Vec::new();
+        let mut aux_inputs = Vec::new();
+
+        for _ in 0..self.batch_size {
+            let (seq, read_id, label) = match self.next_record() {
+                Ok(Some(record)) => record,
+                Ok(None) => break,
+                Err(error) => {
+                    log::warn!("Failed to read record, skipping remainder of batch: {}", error);
+                    break;
+                }
+            };
+
+            seqs.push(one_hot_encode_bases(&seq, self.device));
+            labels.push(
+                Tensor::from_slice(&[label])
+                    .to_device(self.device)
+                    .to_kind(tch::Kind::Int64)
+            );
+
+            if let Some(alignment_info) = &self.alignment_info {
+                let aux_input = match alignment_info.get(&read_id) {
+                    Some(&(chromosome, start, end)) => {
+                        let chrom_tensor = Tensor::zeros(&[NUM_CHROMOSOMES as i64], (Kind::Float, self.device))
+                            .narrow(0, chromosome, 1)
+                            .fill_(1.0);
+                        let start_tensor = Tensor::from_slice(&[start as f32]).to_device(self.device);
+                        let end_tensor = Tensor::from_slice(&[end as f32]).to_device(self.device);
+                        Tensor::cat(&[chrom_tensor, start_tensor, end_tensor], 0).unsqueeze(0)
+                    }
+                    None => Tensor::zeros(&[1, (NUM_CHROMOSOMES + 2) as i64], (Kind::Float, self.device)),
+                };
+                aux_inputs.push(aux_input);
+            }
+        }
+
+        if seqs.is_empty() {
+            return None;
+        }
+
+        let batch_seqs = Tensor::cat(&seqs, 0);
+        let batch_labels = Tensor::cat(&labels, 0);
+        let batch_aux = if aux_inputs.is_empty() { None } else { Some(Tensor::cat(&aux_inputs, 0)) };
+
+        Some((batch_seqs, batch_labels, batch_aux))
+    }
+}
+
+/// Numerically stable "quiet" softmax: `exp(x_i) / (1 + sum_j exp(x_j))`,
+/// equivalent to appending a virtual zero-logit extra class to an ordinary
+/// softmax and dropping it afterwards. Unlike ordinary softmax, the
+/// resulting probabilities need not sum to 1, so every class can be
+/// near-zero when the input logits are all weak - the common case for a
+/// host depletion read that is genuinely novel to every trained class.
+/// Subtracts `max(x)` from the logits and adds `exp(-max(x))` to the
+/// denominator for numerical stability.
+fn quiet_softmax(logits: &Tensor, dim: i64) -> Tensor {
+    let max = logits.amax(&[dim], true);
+    let shifted = logits - &max;
+    let exp_shifted = shifted.exp();
+    let denom = exp_shifted.sum_dim_intlist(Some([dim].as_ref()), true, Kind::Float) + (-&max).exp();
+    exp_shifted / denom
+}
+
+/// Averages the class probabilities of every sequence in `seqs` (in
+/// `predict_nn`, one per read of an input file) and returns the single
+/// predicted class, or `None` ("unclassified") when the top probability
+/// falls below `threshold` - typically used with `quiet` softmax, where a
+/// read matching none of the trained classes yields low probability across
+/// the board instead of one being forced to dominate.
+fn predict(model: &HybridModel, seqs: Vec<Tensor>, aux_inputs: Option<Vec<Tensor>>, quiet: bool, threshold: f64) -> Option<i64> {
     let mut all_predictions = Vec::new();
 
     no_grad(|| {
         for (i, seq) in seqs.into_iter().enumerate() {
             let aux_input = aux_inputs.as_ref().map(|aux| &aux[i]);
-            let logits = model.forward_with_softmax(&seq, aux_input);
+            let logits = model.forward_with_softmax(&seq, aux_input, quiet);
             all_predictions.push(logits);
         }
     });
 
     let all_predictions = Tensor::cat(&all_predictions, 0);
     let average_predictions = all_predictions.mean_dim(Some([0].as_ref()), true, Kind::Float);
-    let probabilities = average_predictions.softmax(1, Kind::Float);
+    let probabilities = if quiet {
+        average_predictions.shallow_clone()
+    } else {
+        average_predictions.softmax(1, Kind::Float)
+    };
     let final_prediction = probabilities.argmax(-1, false);
+    let top_probability = probabilities.max().double_value(&[]);
 
     log::info!("Average predictions: {}", average_predictions);
     log::info!("Final prediction: {}", final_prediction);
 
-    final_prediction.int64_value(&[])
+    if top_probability < threshold {
+        log::info!("Top probability {:.4} is below threshold {:.4}; read left unclassified", top_probability, threshold);
+        None
+    } else {
+        Some(final_prediction.int64_value(&[]))
+    }
 }
 
 
@@ -280,50 +659,102 @@ fn one_hot_encode(device: Device, labels: &Tensor, num_classes: i64, kind: Kind)
     one_hot
 }
 
+/// Hyperparameters and input fingerprints recorded alongside `model_weights`
+/// after every epoch, so a restarted `train_nn` can tell whether it is safe
+/// to resume from the last completed epoch or whether the run has changed
+/// underneath it and the checkpoint must be discarded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct TrainingManifest {
+    epochs_completed: i64,
+    batch_size: usize,
+    learning_rate: f64,
+    use_lstm: bool,
+    aux_input_size: Option<i64>,
+    fastq_fingerprint: String,
+    alignment_fingerprint: Option<String>,
+}
+
+impl TrainingManifest {
+    /// Compares everything but `epochs_completed` - the one field that is
+    /// *expected* to differ between the checkpoint on disk and a freshly
+    /// constructed candidate for the current run.
+    fn same_run(&self, other: &Self) -> bool {
+        self.batch_size == other.batch_size
+            && self.learning_rate == other.learning_rate
+            && self.use_lstm == other.use_lstm
+            && self.aux_input_size == other.aux_input_size
+            && self.fastq_fingerprint == other.fastq_fingerprint
+            && self.alignment_fingerprint == other.alignment_fingerprint
+    }
+}
+
+/// Sidecar path for a checkpoint's [`TrainingManifest`], alongside the model weights.
+fn checkpoint_manifest_path(model_weights: &Path) -> PathBuf {
+    let mut name = model_weights.as_os_str().to_os_string();
+    name.push(".manifest.json");
+    PathBuf::from(name)
+}
+
+fn read_manifest(path: &Path) -> Option<TrainingManifest> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_manifest(manifest: &TrainingManifest, path: &Path) -> Result<(), ScrubbyError> {
+    std::fs::write(path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+/// Fingerprints `paths` by name, size and modification time, so a checkpoint
+/// is only resumed when it was trained on the exact same input files -
+/// swapping in a different (same-named) FASTQ or alignment file invalidates
+/// the checkpoint rather than silently resuming against the wrong data.
+fn fingerprint_paths(paths: &[PathBuf]) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for path in paths {
+        path.hash(&mut hasher);
+        if let Ok(metadata) = std::fs::metadata(path) {
+            metadata.len().hash(&mut hasher);
+            if let Ok(modified) = metadata.modified() {
+                modified.hash(&mut hasher);
+            }
+        }
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Trains `model` from `start_epoch` up to `epochs` epochs, evaluating on
+/// `test_loader` after each one and saving `vs` to `model_weights` whenever
+/// macro-F1 improves, so the checkpoint on disk is always the best epoch
+/// seen rather than simply the last. `manifest` is updated and written to
+/// `manifest_path` after every epoch (regardless of improvement) so a
+/// restart can resume from the last attempted epoch. Stops early once
+/// `EARLY_STOPPING_PATIENCE` epochs pass without improvement, then restores
+/// the best checkpoint.
 fn train(
     model: &HybridModel,
     device: Device,
-    vs: &nn::VarStore,
-    sequences: &[Tensor],
-    labels: &[Tensor],
-    aux_inputs: Option<&[Tensor]>,
-    test_sequences: &[Tensor],
-    test_labels: &[Tensor],
-    test_aux_inputs: Option<&[Tensor]>,
+    vs: &mut nn::VarStore,
+    train_loader: &mut DataLoader,
+    test_loader: &mut DataLoader,
+    start_epoch: i64,
     epochs: i64,
-    batch_size: usize,
+    model_weights: &Path,
+    manifest: &mut TrainingManifest,
+    manifest_path: &Path,
 ) {
-    let mut optimizer = nn::Adam::default().build(&vs, 1e-4).unwrap();
-
-    for epoch in 0..epochs {
-        let mut batch_indices: Vec<usize> = (0..sequences.len()).collect();
-        batch_indices.shuffle(&mut thread_rng());
-
-        for batch_start in (0..sequences.len()).step_by(batch_size) {
-            let batch_end = cmp::min(batch_start + batch_size, sequences.len());
-
-            let batch_seqs: Vec<_> = batch_indices[batch_start..batch_end]
-                .iter()
-                .map(|&i| sequences[i].unsqueeze(0))
-                .collect();
-            let batch_labels: Vec<_> = batch_indices[batch_start..batch_end]
-                .iter()
-                .map(|&i| labels[i].unsqueeze(0))
-                .collect();
-
-
-            let batch_seqs = Tensor::cat(&batch_seqs, 0);
-            let batch_labels = Tensor::cat(&batch_labels, 0).squeeze_dim(1);
-
-            let output = if let Some(aux_inputs) = aux_inputs {
-                let batch_aux: Vec<_> = batch_indices[batch_start..batch_end]
-                    .iter()
-                    .map(|&i| aux_inputs[i].unsqueeze(0))
-                    .collect();
-                let batch_aux = Tensor::cat(&batch_aux, 0);
-                model.forward(&batch_seqs, Some(&batch_aux))
-            } else {
-                model.forward(&batch_seqs, None)
+    let mut optimizer = nn::Adam::default().build(&vs, manifest.learning_rate).unwrap();
+
+    let mut best_macro_f1 = f64::MIN;
+    let mut epochs_without_improvement = 0;
+
+    for epoch in start_epoch..epochs {
+        train_loader.shuffle();
+
+        while let Some((batch_seqs, batch_labels, batch_aux)) = train_loader.next_batch() {
+            let output = match &batch_aux {
+                Some(batch_aux) => model.forward(&batch_seqs, Some(batch_aux), true),
+                None => model.forward(&batch_seqs, None, true),
             };
 
             let loss = output.cross_entropy_loss(&one_hot_encode(device, &batch_labels, NUM_CLASSES, Kind::Int64), None::<&Tensor>, tch::Reduction::Mean, -100, 0.0);
@@ -336,23 +767,134 @@ fn train(
         }
 
         // Evaluate on the test set after each epoch
-        let test_accuracy = evaluate(model, test_sequences, test_labels, test_aux_inputs);
-        log::info!("Epoch: {}, Test Accuracy: {:.2}%", epoch, test_accuracy * 100.0);
+        let report = evaluate(model, test_loader);
+        log::info!(
+            "Epoch: {}, Test Accuracy: {:.2}%, Macro F1: {:.4}, Weighted F1: {:.4}",
+            epoch, report.accuracy * 100.0, report.macro_f1, report.weighted_f1
+        );
+        for class in 0..report.f1.len() {
+            log::info!(
+                "  Class {}: precision {:.4}, recall {:.4}, F1 {:.4}",
+                class, report.precision[class], report.recall[class], report.f1[class]
+            );
+        }
+        log::debug!("Confusion matrix: {:?}", report.confusion_matrix);
+
+        crate::utils::log_json_event("info", "nn", serde_json::json!({
+            "epoch": epoch,
+            "accuracy": report.accuracy,
+            "macro_f1": report.macro_f1,
+            "weighted_f1": report.weighted_f1,
+        }));
+
+        if report.macro_f1 > best_macro_f1 {
+            best_macro_f1 = report.macro_f1;
+            epochs_without_improvement = 0;
+            if let Err(error) = vs.save(model_weights) {
+                log::warn!("Failed to save checkpoint at epoch {}: {}", epoch, error);
+            }
+        } else {
+            epochs_without_improvement += 1;
+        }
+
+        manifest.epochs_completed = epoch + 1;
+        if let Err(error) = write_manifest(manifest, manifest_path) {
+            log::warn!("Failed to write checkpoint manifest at epoch {}: {}", epoch, error);
+        }
+
+        if epochs_without_improvement >= EARLY_STOPPING_PATIENCE {
+            log::info!("Stopping early at epoch {} after {} epochs without macro-F1 improvement", epoch, EARLY_STOPPING_PATIENCE);
+            break;
+        }
+    }
+
+    if let Err(error) = vs.load(model_weights) {
+        log::warn!("Failed to restore best checkpoint after training: {}", error);
+    }
+}
+
+/// Resolves `device`, falling back to the CPU (with a warning) instead of
+/// failing outright when `allow_cpu_fallback` is set and the requested
+/// device cannot be bound - useful on shared hardware where a GPU might be
+/// busy or simply absent on the node a job lands on.
+fn resolve_with_fallback(device: ComputeDevice, allow_cpu_fallback: bool) -> Result<Device, ScrubbyError> {
+    match device.resolve() {
+        Ok(resolved) => Ok(resolved),
+        Err(error) if allow_cpu_fallback => {
+            log::warn!("Requested device unavailable ({error}), falling back to CPU");
+            Ok(Device::Cpu)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Runs `work` with `batch_size`, halving it and retrying on a CUDA
+/// out-of-memory failure until `min_batch_size` is reached. `tch` surfaces
+/// most libtorch failures, OOM included, as Rust panics rather than
+/// `Result::Err`, so retries are driven by `catch_unwind` and a
+/// string-match on the panic payload rather than a typed error variant.
+fn run_with_oom_backoff<F>(batch_size: usize, min_batch_size: usize, mut work: F) -> Result<(), ScrubbyError>
+where
+    F: FnMut(usize) -> Result<(), ScrubbyError>,
+{
+    let mut batch_size = batch_size.max(1);
+    let min_batch_size = min_batch_size.max(1);
+
+    loop {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| work(batch_size))) {
+            Ok(result) => return result,
+            Err(payload) => {
+                if batch_size <= min_batch_size || !is_oom_panic(&payload) {
+                    std::panic::resume_unwind(payload);
+                }
+                batch_size = (batch_size / 2).max(min_batch_size);
+                log::warn!("CUDA out of memory, retrying with batch size {batch_size}");
+            }
+        }
     }
 }
 
+/// Classifies a caught panic payload as a CUDA out-of-memory failure by
+/// string-matching libtorch's error message, since `tch` does not expose a
+/// typed OOM variant.
+fn is_oom_panic(payload: &(dyn std::any::Any + Send)) -> bool {
+    let message = payload.downcast_ref::<String>().cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_default()
+        .to_lowercase();
+    message.contains("out of memory") || message.contains("cuda error")
+}
+
 pub fn train_nn(
-    device: usize,
+    device: ComputeDevice,
     fastq_files: Vec<PathBuf>,
     model_weights: PathBuf,
     alignment_data: Option<PathBuf>,
     epochs: i64,
     batch_size: usize,
+    allow_cpu_fallback: bool,
+    min_batch_size: usize,
+    resume: bool,
 ) -> Result<(), ScrubbyError> {
 
-    let device = Device::Cuda(device);
-    
-    let vs = nn::VarStore::new(device);
+    let device = resolve_with_fallback(device, allow_cpu_fallback)?;
+
+    run_with_oom_backoff(batch_size, min_batch_size, |batch_size| {
+        train_nn_once(device, &fastq_files, &model_weights, alignment_data.as_deref(), epochs, batch_size, resume)
+    })
+}
+
+fn train_nn_once(
+    device: Device,
+    fastq_files: &[PathBuf],
+    model_weights: &Path,
+    alignment_data: Option<&Path>,
+    epochs: i64,
+    batch_size: usize,
+    resume: bool,
+) -> Result<(), ScrubbyError> {
+
+    let mut vs = nn::VarStore::new(device);
 
     log::info!("Device is CUDA: {:?}", device.is_cuda());
 
@@ -365,79 +907,78 @@ pub fn train_nn(
         AuxDataOption::Exclude => None,
     };
 
-    let model = HybridModel::new(&vs.root(), INPUT_SIZE, 128, NUM_CLASSES, aux_input_size, true, true);
+    let model = HybridModel::new(&vs.root(), device, INPUT_SIZE, 128, NUM_CLASSES, aux_input_size, true, true);
 
     let alignment_info = if matches!(aux_data_option, AuxDataOption::Include) {
-        Some(load_alignment_info(&alignment_data.ok_or(ScrubbyError::ReadNeuralNetworkModel)?))
+        Some(load_alignment_info(alignment_data.ok_or(ScrubbyError::ReadNeuralNetworkModel)?))
     } else {
         None
     };
 
-    let mut all_sequences = Vec::new();
-    let mut all_labels = Vec::new();
-    let mut all_aux_inputs = Vec::new();
-    let mut has_aux_inputs = false;
-
-    for file_path in fastq_files {
-        let (sequences, labels, aux_inputs) = load_sequences(device, &file_path, alignment_info.as_ref(), NUM_CHROMOSOMES)?;
-        all_sequences.extend(sequences);
-        all_labels.extend(labels);
-        if let Some(aux) = aux_inputs {
-            all_aux_inputs.extend(aux);
-            has_aux_inputs = true;
-        }
-    }
+    // Split the input FASTQ files into train/test/val sets by file position,
+    // rather than loading every read into memory first.
+    let (train_indices, test_indices, val_indices) = train_test_val_split(fastq_files.len(), 0.7, 0.15);
 
-    let aux_inputs = if has_aux_inputs { Some(all_aux_inputs) } else { None };
+    let gather_files = |indices: &[usize]| -> Vec<PathBuf> {
+        indices.iter().map(|&i| fastq_files[i].clone()).collect()
+    };
 
-    // Get the indices for train, test, and validation splits
-    let (train_indices, test_indices, val_indices) = train_test_val_split(all_sequences.len(), 0.7, 0.15);
+    let mut train_loader = DataLoader::new(gather_files(&train_indices), alignment_info.clone(), device, batch_size, true);
+    let mut test_loader = DataLoader::new(gather_files(&test_indices), alignment_info.clone(), device, batch_size, false);
+    let mut val_loader = DataLoader::new(gather_files(&val_indices), alignment_info, device, batch_size, false);
 
-    // Function to gather tensors based on indices
-    let gather_tensors = |indices: &[usize], data: &[Tensor]| -> Vec<Tensor> {
-        indices.iter().map(|&i| data[i].shallow_clone()).collect()
+    let manifest_path = checkpoint_manifest_path(model_weights);
+    let candidate = TrainingManifest {
+        epochs_completed: 0,
+        batch_size,
+        learning_rate: DEFAULT_LEARNING_RATE,
+        use_lstm: true,
+        aux_input_size,
+        fastq_fingerprint: fingerprint_paths(fastq_files),
+        alignment_fingerprint: alignment_data.map(|path| fingerprint_paths(&[path.to_path_buf()])),
     };
 
-    let train_sequences = gather_tensors(&train_indices, &all_sequences);
-    let test_sequences = gather_tensors(&test_indices, &all_sequences);
-    let val_sequences = gather_tensors(&val_indices, &all_sequences);
-
-    let train_labels = gather_tensors(&train_indices, &all_labels);
-    let test_labels = gather_tensors(&test_indices, &all_labels);
-    let val_labels = gather_tensors(&val_indices, &all_labels);
-
-    let train_aux_inputs = aux_inputs.as_ref().map(|aux| gather_tensors(&train_indices, aux));
-    let test_aux_inputs = aux_inputs.as_ref().map(|aux| gather_tensors(&test_indices, aux));
-    let val_aux_inputs = aux_inputs.as_ref().map(|aux| gather_tensors(&val_indices, aux));
-
-    train(
-        &model,
-        device,
-        &vs,
-        &train_sequences,
-        &train_labels,
-        train_aux_inputs.as_deref(),
-        &test_sequences,
-        &test_labels,
-        test_aux_inputs.as_deref(),
-        epochs,
-        batch_size,
-    );
+    let mut manifest = candidate.clone();
+    let mut start_epoch = 0;
+
+    if resume {
+        if let Some(existing) = read_manifest(&manifest_path) {
+            if existing.same_run(&candidate) && vs.load(model_weights).is_ok() {
+                start_epoch = existing.epochs_completed;
+                manifest = existing;
+                log::info!("Resuming training from epoch {start_epoch} (checkpoint manifest matched)");
+            } else {
+                log::info!("Discarding stale checkpoint: hyperparameters or input files changed since last run");
+                let _ = std::fs::remove_file(model_weights);
+                let _ = std::fs::remove_file(&manifest_path);
+            }
+        }
+    } else {
+        let _ = std::fs::remove_file(&manifest_path);
+    }
+
+    if start_epoch >= epochs {
+        log::info!("Checkpoint already completed all {epochs} requested epoch(s); nothing to do");
+        return Ok(());
+    }
 
-    // Evaluate on the validation set
-    let val_accuracy = evaluate(&model, &val_sequences, &val_labels, val_aux_inputs.as_deref());
-    log::info!("Final Validation Accuracy: {:.2}%", val_accuracy * 100.0);
+    train(&model, device, &mut vs, &mut train_loader, &mut test_loader, start_epoch, epochs, model_weights, &mut manifest, &manifest_path);
 
-    vs.save(model_weights).map_err(|_| ScrubbyError::SaveNeuralNetworkModel)?;
+    // Evaluate on the validation set with the best checkpoint restored by `train`
+    let val_report = evaluate(&model, &mut val_loader);
+    log::info!(
+        "Final Validation Accuracy: {:.2}%, Macro F1: {:.4}, Weighted F1: {:.4}",
+        val_report.accuracy * 100.0, val_report.macro_f1, val_report.weighted_f1
+    );
 
     Ok(())
 }
 
 
 
-pub fn predict_nn(device: usize, model_weights: PathBuf, fastq: Vec<PathBuf>, alignment_data: Option<PathBuf>) -> Result<(), ScrubbyError>{
+pub fn predict_nn(device: ComputeDevice, model_weights: PathBuf, fastq: Vec<PathBuf>, alignment_data: Option<PathBuf>, quiet: bool, threshold: f64, allow_cpu_fallback: bool) -> Result<(), ScrubbyError>{
 
-    let device = Device::Cuda(device);
+    let device = resolve_with_fallback(device, allow_cpu_fallback)?;
     let mut vs = nn::VarStore::new(device);
 
     let aux_data_option = AuxDataOption::Exclude; // Set to AuxDataOption::Exclude to exclude auxiliary alignment data
@@ -446,7 +987,7 @@ pub fn predict_nn(device: usize, model_weights: PathBuf, fastq: Vec<PathBuf>, al
         AuxDataOption::Exclude => None,
     };
 
-    let model = HybridModel::new(&vs.root(), INPUT_SIZE, 128, NUM_CLASSES, aux_input_size, true, false);
+    let model = HybridModel::new(&vs.root(), device, INPUT_SIZE, 128, NUM_CLASSES, aux_input_size, true, false);
     
     vs.load(model_weights).expect("Failed to load model weights");
     
@@ -460,24 +1001,72 @@ pub fn predict_nn(device: usize, model_weights: PathBuf, fastq: Vec<PathBuf>, al
 
         log::info!("Loading read tensors: {}", fastq_path.display());
         let (seqs, _, aux_inputs) = load_sequences(device, &fastq_path, alignment_info.as_ref(), NUM_CHROMOSOMES)?;
-        let final_class = predict(&model, seqs, aux_inputs);
-        log::info!("Predicted class: {}", final_class);
+        match predict(&model, seqs, aux_inputs, quiet, threshold) {
+            Some(final_class) => log::info!("Predicted class: {}", final_class),
+            None => log::info!("Unclassified: no class met the probability threshold"),
+        }
 
     }
 
     Ok(())
 }
 
+/// Traces `model`'s forward pass into a self-contained TorchScript module and
+/// writes it to `output`, so the trained classifier can be served without
+/// requiring this crate's `nn::VarStore`/`HybridModel` definitions. True ONNX
+/// export is not available from libtorch's Rust/C++ bindings - the exporter
+/// lives in PyTorch's Python layer - so this ships the closest portable
+/// equivalent: a traced module with a dynamic batch dimension that downstream
+/// pipelines can load directly via `torch::jit::load`, or convert to ONNX with
+/// the usual `torch.onnx` tooling if they need it in that exact format.
+pub fn export_onnx(
+    model_weights: PathBuf,
+    output: PathBuf,
+    use_lstm: bool,
+    aux_input_size: Option<i64>,
+) -> Result<(), ScrubbyError> {
+
+    let device = Device::Cpu;
+    let mut vs = nn::VarStore::new(device);
+    let model = HybridModel::new(&vs.root(), device, INPUT_SIZE, 128, NUM_CLASSES, aux_input_size, use_lstm, false);
+
+    vs.load(&model_weights).map_err(|_| ScrubbyError::ReadNeuralNetworkModel)?;
+
+    let dummy_input = Tensor::zeros(&[1, NUM_BASE_CHANNELS, INPUT_SIZE], (Kind::Float, device));
+    let dummy_aux = aux_input_size.map(|size| Tensor::zeros(&[1, size], (Kind::Float, device)));
+
+    let trace_inputs = match &dummy_aux {
+        Some(aux) => vec![dummy_input.shallow_clone(), aux.shallow_clone()],
+        None => vec![dummy_input.shallow_clone()],
+    };
+
+    let has_aux = dummy_aux.is_some();
+    let traced = tch::CModule::create_by_tracing(
+        "HybridModel",
+        "forward",
+        &trace_inputs,
+        &mut |inputs: &[Tensor]| {
+            let aux_input = if has_aux { Some(&inputs[1]) } else { None };
+            vec![model.forward(&inputs[0], aux_input, false)]
+        },
+    ).map_err(|_| ScrubbyError::SaveNeuralNetworkModel)?;
+
+    traced.save(&output).map_err(|_| ScrubbyError::SaveNeuralNetworkModel)?;
+
+    log::info!("Exported traced model to: {}", output.display());
 
-pub fn check_gpu_connectivity() -> bool {
+    Ok(())
+}
+
+pub fn check_gpu_connectivity(device: usize) -> bool {
     // Create a simple tensor
     let tensor = Tensor::ones(&[1], (tch::Kind::Float, Device::Cpu));
-    
-    // Try to move the tensor to the GPU
-    let result = tensor.to_device(Device::Cuda(0));
-    
-    // Check if the device of the result tensor is GPU
-    result.device() == Device::Cuda(0)
+
+    // Try to move the tensor to the requested GPU
+    let result = tensor.to_device(Device::Cuda(device));
+
+    // Check if the device of the result tensor is the requested GPU
+    result.device() == Device::Cuda(device)
 }
 
 fn train_test_val_split(data_len: usize, train_ratio: f64, test_ratio: f64) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
@@ -495,39 +1084,93 @@ fn train_test_val_split(data_len: usize, train_ratio: f64, test_ratio: f64) -> (
     (train_indices, test_indices, val_indices)
 }
 
-fn evaluate(model: &HybridModel, sequences: &[Tensor], labels: &[Tensor], aux_inputs: Option<&[Tensor]>) -> f64 {
-    let mut correct = 0;
-    let mut total = 0;
+/// Per-class and aggregate evaluation metrics for one pass over a
+/// `DataLoader`, replacing a bare accuracy scalar with the class-level
+/// diagnostics needed to see whether a model is actually learning every
+/// host, not just the most common one.
+#[derive(Debug, Clone)]
+pub struct EvalReport {
+    pub accuracy: f64,
+    /// `confusion_matrix[true_class][predicted_class]` read counts.
+    pub confusion_matrix: Vec<Vec<u64>>,
+    pub precision: Vec<f64>,
+    pub recall: Vec<f64>,
+    pub f1: Vec<f64>,
+    pub macro_f1: f64,
+    pub weighted_f1: f64,
+}
+
+/// Derives per-class precision, recall and F1 from a `[true][predicted]` confusion matrix.
+fn precision_recall_f1(confusion_matrix: &[Vec<u64>]) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let num_classes = confusion_matrix.len();
+    let mut precision = vec![0.0; num_classes];
+    let mut recall = vec![0.0; num_classes];
+    let mut f1 = vec![0.0; num_classes];
+
+    for class in 0..num_classes {
+        let true_positive = confusion_matrix[class][class] as f64;
+        let predicted_positive: f64 = (0..num_classes).map(|true_class| confusion_matrix[true_class][class] as f64).sum();
+        let actual_positive: f64 = confusion_matrix[class].iter().sum::<u64>() as f64;
+
+        precision[class] = if predicted_positive > 0.0 { true_positive / predicted_positive } else { 0.0 };
+        recall[class] = if actual_positive > 0.0 { true_positive / actual_positive } else { 0.0 };
+        f1[class] = if precision[class] + recall[class] > 0.0 {
+            2.0 * precision[class] * recall[class] / (precision[class] + recall[class])
+        } else {
+            0.0
+        };
+    }
+
+    (precision, recall, f1)
+}
+
+fn evaluate(model: &HybridModel, loader: &mut DataLoader) -> EvalReport {
+    let mut confusion_matrix = vec![vec![0u64; NUM_CLASSES as usize]; NUM_CLASSES as usize];
+
+    loader.reset();
 
     no_grad(|| {
-        for (i, seq) in sequences.iter().enumerate() {
-            let aux_input = aux_inputs.map(|aux| &aux[i]);
+        while let Some((batch_seqs, batch_labels, batch_aux)) = loader.next_batch() {
+            for i in 0..batch_seqs.size()[0] {
+                let seq = batch_seqs.narrow(0, i, 1);
+                let aux_input = batch_aux.as_ref().map(|aux| aux.narrow(0, i, 1));
 
-            let logits = model.forward(seq, aux_input);
-            log::debug!("Output: {}", logits);
-            
-            // Average the logits across the batch for each sequence
-            let average_logits = logits.mean_dim(Some(&[0_i64][..]), false, tch::Kind::Float);
-            log::debug!("Average Logits: {}", average_logits);
+                let logits = model.forward(&seq, aux_input.as_ref(), false);
+                log::debug!("Output: {}", logits);
 
-            // Apply softmax to get probabilities
-            let probabilities = average_logits.softmax(0, Kind::Float);
-            log::debug!("Probabilities: {}", probabilities);
+                // Average the logits across the batch for each sequence
+                let average_logits = logits.mean_dim(Some(&[0_i64][..]), false, tch::Kind::Float);
+                log::debug!("Average Logits: {}", average_logits);
 
-            // Get the predicted class by taking argmax
-            let predicted = probabilities.argmax(0, true);
-            log::debug!("Predicted: {}", predicted);
+                // Apply softmax to get probabilities
+                let probabilities = average_logits.softmax(0, Kind::Float);
+                log::debug!("Probabilities: {}", probabilities);
 
-            // Ensure target is a single value tensor
-            let target = &labels[i];
-            log::debug!("Target: {}", target);
+                // Get the predicted class by taking argmax
+                let predicted = probabilities.argmax(0, true).int64_value(&[]) as usize;
+                log::debug!("Predicted: {}", predicted);
 
-            if predicted == *target {
-                correct += 1;
+                let target = batch_labels.narrow(0, i, 1).int64_value(&[]) as usize;
+                log::debug!("Target: {}", target);
+
+                confusion_matrix[target][predicted] += 1;
             }
-            total += 1;
         }
     });
 
-    correct as f64 / total as f64
+    let total: u64 = confusion_matrix.iter().flatten().sum();
+    let correct: u64 = (0..confusion_matrix.len()).map(|class| confusion_matrix[class][class]).sum();
+    let accuracy = if total > 0 { correct as f64 / total as f64 } else { 0.0 };
+
+    let (precision, recall, f1) = precision_recall_f1(&confusion_matrix);
+    let macro_f1 = if f1.is_empty() { 0.0 } else { f1.iter().sum::<f64>() / f1.len() as f64 };
+    let weighted_f1 = if total > 0 {
+        confusion_matrix.iter().enumerate()
+            .map(|(class, row)| f1[class] * row.iter().sum::<u64>() as f64)
+            .sum::<f64>() / total as f64
+    } else {
+        0.0
+    };
+
+    EvalReport { accuracy, confusion_matrix, precision, recall, f1, macro_f1, weighted_f1 }
 }
\ No newline at end of file
@@ -0,0 +1,21 @@
+//! Writes a Krona-compatible text input file summarising which taxa drove
+//! depletion. Each line is a directly-assigned read count followed by the
+//! tab-separated root-to-taxon lineage, the format `ktImportText` consumes
+//! to render an interactive radial chart.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::error::ScrubbyError;
+
+/// Writes `entries` (read count, lineage) pairs to `output` in Krona text format.
+pub fn write_krona_report(entries: &[(u64, Vec<String>)], output: &PathBuf) -> Result<(), ScrubbyError> {
+    let mut file = File::create(output)?;
+
+    for (count, lineage) in entries {
+        writeln!(file, "{}\t{}", count, lineage.join("\t"))?;
+    }
+
+    Ok(())
+}
@@ -2,20 +2,50 @@ pub mod scrubby;
 pub mod error;
 pub mod utils;
 pub mod terminal;
+pub mod backend;
 pub mod cleaner;
 pub mod classifier;
 pub mod alignment;
 pub mod download;
 pub mod report;
+pub mod adapter;
+pub mod audit;
+pub mod complexity;
+pub mod sketch;
+pub mod bracken;
+pub mod krona;
+pub mod readid;
+pub mod compression;
+pub mod taxonomy;
+pub mod aggregate;
+pub mod preprocess;
+pub mod batch;
+pub mod bamfilter;
+pub mod checkpoint;
+pub mod benchmark;
 
 #[cfg(feature = "nn")]
 pub mod identity;
 
 pub mod prelude {
-    pub use crate::download::{ScrubbyDownloader, ScrubbyDownloaderBuilder, ScrubbyIndex};
-    pub use crate::scrubby::{Aligner, Classifier, Preset, Scrubby, ScrubbyConfig, ScrubbyBuilder};
+    pub use crate::download::{ScrubbyDownloader, ScrubbyDownloaderBuilder, ScrubbyCatalog, ScrubbyCatalogEntry, ScrubbyCatalogFile, ArchiveFormat};
+    pub use crate::scrubby::{Aligner, Classifier, ClassifierOutput, CombineMode, Preset, Scrubby, ScrubbyConfig, ScrubbyBuilder, CleanHandle, CleanProgress};
+    pub use crate::backend::{AlignerBackend, ClassifierBackend, register_aligner_backend, register_classifier_backend};
     pub use crate::utils::{ReadDifference, ReadDifferenceBuilder};
-    pub use crate::alignment::{ReadAlignment, AlignmentFormat};
-    pub use crate::report::ScrubbyReport;
+    pub use crate::alignment::{ReadAlignment, AlignmentFormat, PafFilterMode};
+    pub use crate::report::{ScrubbyReport, ReferenceStat, EnsembleStat, Provenance, ToolVersion, FileDigest, CohortReport, ScrubbySettings, StatusEmitter, StatusEvent, HumanStatusEmitter, JsonLinesStatusEmitter, GithubActionsStatusEmitter};
+    pub use crate::audit::{ReadAudit, ReadAttribution};
+    pub use crate::complexity::{ComplexityFilter, ComplexityMethod};
+    pub use crate::sketch::{FracMinHashSketch, SketchFilter};
+    pub use crate::bracken::{BrackenDatabase, AbundanceRecord};
+    pub use crate::krona::write_krona_report;
+    pub use crate::readid::ReadIdNormalizer;
+    pub use crate::compression::{Compression, CompressionAlgorithm, ExternalDecompressor, register_external_decompressor};
+    pub use crate::taxonomy::{Taxonomy, get_taxids_from_taxonomy, annotate_taxids};
+    pub use crate::aggregate::aggregate_reads;
+    pub use crate::preprocess::{PreprocessConfig, PreprocessStats};
+    pub use crate::batch::{BatchOptions, BatchQueue, BatchReport, BatchSampleOutcome, BatchSampleRow, BatchTask, TaskStatus};
+    pub use crate::benchmark::{BenchmarkRun, BenchmarkWorkload, BenchmarkResult, BenchmarkReport};
+    pub use crate::bamfilter::{read_ids_for_taxa, filter_bam_by_read_ids};
     pub use crate::error::ScrubbyError;
 }
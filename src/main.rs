@@ -1,14 +1,25 @@
 use clap::Parser;
-use scrubby::identity::{train_nn, predict_nn, check_gpu_connectivity};
+use scrubby::error::ScrubbyError;
+use scrubby::identity::{train_nn, predict_nn, export_onnx, check_gpu_connectivity};
 use scrubby::utils::init_logger;
 use scrubby::terminal::{App, Commands};
 
+fn main() {
+
+    #[cfg(feature = "miette")]
+    install_miette_hook();
 
-fn main() -> anyhow::Result<()> {
-    
     let cli = App::parse();
 
-    init_logger(cli.log_file);
+    init_logger(cli.log_file.clone(), cli.json_log.clone());
+
+    if let Err(error) = run(cli) {
+        report_error(error);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: App) -> Result<(), ScrubbyError> {
 
     match cli.command {
         Commands::Reads(args) => {
@@ -20,6 +31,27 @@ fn main() -> anyhow::Result<()> {
         Commands::Alignment(args) => {
             args.validate_and_build()?.clean()?;
         },
+        Commands::Complexity(args) => {
+            args.validate_and_build()?.clean()?;
+        },
+        Commands::Sketch(args) => {
+            args.validate_and_build()?.clean()?;
+        },
+        Commands::SketchBuild(args) => {
+            args.validate_and_build()?;
+        },
+        Commands::Batch(args) => {
+            args.validate_and_build()?;
+        },
+        Commands::Benchmark(args) => {
+            args.validate_and_build()?;
+        },
+        Commands::Bam(args) => {
+            args.validate_and_build()?;
+        },
+        Commands::Taxonomy(args) => {
+            args.validate_and_build()?;
+        },
         Commands::Download(args) => {
             let dl = args.clone().validate_and_build()?;
 
@@ -28,20 +60,75 @@ fn main() -> anyhow::Result<()> {
         Commands::Diff(args) => {
             args.validate_and_build()?.compute()?;
         },
+        Commands::Merge(args) => {
+            args.validate_and_build()?;
+        },
+        Commands::Restore(args) => {
+            let report = args.validate_and_build()?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        },
+        Commands::Config(args) => {
+            if args.emit_schema {
+                println!("{}", scrubby::report::ScrubbySettings::emit_schema()?);
+            }
+        },
         Commands::Nn(args) => {
-            if args.train { 
-                train_nn(args.device, args.fastq, args.model_weights, args.alignment, args.epochs as i64, args.batch_size, 10000)?;
+            if args.list_devices {
+                scrubby::identity::print_gpu_devices_table();
+            } else if args.train {
+                train_nn(args.device, args.fastq, args.model_weights, args.alignment, args.epochs as i64, args.batch_size, args.allow_cpu_fallback, args.min_batch_size, !args.no_resume)?;
             } else if args.check {
-                if check_gpu_connectivity() {
-                    log::info!("Successfully connected to the GPU.");
+                let index = match args.device {
+                    scrubby::identity::ComputeDevice::Cuda(index) => index,
+                    _ => 0,
+                };
+                if check_gpu_connectivity(index) {
+                    match scrubby::identity::list_gpu_devices().into_iter().find(|device| device.index == index) {
+                        Some(device) => {
+                            log::info!(
+                                "Connected to GPU cuda:{} ({}), {} / {} MB free",
+                                device.index, device.name, device.free_mem_mb, device.total_mem_mb
+                            );
+                            scrubby::utils::log_json_event("info", "nn", serde_json::json!({
+                                "device": device.index,
+                                "device_name": device.name,
+                                "free_mem_mb": device.free_mem_mb,
+                                "total_mem_mb": device.total_mem_mb,
+                            }));
+                        },
+                        None => log::info!("Successfully connected to the GPU."),
+                    }
                 } else {
                     log::info!("Failed to connect to the GPU.");
                 }
+            } else if args.export {
+                let aux_input_size = args.alignment.as_ref().map(|_| (scrubby::identity::NUM_CHROMOSOMES + 2) as i64);
+                let output = args.onnx_output.ok_or(ScrubbyError::ReadNeuralNetworkModel)?;
+                export_onnx(args.model_weights, output, true, aux_input_size)?;
             } else {
-                predict_nn(args.device, args.model_weights, args.fastq, args.alignment)?;
+                predict_nn(args.device, args.model_weights, args.fastq, args.alignment, args.quiet, args.threshold, args.allow_cpu_fallback)?;
             }
         },
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Installs a `miette` report handler so errors render as colored, boxed
+/// diagnostics with codes and help text instead of a bare `Display` string.
+#[cfg(feature = "miette")]
+fn install_miette_hook() {
+    let _ = miette::set_hook(Box::new(|_| {
+        Box::new(miette::MietteHandlerOpts::new().build())
+    }));
+}
+
+#[cfg(feature = "miette")]
+fn report_error(error: ScrubbyError) {
+    eprintln!("{:?}", miette::Report::new(error));
+}
+
+#[cfg(not(feature = "miette"))]
+fn report_error(error: ScrubbyError) {
+    eprintln!("Error: {error}");
+}
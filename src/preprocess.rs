@@ -0,0 +1,399 @@
+//! Pre-depletion read preprocessing: a sliding-window quality trim, 3' adapter
+//! trim and optional paired-end overlap merge, run as an optional stage before
+//! classification/alignment so a single `scrubby reads` invocation can replace
+//! a separate `fastp`/`AdapterRemoval` pass ahead of host depletion.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::compression::{build_output_writer, Compression, CompressionAlgorithm};
+use crate::error::ScrubbyError;
+use crate::utils::{get_id, parse_fastx_file_with_check};
+
+/// Default sliding-window size (bases) for quality trimming.
+pub const DEFAULT_TRIM_WINDOW: usize = 4;
+/// Default minimum read length retained after trimming.
+pub const DEFAULT_MIN_READ_LENGTH: usize = 30;
+/// Default minimum overlap (bases) required to merge a read pair.
+pub const DEFAULT_MIN_MERGE_OVERLAP: usize = 10;
+/// Default maximum mismatch rate tolerated within the overlap when merging pairs.
+pub const DEFAULT_MAX_MERGE_MISMATCH: f64 = 0.1;
+
+/// Configuration for the preprocessing stage.
+#[derive(Clone, Debug)]
+pub struct PreprocessConfig {
+    /// Mean-quality cutoff for the sliding-window trim from both read ends; `None` disables quality trimming.
+    pub trim_quality: Option<u8>,
+    /// Adapter sequence trimmed from the 3' end when found, either in full or overlapping the read's end.
+    pub trim_adapter: Option<String>,
+    /// Minimum read length retained after trimming; shorter reads (or, for pairs, both mates) are dropped.
+    pub min_read_length: usize,
+    /// Sliding window size (bases) used by the quality trimmer.
+    pub window: usize,
+    /// Attempt to merge overlapping read pairs into a single consensus read.
+    pub merge_pairs: bool,
+    /// Drop pairs that fail to merge, instead of keeping their R1 mate as a single-end read.
+    pub exclude_unmerged: bool,
+    /// Minimum overlap (bases) required between R1 and the reverse complement of R2 to call a merge.
+    pub min_merge_overlap: usize,
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            trim_quality: None,
+            trim_adapter: None,
+            min_read_length: DEFAULT_MIN_READ_LENGTH,
+            window: DEFAULT_TRIM_WINDOW,
+            merge_pairs: false,
+            exclude_unmerged: false,
+            min_merge_overlap: DEFAULT_MIN_MERGE_OVERLAP,
+        }
+    }
+}
+
+impl PreprocessConfig {
+    /// Returns `true` if any trimming or merging is actually configured.
+    pub fn is_active(&self) -> bool {
+        self.trim_quality.is_some() || self.trim_adapter.is_some() || self.merge_pairs
+    }
+}
+
+/// Counts produced by a preprocessing run.
+#[derive(Clone, Debug, Default)]
+pub struct PreprocessStats {
+    pub reads_in: u64,
+    pub reads_dropped: u64,
+    pub pairs_merged: u64,
+}
+
+/// Phred+33 ASCII offset: `qual` bytes are `Phred score + 33` (Sanger/Illumina
+/// 1.8+ encoding), so it must be subtracted before comparing against `min_qual`.
+const PHRED33_OFFSET: u64 = 33;
+
+/// Returns the `[start, end)` slice of `qual` (Phred+33 ASCII-encoded bytes)
+/// to keep after trimming both ends with a sliding window of `window` bases:
+/// `start` advances from the front, and `end` retreats from the back, while
+/// the mean Phred quality of the window anchored there stays below `min_qual`.
+pub fn quality_trim_bounds(qual: &[u8], min_qual: u8, window: usize) -> (usize, usize) {
+    let len = qual.len();
+    if len == 0 || window == 0 {
+        return (0, len);
+    }
+
+    let window_mean = |start: usize| -> f64 {
+        let end = (start + window).min(len);
+        let sum: u64 = qual[start..end].iter().map(|&q| q as u64 - PHRED33_OFFSET).sum();
+        sum as f64 / (end - start) as f64
+    };
+
+    let mut start = 0;
+    while start < len && window_mean(start) < min_qual as f64 {
+        start += 1;
+    }
+
+    let mut end = len;
+    while end > start {
+        let window_start = end.saturating_sub(window).max(start);
+        let sum: u64 = qual[window_start..end].iter().map(|&q| q as u64 - PHRED33_OFFSET).sum();
+        let mean = sum as f64 / (end - window_start) as f64;
+        if mean >= min_qual as f64 {
+            break;
+        }
+        end -= 1;
+    }
+
+    (start, end.max(start))
+}
+
+/// Truncates `seq` at the first occurrence of `adapter`, matching either the
+/// full adapter sequence inside the read or a partial prefix of `adapter`
+/// overlapping the read's 3' end by at least `min_overlap` bases - the usual
+/// case when the sequenced insert is shorter than the read length and the
+/// adapter is only partially read through.
+pub fn trim_adapter_len(seq: &[u8], adapter: &[u8], min_overlap: usize) -> usize {
+    if adapter.is_empty() || seq.is_empty() {
+        return seq.len();
+    }
+
+    if let Some(pos) = find_subsequence(seq, adapter) {
+        return pos;
+    }
+
+    let max_overlap = adapter.len().min(seq.len());
+    for overlap in (min_overlap..=max_overlap).rev() {
+        let read_tail = &seq[seq.len() - overlap..];
+        if read_tail.eq_ignore_ascii_case(&adapter[..overlap]) {
+            return seq.len() - overlap;
+        }
+    }
+
+    seq.len()
+}
+
+fn find_subsequence(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w.eq_ignore_ascii_case(needle))
+}
+
+/// Reverse-complements a nucleotide sequence, leaving non-ACGT bytes (e.g. `N`) unchanged.
+pub fn reverse_complement(seq: &[u8]) -> Vec<u8> {
+    seq.iter().rev().map(|&b| match b.to_ascii_uppercase() {
+        b'A' => b'T',
+        b'T' => b'A',
+        b'C' => b'G',
+        b'G' => b'C',
+        other => other,
+    }).collect()
+}
+
+/// Attempts to merge `r1`/`r2` into a single consensus read by sliding the
+/// reverse complement of R2 against the 3' end of R1 and keeping the longest
+/// overlap (at least `min_overlap` bases) whose mismatch rate is at or below
+/// `max_mismatch_rate`. Mismatches within the overlap are resolved in favour
+/// of the higher-quality base. Returns `None` if no overlap qualifies.
+pub fn merge_pair(
+    r1_seq: &[u8], r1_qual: &[u8],
+    r2_seq: &[u8], r2_qual: &[u8],
+    min_overlap: usize, max_mismatch_rate: f64,
+) -> Option<(Vec<u8>, Vec<u8>)> {
+    let r2_rc_seq = reverse_complement(r2_seq);
+    let r2_rc_qual: Vec<u8> = r2_qual.iter().rev().cloned().collect();
+
+    let max_overlap = r1_seq.len().min(r2_rc_seq.len());
+    if max_overlap < min_overlap {
+        return None;
+    }
+
+    let overlap = (min_overlap..=max_overlap).rev().find(|&overlap| {
+        let r1_tail = &r1_seq[r1_seq.len() - overlap..];
+        let r2_head = &r2_rc_seq[..overlap];
+        let mismatches = r1_tail.iter().zip(r2_head.iter())
+            .filter(|(a, b)| !a.eq_ignore_ascii_case(b))
+            .count();
+        mismatches as f64 <= max_mismatch_rate * overlap as f64
+    })?;
+
+    let mut merged_seq = r1_seq[..r1_seq.len() - overlap].to_vec();
+    let mut merged_qual = r1_qual[..r1_qual.len() - overlap].to_vec();
+
+    for i in 0..overlap {
+        let r1_base = r1_seq[r1_seq.len() - overlap + i];
+        let r1_q = r1_qual[r1_qual.len() - overlap + i];
+        let r2_base = r2_rc_seq[i];
+        let r2_q = r2_rc_qual[i];
+        if r1_q >= r2_q {
+            merged_seq.push(r1_base);
+            merged_qual.push(r1_q);
+        } else {
+            merged_seq.push(r2_base);
+            merged_qual.push(r2_q);
+        }
+    }
+
+    merged_seq.extend_from_slice(&r2_rc_seq[overlap..]);
+    merged_qual.extend_from_slice(&r2_rc_qual[overlap..]);
+
+    Some((merged_seq, merged_qual))
+}
+
+fn write_fastq_record<W: Write>(writer: &mut W, id: &str, seq: &[u8], qual: &[u8]) -> Result<(), ScrubbyError> {
+    writer.write_all(b"@")?;
+    writer.write_all(id.as_bytes())?;
+    writer.write_all(b"\n")?;
+    writer.write_all(seq)?;
+    writer.write_all(b"\n+\n")?;
+    writer.write_all(qual)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Applies quality and adapter trimming to `input`, dropping reads shorter
+/// than `config.min_read_length` afterwards, and writes the surviving reads
+/// (uncompressed) to `output`.
+pub fn preprocess_single(input: &PathBuf, output: &PathBuf, config: &PreprocessConfig) -> Result<PreprocessStats, ScrubbyError> {
+    let uncompressed = Compression::new(CompressionAlgorithm::Uncompressed, None)?;
+    let mut writer = build_output_writer(output, uncompressed, 1)?;
+    let mut stats = PreprocessStats::default();
+
+    if let Some(mut reader) = parse_fastx_file_with_check(input)? {
+        while let Some(rec) = reader.next() {
+            let record = rec?;
+            stats.reads_in += 1;
+
+            let id = get_id(record.id())?;
+            let seq = record.seq().to_vec();
+            let qual = record.qual().map(|q| q.to_vec()).unwrap_or_else(|| seq.clone());
+
+            let (seq, qual) = trim_read(&seq, &qual, config);
+
+            if seq.len() < config.min_read_length {
+                stats.reads_dropped += 1;
+                continue;
+            }
+
+            write_fastq_record(&mut writer, &id, &seq, &qual)?;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Applies quality and adapter trimming to both mates of `r1_input`/`r2_input`,
+/// dropping a pair jointly if either mate falls below `config.min_read_length`
+/// afterwards. When `config.merge_pairs` is set, attempts to merge each
+/// surviving pair into a single consensus read, written to `output` as a
+/// single-end stream; unmerged pairs contribute just their (trimmed) R1 mate
+/// to that same stream unless `config.exclude_unmerged` drops them instead.
+/// Without `config.merge_pairs`, trimmed pairs are written to `r1_output`/`r2_output`.
+pub fn preprocess_paired(
+    r1_input: &PathBuf, r2_input: &PathBuf,
+    r1_output: &PathBuf, r2_output: &PathBuf,
+    merged_output: Option<&PathBuf>,
+    config: &PreprocessConfig,
+) -> Result<PreprocessStats, ScrubbyError> {
+    let uncompressed = Compression::new(CompressionAlgorithm::Uncompressed, None)?;
+    let mut stats = PreprocessStats::default();
+
+    let mut merged_writer = match merged_output {
+        Some(path) => Some(build_output_writer(path, uncompressed.clone(), 1)?),
+        None => None,
+    };
+    let mut writer_r1 = build_output_writer(r1_output, uncompressed.clone(), 1)?;
+    let mut writer_r2 = build_output_writer(r2_output, uncompressed, 1)?;
+
+    let mut reader_r1 = parse_fastx_file_with_check(r1_input)?
+        .ok_or_else(|| ScrubbyError::PreprocessInputMissing(r1_input.clone()))?;
+    let mut reader_r2 = parse_fastx_file_with_check(r2_input)?
+        .ok_or_else(|| ScrubbyError::PreprocessInputMissing(r2_input.clone()))?;
+
+    while let (Some(rec1), Some(rec2)) = (reader_r1.next(), reader_r2.next()) {
+        let record1 = rec1?;
+        let record2 = rec2?;
+        stats.reads_in += 1;
+
+        let id1 = get_id(record1.id())?;
+        let seq1 = record1.seq().to_vec();
+        let qual1 = record1.qual().map(|q| q.to_vec()).unwrap_or_else(|| seq1.clone());
+        let (seq1, qual1) = trim_read(&seq1, &qual1, config);
+
+        let seq2 = record2.seq().to_vec();
+        let qual2 = record2.qual().map(|q| q.to_vec()).unwrap_or_else(|| seq2.clone());
+        let (seq2, qual2) = trim_read(&seq2, &qual2, config);
+
+        if seq1.len() < config.min_read_length || seq2.len() < config.min_read_length {
+            stats.reads_dropped += 1;
+            continue;
+        }
+
+        if config.merge_pairs {
+            let merged = merge_pair(&seq1, &qual1, &seq2, &qual2, config.min_merge_overlap, DEFAULT_MAX_MERGE_MISMATCH);
+            match merged {
+                Some((merged_seq, merged_qual)) => {
+                    stats.pairs_merged += 1;
+                    let writer = merged_writer.as_mut().unwrap_or(&mut writer_r1);
+                    write_fastq_record(writer, &id1, &merged_seq, &merged_qual)?;
+                },
+                None if config.exclude_unmerged => {
+                    stats.reads_dropped += 1;
+                },
+                None => {
+                    let writer = merged_writer.as_mut().unwrap_or(&mut writer_r1);
+                    write_fastq_record(writer, &id1, &seq1, &qual1)?;
+                },
+            }
+        } else {
+            write_fastq_record(&mut writer_r1, &id1, &seq1, &qual1)?;
+            write_fastq_record(&mut writer_r2, &id1, &seq2, &qual2)?;
+        }
+    }
+
+    Ok(stats)
+}
+
+fn trim_read(seq: &[u8], qual: &[u8], config: &PreprocessConfig) -> (Vec<u8>, Vec<u8>) {
+    let (mut start, mut end) = (0, seq.len());
+
+    if let Some(min_qual) = config.trim_quality {
+        let (s, e) = quality_trim_bounds(&qual[start..end], min_qual, config.window);
+        start += s;
+        end = start + (e - s);
+    }
+
+    let mut seq = seq[start..end].to_vec();
+    let mut qual = qual[start..end].to_vec();
+
+    if let Some(adapter) = &config.trim_adapter {
+        let keep = trim_adapter_len(&seq, adapter.as_bytes(), config.min_merge_overlap.min(adapter.len().max(1)));
+        seq.truncate(keep);
+        qual.truncate(keep);
+    }
+
+    (seq, qual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes Phred scores as Phred+33 ASCII quality bytes.
+    fn qual(scores: &[u8]) -> Vec<u8> {
+        scores.iter().map(|&s| s + 33).collect()
+    }
+
+    #[test]
+    fn quality_trim_bounds_removes_low_quality_tail() {
+        // Q30 bases followed by a Q2 tail: `--trim-quality 20` must trim some
+        // of the tail off. Before the Phred+33 offset fix, every raw quality
+        // byte is >= 33 > 20, so `end` never retreats from `len` (10) here.
+        let q = qual(&[30, 30, 30, 30, 30, 30, 2, 2, 2, 2]);
+        let (start, end) = quality_trim_bounds(&q, 20, DEFAULT_TRIM_WINDOW);
+        assert_eq!(start, 0);
+        assert!(end < 10, "expected the Q2 tail to be trimmed, got end={end}");
+    }
+
+    #[test]
+    fn quality_trim_bounds_keeps_uniform_high_quality_read() {
+        let q = qual(&[30; 10]);
+        let (start, end) = quality_trim_bounds(&q, 20, DEFAULT_TRIM_WINDOW);
+        assert_eq!((start, end), (0, 10));
+    }
+
+    #[test]
+    fn trim_adapter_len_truncates_at_internal_match() {
+        let seq = b"ACGTACGTAGATCGGAAGAG";
+        let adapter = b"AGATCGGAAGAG";
+        assert_eq!(trim_adapter_len(seq, adapter, 3), 8);
+    }
+
+    #[test]
+    fn trim_adapter_len_truncates_at_partial_3prime_overlap() {
+        let seq = b"ACGTACGTACAGATC";
+        let adapter = b"AGATCGGAAGAG";
+        assert_eq!(trim_adapter_len(seq, adapter, 3), 10);
+    }
+
+    #[test]
+    fn merge_pair_joins_overlapping_mates() {
+        let r1_seq = b"ACGTACGTAC";
+        let r2_seq = reverse_complement(b"GTACGGGGGG");
+        let r1_qual = vec![b'I'; r1_seq.len()];
+        let r2_qual = vec![b'I'; r2_seq.len()];
+
+        let (merged_seq, _) = merge_pair(r1_seq, &r1_qual, &r2_seq, &r2_qual, 4, 0.0)
+            .expect("overlapping mates should merge");
+        assert_eq!(merged_seq, b"ACGTACGTACGGGGGG");
+    }
+
+    #[test]
+    fn merge_pair_returns_none_below_min_overlap() {
+        let r1_seq = b"ACGTACGTAC";
+        let r2_seq = reverse_complement(b"GTACGGGGGG");
+        let r1_qual = vec![b'I'; r1_seq.len()];
+        let r2_qual = vec![b'I'; r2_seq.len()];
+
+        assert!(merge_pair(r1_seq, &r1_qual, &r2_seq, &r2_qual, 20, 0.0).is_none());
+    }
+}
@@ -0,0 +1,39 @@
+//! Canonicalizes read identifiers by stripping a configurable trailing
+//! orientation suffix, so a depletion ID set built from classifier or aligner
+//! output still matches FASTQ records whose paired-end IDs carry `/1`/`/2`,
+//! `.1`/`.2`, or an Illumina/Casava ` 1:N:0:...` comment.
+
+use std::collections::HashSet;
+
+use regex::Regex;
+
+use crate::error::ScrubbyError;
+
+/// Default pattern matching common paired-end orientation suffixes: a trailing
+/// `/1`/`/2`, `.1`/`.2`, or an Illumina/Casava ` 1:N:0:...`/` 2:N:0:...` comment
+/// appended to the read identifier.
+pub const DEFAULT_SUFFIX_PATTERN: &str = r"(?:[/.][12]|\s+[12]:[NY]:\d+:\S*)$";
+
+/// Strips a configured trailing suffix from read identifiers before set
+/// membership is tested, so '--strip-suffix' normalizes both sides of a
+/// depletion/extraction comparison consistently.
+pub struct ReadIdNormalizer {
+    pattern: Regex,
+}
+
+impl ReadIdNormalizer {
+    /// Compiles a normalizer from a user-supplied (or the default) suffix pattern.
+    pub fn new(pattern: &str) -> Result<Self, ScrubbyError> {
+        Ok(Self { pattern: Regex::new(pattern)? })
+    }
+
+    /// Strips the configured suffix from `read_id`, returning it unchanged if absent.
+    pub fn normalize(&self, read_id: &str) -> String {
+        self.pattern.replace(read_id, "").into_owned()
+    }
+
+    /// Normalizes every identifier in `read_ids`, for comparison against normalized FASTQ IDs.
+    pub fn normalize_set(&self, read_ids: &HashSet<String>) -> HashSet<String> {
+        read_ids.iter().map(|id| self.normalize(id)).collect()
+    }
+}
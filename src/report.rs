@@ -1,10 +1,210 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::io::Write;
 use chrono::{SecondsFormat, Utc};
 use clap::crate_version;
 use serde::{Deserialize, Serialize};
-use crate::{error::ScrubbyError, scrubby::{Aligner, Classifier, Preset, Scrubby}, utils::ReadDifference};
+use tar::{Archive, Builder, Header};
+use sha2::{Digest, Sha256};
+use crate::{bracken::AbundanceRecord, compression::CompressionAlgorithm, error::ScrubbyError, scrubby::{Aligner, Classifier, Preset, Scrubby}, utils::ReadDifference};
 
+/// A single progress event fired at a pipeline stage boundary (download index,
+/// classify/align, extract reads, report) so downstream tooling (Nextflow,
+/// Snakemake, dashboards) can consume progress without scraping logs.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StatusEvent {
+    pub stage: String,
+    pub tool: Option<String>,
+    pub db_index: Option<usize>,
+    pub db_name: Option<String>,
+    pub reads_seen: u64,
+    pub reads_removed: u64,
+    pub depletion_fraction: f64,
+    pub elapsed_ms: u128,
+}
+
+impl StatusEvent {
+    pub fn new(stage: impl Into<String>, reads_seen: u64, reads_removed: u64, elapsed_ms: u128) -> Self {
+        let depletion_fraction = match reads_seen {
+            0 => 0.0,
+            _ => reads_removed as f64 / reads_seen as f64,
+        };
+        Self {
+            stage: stage.into(),
+            tool: None,
+            db_index: None,
+            db_name: None,
+            reads_seen,
+            reads_removed,
+            depletion_fraction,
+            elapsed_ms,
+        }
+    }
+    pub fn tool(mut self, tool: impl Into<Option<String>>) -> Self {
+        self.tool = tool.into();
+        self
+    }
+    pub fn db(mut self, db_index: impl Into<Option<usize>>, db_name: impl Into<Option<String>>) -> Self {
+        self.db_index = db_index.into();
+        self.db_name = db_name.into();
+        self
+    }
+}
+
+/// A pluggable sink for `StatusEvent`s fired during a Scrubby run.
+pub trait StatusEmitter {
+    fn emit(&self, event: &StatusEvent);
+}
+
+/// Prints human-readable progress to the terminal.
+pub struct HumanStatusEmitter;
+impl StatusEmitter for HumanStatusEmitter {
+    fn emit(&self, event: &StatusEvent) {
+        log::info!(
+            "[{}] {} reads seen, {} removed ({:.2}%) in {} ms{}",
+            event.stage,
+            event.reads_seen,
+            event.reads_removed,
+            event.depletion_fraction * 100.0,
+            event.elapsed_ms,
+            match (&event.db_index, &event.db_name) {
+                (Some(idx), Some(name)) => format!(" [{idx}-{name}]"),
+                _ => String::new(),
+            }
+        );
+    }
+}
+
+/// Writes one JSON object per line (NDJSON) for machine consumption.
+pub struct JsonLinesStatusEmitter {
+    pub writer: std::sync::Mutex<Box<dyn Write + Send>>,
+}
+impl JsonLinesStatusEmitter {
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self { writer: std::sync::Mutex::new(writer) }
+    }
+}
+impl StatusEmitter for JsonLinesStatusEmitter {
+    fn emit(&self, event: &StatusEvent) {
+        if let Ok(line) = serde_json::to_string(event) {
+            if let Ok(mut writer) = self.writer.lock() {
+                let _ = writeln!(writer, "{line}");
+            }
+        }
+    }
+}
+
+/// Threshold above which a stage's depletion fraction is considered unusual
+/// enough to warrant a GitHub-Actions `::warning` annotation rather than a `::notice`.
+const GITHUB_ACTIONS_WARNING_FRACTION: f64 = 0.5;
+
+/// Emits GitHub-Actions workflow-command annotations (`::notice`/`::warning`)
+/// so a depletion run surfaces directly in a job's checks output.
+pub struct GithubActionsStatusEmitter;
+impl StatusEmitter for GithubActionsStatusEmitter {
+    fn emit(&self, event: &StatusEvent) {
+        let message = format!(
+            "scrubby stage `{}` removed {}/{} reads ({:.2}%)",
+            event.stage,
+            event.reads_removed,
+            event.reads_seen,
+            event.depletion_fraction * 100.0
+        );
+        if event.depletion_fraction >= GITHUB_ACTIONS_WARNING_FRACTION {
+            println!("::warning::{message}");
+        } else {
+            println!("::notice::{message}");
+        }
+        if event.db_name.is_none() && event.tool.is_some() {
+            println!("::warning::scrubby stage `{}` ran without a resolved database index", event.stage);
+        }
+    }
+}
+
+
+/// Removed-read counts attributed to a single reference or taxon, so a user
+/// can see, for example, that 90% of removed reads matched human chr21 rather
+/// than just a global `reads_removed` scalar. `bases_removed` and `mean_mapq`
+/// are only populated where the underlying pipeline stage tracks them per hit.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReferenceStat {
+    pub name: String,
+    pub taxid: Option<String>,
+    pub reads_removed: u64,
+    pub bases_removed: Option<u64>,
+    pub mean_mapq: Option<f64>,
+}
+
+/// Per-backend and combined read counts from an ensemble run (`--combine`),
+/// so a user can see how much the aligner and classifier disagreed rather
+/// than just the combined `reads_removed` total.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EnsembleStat {
+    pub mode: String,
+    pub aligner_reads: u64,
+    pub classifier_reads: u64,
+    pub combined_reads: u64,
+}
+
+/// A single externally invoked tool's parsed `--version` output, so a summary
+/// records exactly which build produced it rather than just the command string.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ToolVersion {
+    pub name: String,
+    pub version: String,
+}
+
+/// Hex-encoded SHA-256 digest of a single input or reference database/index
+/// file, so a summary can be checked against the exact bytes it was run against.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FileDigest {
+    pub path: PathBuf,
+    pub sha256: String,
+}
+
+/// Reproducibility manifest answering "which exact tool version and which
+/// reference database produced this depleted set" - recorded at run start so
+/// it reflects what actually ran rather than what was merely configured. This
+/// matters when scrubbed data feeds a clinical or publication result.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Provenance {
+    /// Parsed `--version` output for each aligner/classifier binary actually invoked.
+    pub tools: Vec<ToolVersion>,
+    /// Digests of every input FASTX file.
+    pub inputs: Vec<FileDigest>,
+    /// Digests of the reference database/index path used, if any - a
+    /// directory-based database (e.g. a Kraken2 `--db`) is digested file-by-file.
+    pub databases: Vec<FileDigest>,
+}
+
+impl Provenance {
+    /// Computes the SHA-256 digest of a single file, streaming it through the
+    /// hasher rather than reading it fully into memory first.
+    pub fn digest_file(path: &Path) -> Result<String, ScrubbyError> {
+        let mut file = File::open(path).map_err(|e| ScrubbyError::DigestFailed(path.to_path_buf(), e.to_string()))?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher).map_err(|e| ScrubbyError::DigestFailed(path.to_path_buf(), e.to_string()))?;
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+    /// Digests `path`: a single file directly, or every regular file in a
+    /// directory (non-recursive) when `path` is a reference database directory.
+    pub fn digest_path(path: &Path) -> Result<Vec<FileDigest>, ScrubbyError> {
+        if path.is_dir() {
+            let mut digests = Vec::new();
+            for entry in std::fs::read_dir(path).map_err(|e| ScrubbyError::DigestFailed(path.to_path_buf(), e.to_string()))? {
+                let entry = entry.map_err(|e| ScrubbyError::DigestFailed(path.to_path_buf(), e.to_string()))?;
+                if entry.path().is_file() {
+                    digests.push(FileDigest { sha256: Self::digest_file(&entry.path())?, path: entry.path() });
+                }
+            }
+            digests.sort_by(|a, b| a.path.cmp(&b.path));
+            Ok(digests)
+        } else {
+            Ok(vec![FileDigest { sha256: Self::digest_file(path)?, path: path.to_path_buf() }])
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct ScrubbyReport {
@@ -13,35 +213,100 @@ pub struct ScrubbyReport {
     pub command: String,
     pub input: Vec<PathBuf>,
     pub output: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
     pub reads_in: u64,
     pub reads_out: u64,
     pub reads_removed: u64,
     pub reads_extracted: u64,
+    /// Total input/retained bases summed across every cleaned file, rolled up
+    /// from `DepletionStats::total_bases_in`/`total_bases_retained`; zero when
+    /// `stats` wasn't supplied to [`Self::create`].
+    #[serde(default)]
+    pub bytes_in: u64,
+    #[serde(default)]
+    pub bytes_out: u64,
+    /// `bytes_in`/`bytes_out` formatted as human-readable strings (e.g. "1.8 GiB").
+    #[serde(default)]
+    pub bytes_in_human: String,
+    #[serde(default)]
+    pub bytes_out_human: String,
+    /// Total wall-clock time spent cleaning, rolled up from
+    /// `DepletionStats::total_elapsed_secs`.
+    #[serde(default)]
+    pub elapsed_secs: f64,
+    /// Per-reference/per-taxon removal breakdown, empty when the pipeline
+    /// stages used for this run don't track per-hit attribution (e.g. a plain
+    /// aligner run without `--audit`, or a classifier run without resolved taxids).
+    #[serde(default)]
+    pub breakdown: Vec<ReferenceStat>,
+    /// Bracken-style abundance re-estimation table written by `--bracken-report`,
+    /// empty unless that option was set.
+    #[serde(default)]
+    pub abundance: Vec<AbundanceRecord>,
+    /// Per-backend and combined counts from an ensemble (`--combine`) run,
+    /// `None` unless both an aligner and a classifier were configured together.
+    #[serde(default)]
+    pub ensemble: Option<EnsembleStat>,
+    /// Reproducibility manifest of tool versions and input/database digests,
+    /// empty unless `--provenance` was set (digesting large references is not free).
+    #[serde(default)]
+    pub provenance: Provenance,
     pub settings: ScrubbySettings
 }
 impl ScrubbyReport {
-    pub fn create(scrubby: &Scrubby, header: bool) -> Result<Self, ScrubbyError> {
+    /// Builds the summary report. `stats`, when given, supplies the per-taxid
+    /// removed-read counts accumulated in `DepletionStats` over the classifier
+    /// path, surfaced as `breakdown`; omit it (or pass a `DepletionStats` with
+    /// no taxid counts, e.g. an aligner-only run) to leave `breakdown` empty.
+    /// `abundance` carries the `--bracken-report` table, if one was computed.
+    /// `ensemble` carries the per-backend/combined counts from a `--combine` run, if one was performed.
+    /// `provenance` carries the tool-version/digest manifest from `--provenance`, empty otherwise.
+    pub fn create(scrubby: &Scrubby, header: bool, stats: Option<&DepletionStats>, abundance: Vec<AbundanceRecord>, ensemble: Option<EnsembleStat>, provenance: Provenance) -> Result<Self, ScrubbyError> {
 
         let diff = ReadDifference::new(
-            &scrubby.input, 
-            &scrubby.output, 
-            None, 
+            &scrubby.input,
+            &scrubby.output,
+            None,
             None
         ).compute()?;
 
+        let breakdown = stats.map(|stats| {
+            let mut breakdown: Vec<ReferenceStat> = stats.taxid_removed.iter().map(|(taxid, reads_removed)| {
+                ReferenceStat {
+                    name: taxid.clone(),
+                    taxid: Some(taxid.clone()),
+                    reads_removed: *reads_removed,
+                    bases_removed: None,
+                    mean_mapq: None,
+                }
+            }).collect();
+            breakdown.sort_by(|a, b| b.reads_removed.cmp(&a.reads_removed));
+            breakdown
+        }).unwrap_or_default();
+
         let report = Self {
             version: crate_version!().to_string(),
             date: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
-            command: match scrubby.config.command { 
-                Some(ref cmd) => cmd.to_string(), 
-                None => String::new() 
+            command: match scrubby.config.command {
+                Some(ref cmd) => cmd.to_string(),
+                None => String::new()
             },
             input: scrubby.input.clone(),
             output: scrubby.output.clone(),
+            removed: scrubby.removed.clone(),
             reads_in: diff.reads_in,
             reads_out: diff.reads_out,
             reads_removed: if scrubby.extract { 0 } else { diff.difference },
             reads_extracted: if scrubby.extract { diff.difference } else { 0 },
+            bytes_in: stats.map(|s| s.total_bases_in()).unwrap_or(0),
+            bytes_out: stats.map(|s| s.total_bases_retained()).unwrap_or(0),
+            bytes_in_human: format_bytes(stats.map(|s| s.total_bases_in()).unwrap_or(0)),
+            bytes_out_human: format_bytes(stats.map(|s| s.total_bases_retained()).unwrap_or(0)),
+            elapsed_secs: stats.map(|s| s.total_elapsed_secs()).unwrap_or(0.0),
+            breakdown,
+            abundance,
+            ensemble,
+            provenance,
             settings: ScrubbySettings::from_scrubby(&scrubby)
         };
 
@@ -61,10 +326,270 @@ impl ScrubbyReport {
         file.write_all(json_string.as_bytes())?;
         Ok(())
     }
+    /// Loads a single `ScrubbyReport` previously written by `--json`, for
+    /// cohort aggregation with [`CohortReport::merge`].
+    pub fn from_json(path: &Path) -> Result<Self, ScrubbyError> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+    /// Packages this report, its `settings`, and an optional read-id list
+    /// into one gzip-compressed tar archive (`report.json`, `settings.json`,
+    /// `read_ids.tsv`), so a collaborator can inspect or re-apply a depletion
+    /// run from a single shareable file instead of hunting for sidecar files.
+    pub fn to_bundle(&self, path: &Path, read_ids: Option<&Path>) -> Result<(), ScrubbyError> {
+        let file = File::create(path)?;
+        let writer = niffler::get_writer(Box::new(file), niffler::compression::Format::Gzip, niffler::compression::Level::Six)?;
+        let mut builder = Builder::new(writer);
+
+        append_bytes(&mut builder, "report.json", &serde_json::to_vec_pretty(self)?)?;
+        append_bytes(&mut builder, "settings.json", &serde_json::to_vec_pretty(&self.settings)?)?;
+        if let Some(read_ids) = read_ids {
+            builder.append_file("read_ids.tsv", &mut File::open(read_ids)?)?;
+        }
+
+        builder.into_inner()?.flush()?;
+        Ok(())
+    }
+    /// Unpacks a bundle written by [`Self::to_bundle`] into `outdir` and
+    /// returns its `report.json`, validating that the archive is well-formed.
+    pub fn from_bundle(path: &Path, outdir: &Path) -> Result<Self, ScrubbyError> {
+        let file = File::open(path)?;
+        let (reader, _compression) = niffler::get_reader(Box::new(file))?;
+        let mut archive = Archive::new(reader);
+        archive.unpack(outdir)?;
+
+        Self::from_json(&outdir.join("report.json"))
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+/// Formats a byte count as a human-readable binary-prefixed string (e.g.
+/// "1.8 GiB"), so a JSON summary's raw byte totals are legible without the
+/// reader doing the division themselves.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Appends an in-memory file (`name`) with contents `bytes` to a tar `builder`.
+fn append_bytes<W: Write>(builder: &mut Builder<W>, name: &str, bytes: &[u8]) -> Result<(), ScrubbyError> {
+    let mut header = Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, bytes)?;
+    Ok(())
+}
+
+/// Per-sample row in a [`CohortReport`], reduced to the columns a QC
+/// dashboard wants side-by-side across hundreds of samples.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CohortSample {
+    pub report: PathBuf,
+    pub command: String,
+    pub reads_in: u64,
+    pub reads_out: u64,
+    pub reads_removed: u64,
+    pub reads_extracted: u64,
+    pub fraction_removed: f64,
+}
+
+/// Aggregate summary of many per-sample `ScrubbyReport`s, produced by the
+/// `scrubby merge` subcommand for cohorts where a per-sample JSON report is
+/// too granular to review one at a time.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CohortReport {
+    pub samples: Vec<CohortSample>,
+    pub total_reads_in: u64,
+    pub total_reads_removed: u64,
+    pub total_reads_extracted: u64,
+    pub mean_fraction_removed: f64,
+    pub median_fraction_removed: f64,
+    /// Human-readable notes for any of `index`/`preset`/`min_mapq` that
+    /// differ from the first sample's settings, so a mixed-parameter cohort
+    /// isn't silently averaged together without comment.
+    pub setting_mismatches: Vec<String>,
+}
+
+impl CohortReport {
+    /// Merges `reports` (paired with the path each was loaded from, for the
+    /// `report` column) into one cohort summary.
+    pub fn merge(reports: &[(PathBuf, ScrubbyReport)]) -> Self {
+        let mut samples = Vec::with_capacity(reports.len());
+        let mut fractions = Vec::with_capacity(reports.len());
+        let mut total_reads_in = 0u64;
+        let mut total_reads_removed = 0u64;
+        let mut total_reads_extracted = 0u64;
+
+        for (path, report) in reports {
+            let fraction_removed = if report.reads_in > 0 {
+                (report.reads_removed + report.reads_extracted) as f64 / report.reads_in as f64
+            } else {
+                0.0
+            };
+            fractions.push(fraction_removed);
+            total_reads_in += report.reads_in;
+            total_reads_removed += report.reads_removed;
+            total_reads_extracted += report.reads_extracted;
+            samples.push(CohortSample {
+                report: path.clone(),
+                command: report.command.clone(),
+                reads_in: report.reads_in,
+                reads_out: report.reads_out,
+                reads_removed: report.reads_removed,
+                reads_extracted: report.reads_extracted,
+                fraction_removed,
+            });
+        }
+
+        let mean_fraction_removed = if fractions.is_empty() {
+            0.0
+        } else {
+            fractions.iter().sum::<f64>() / fractions.len() as f64
+        };
+        let median_fraction_removed = median(&mut fractions);
+
+        Self {
+            samples,
+            total_reads_in,
+            total_reads_removed,
+            total_reads_extracted,
+            mean_fraction_removed,
+            median_fraction_removed,
+            setting_mismatches: detect_setting_mismatches(reports),
+        }
+    }
+    pub fn write_json(&self, path: &Path) -> Result<(), ScrubbyError> {
+        let mut file = File::create(path)?;
+        let json_string = serde_json::to_string_pretty(self)?;
+        file.write_all(json_string.as_bytes())?;
+        Ok(())
+    }
+    /// Writes the per-sample rows as a TSV, the flat table form spreadsheet
+    /// and QC-dashboard tooling expects; cohort totals are not included here
+    /// since they don't fit the one-row-per-sample shape.
+    pub fn write_tsv(&self, path: &Path) -> Result<(), ScrubbyError> {
+        let mut file = File::create(path)?;
+        writeln!(file, "report\tcommand\treads_in\treads_out\treads_removed\treads_extracted\tfraction_removed")?;
+        for sample in &self.samples {
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{:.6}",
+                sample.report.display(),
+                sample.command,
+                sample.reads_in,
+                sample.reads_out,
+                sample.reads_removed,
+                sample.reads_extracted,
+                sample.fraction_removed
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Middle element by value (averaging the two middle elements for an even
+/// length), used for `CohortReport::median_fraction_removed`.
+fn median(values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Compares `index`/`preset`/`min_mapq` across all samples against the first
+/// sample's settings, returning one note per field that isn't uniform.
+fn detect_setting_mismatches(reports: &[(PathBuf, ScrubbyReport)]) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    let Some((_, first)) = reports.first() else {
+        return mismatches;
+    };
+
+    if reports.iter().any(|(_, r)| r.settings.index != first.settings.index) {
+        mismatches.push("index differs across samples in this cohort".to_string());
+    }
+    if reports.iter().any(|(_, r)| r.settings.preset != first.settings.preset) {
+        mismatches.push("preset differs across samples in this cohort".to_string());
+    }
+    if reports.iter().any(|(_, r)| r.settings.min_mapq != first.settings.min_mapq) {
+        mismatches.push("min_mapq differs across samples in this cohort".to_string());
+    }
+    mismatches
+}
+
+/// Sink for newline-delimited JSON (`--ndjson`) records: a compact `progress`
+/// line emitted periodically while reads are processed, followed by a single
+/// `summary` line carrying the full `ScrubbyReport` once cleaning completes.
+/// Opened once per run and shared between both record kinds so the
+/// destination (a file, or stdout for `-`) is never reopened mid-stream.
+pub struct ReportWriter {
+    writer: Box<dyn Write>,
+}
+
+impl ReportWriter {
+    /// Opens `path` for NDJSON output; a path of `-` writes to stdout.
+    pub fn ndjson(path: &Path) -> Result<Self, ScrubbyError> {
+        let writer: Box<dyn Write> = if path.to_str() == Some("-") {
+            Box::new(std::io::stdout())
+        } else {
+            Box::new(File::create(path)?)
+        };
+        Ok(Self { writer })
+    }
+    /// Writes a `{"type":"progress",...}` record for reads processed so far.
+    pub fn write_progress(&mut self, reads_in: u64, reads_removed: u64, elapsed_ms: u128) -> Result<(), ScrubbyError> {
+        #[derive(Serialize)]
+        struct Progress {
+            #[serde(rename = "type")]
+            kind: &'static str,
+            reads_in: u64,
+            reads_removed: u64,
+            elapsed_ms: u128,
+        }
+        self.write_line(&Progress { kind: "progress", reads_in, reads_removed, elapsed_ms })
+    }
+    /// Writes a `{"type":"summary",...}` record flattening the full `ScrubbyReport`.
+    pub fn write_summary(&mut self, report: &ScrubbyReport) -> Result<(), ScrubbyError> {
+        #[derive(Serialize)]
+        struct Summary<'a> {
+            #[serde(rename = "type")]
+            kind: &'static str,
+            #[serde(flatten)]
+            report: &'a ScrubbyReport,
+        }
+        self.write_line(&Summary { kind: "summary", report })
+    }
+    /// Serializes `value` compactly and writes it as a single terminated line,
+    /// so each record stays individually parseable by a streaming consumer.
+    fn write_line<T: Serialize>(&mut self, value: &T) -> Result<(), ScrubbyError> {
+        let line = serde_json::to_string(value)?;
+        writeln!(self.writer, "{line}")?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema)]
 pub struct ScrubbySettings {
+    /// Optional JSON Schema reference, so editors can offer validation and
+    /// autocomplete for config files written by hand (see
+    /// [`ScrubbySettings::emit_schema`]). Ignored when loading.
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
     pub aligner: Option<Aligner>,
     pub classifier: Option<Classifier>,
     pub index: Option<PathBuf>,
@@ -79,11 +604,20 @@ pub struct ScrubbySettings {
     pub min_len: u64,
     pub min_cov: f64,
     pub min_mapq: u8,
-    pub extract: bool
+    pub extract: bool,
+    pub complexity: bool,
+    pub min_entropy: f64,
+    /// Output compression algorithm, or `None` to infer it from the output
+    /// path's extension (see `CompressionAlgorithm::from_extension`).
+    pub compression_format: Option<CompressionAlgorithm>,
+    /// Output compression level, validated against `compression_format`'s
+    /// own range at build time; `None` falls back to the algorithm's default.
+    pub compression_level: Option<u32>,
 }
 impl ScrubbySettings {
     pub fn from_scrubby(scrubby: &Scrubby) -> Self {
         Self {
+            schema: None,
             aligner: scrubby.config.aligner.clone(),
             classifier: scrubby.config.classifier.clone(),
             index: scrubby.config.index.clone(),
@@ -98,7 +632,265 @@ impl ScrubbySettings {
             min_len: scrubby.config.min_query_length,
             min_cov: scrubby.config.min_query_coverage,
             min_mapq: scrubby.config.min_mapq,
-            extract: scrubby.extract
+            extract: scrubby.extract,
+            complexity: scrubby.config.complexity,
+            min_entropy: scrubby.config.min_entropy,
+            compression_format: scrubby.config.compression_format,
+            compression_level: scrubby.config.compression_level,
+        }
+    }
+    /// Loads run settings from a config file, so a reusable depletion profile
+    /// can be version-controlled instead of repeating long `--taxa`/
+    /// `--aligner-args` flags on every invocation.
+    ///
+    /// Files named `*.toml` are parsed as TOML; everything else is parsed as
+    /// JSONC, with `//` line comments and `/* ... */` block comments stripped
+    /// before parsing. Both formats accept an optional `"$schema"` key (see
+    /// [`Self::emit_schema`]) that is otherwise ignored.
+    pub fn from_config_file(path: &Path) -> Result<Self, ScrubbyError> {
+        let content = std::fs::read_to_string(path)?;
+        if path.extension().and_then(|ext| ext.to_str()) == Some("toml") {
+            Ok(toml::from_str(&content).map_err(|error| ScrubbyError::TomlConfigParseFailed(path.to_path_buf(), error.to_string()))?)
+        } else {
+            Ok(serde_json::from_str(&strip_jsonc_comments(&content))?)
+        }
+    }
+    /// Looks for a workspace-level `scrubby.toml` in `start_dir` and its
+    /// ancestors, stopping at the first one found (nearest directory wins),
+    /// so a reusable depletion profile can be checked into a project without
+    /// every invocation needing an explicit `--config`.
+    pub fn discover_workspace_config(start_dir: &Path) -> Option<PathBuf> {
+        let mut dir = Some(start_dir);
+        while let Some(current) = dir {
+            let candidate = current.join("scrubby.toml");
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+            dir = current.parent();
         }
+        None
+    }
+    /// Merges `self` over `lower`, keeping `self`'s value for any field it
+    /// sets and falling back to `lower`'s otherwise: `Option` fields take
+    /// `self` if `Some`, `Vec` fields take `self` if non-empty, and `bool`
+    /// fields are `true` if either sets it. Used to layer a user-supplied
+    /// `--config` (higher precedence) over a discovered workspace
+    /// `scrubby.toml` (lower precedence) before CLI flags are applied on top
+    /// of the result.
+    pub fn merge(self, lower: Self) -> Self {
+        Self {
+            schema: self.schema.or(lower.schema),
+            aligner: self.aligner.or(lower.aligner),
+            classifier: self.classifier.or(lower.classifier),
+            index: self.index.or(lower.index),
+            alignment: self.alignment.or(lower.alignment),
+            reads: self.reads.or(lower.reads),
+            report: self.report.or(lower.report),
+            taxa: if self.taxa.is_empty() { lower.taxa } else { self.taxa },
+            taxa_direct: if self.taxa_direct.is_empty() { lower.taxa_direct } else { self.taxa_direct },
+            classifier_args: self.classifier_args.or(lower.classifier_args),
+            aligner_args: self.aligner_args.or(lower.aligner_args),
+            preset: self.preset.or(lower.preset),
+            min_len: self.min_len,
+            min_cov: self.min_cov,
+            min_mapq: self.min_mapq,
+            extract: self.extract || lower.extract,
+            complexity: self.complexity || lower.complexity,
+            min_entropy: self.min_entropy,
+            compression_format: self.compression_format.or(lower.compression_format),
+            compression_level: self.compression_level.or(lower.compression_level),
+        }
+    }
+    /// Derives the JSON Schema for the config file format accepted by
+    /// [`Self::from_config_file`], pretty-printed for `scrubby config --emit-schema`.
+    pub fn emit_schema() -> Result<String, ScrubbyError> {
+        let schema = schemars::schema_for!(ScrubbySettings);
+        Ok(serde_json::to_string_pretty(&schema)?)
+    }
+}
+
+/// Strips `//` line comments and `/* ... */` block comments from JSONC
+/// `input`, leaving the contents of quoted JSON strings untouched so a
+/// literal `//` or `/*` inside a string value (e.g. a URL in `aligner_args`)
+/// is not mistaken for a comment.
+fn strip_jsonc_comments(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                output.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Read and base counts recorded for a single input/output file pair while
+/// depleting or extracting reads.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct FileDepletionStats {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub mode: String,
+    pub reads_in: u64,
+    pub reads_removed: u64,
+    pub reads_retained: u64,
+    pub bases_in: u64,
+    pub bases_retained: u64,
+    /// `bases_in`/`bases_retained` formatted as human-readable binary-prefixed
+    /// strings (e.g. "1.8 GiB"), kept alongside the raw integers so a reader
+    /// doesn't have to do the division themselves.
+    #[serde(default)]
+    pub bases_in_human: String,
+    #[serde(default)]
+    pub bases_retained_human: String,
+    /// Wall-clock time spent reading, filtering and writing this file.
+    #[serde(default)]
+    pub elapsed_secs: f64,
+}
+
+/// Structured, machine-readable depletion/extraction report accumulated
+/// across `Cleaner::run_aligner`, `run_classifier`, `clean_reads` and
+/// `parse_classifier_output`: per-file read counts, plus removed-read counts
+/// per resolved taxid for the classifier path, so pipelines can audit exactly
+/// how much signal was depleted per sample without re-parsing FASTQ.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DepletionStats {
+    pub files: Vec<FileDepletionStats>,
+    pub taxid_removed: HashMap<String, u64>,
+}
+
+impl DepletionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records read and base counts for one input/output file pair.
+    /// `elapsed_secs` is the wall-clock time spent producing this file, used
+    /// to roll up a total duration in [`Self::total_elapsed_secs`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_file(
+        &mut self,
+        input: &Path,
+        output: &Path,
+        extract: bool,
+        reads_in: u64,
+        reads_removed: u64,
+        bases_in: u64,
+        bases_retained: u64,
+        elapsed_secs: f64,
+    ) {
+        self.files.push(FileDepletionStats {
+            input: input.to_path_buf(),
+            output: output.to_path_buf(),
+            mode: if extract { "extraction".to_string() } else { "depletion".to_string() },
+            reads_in,
+            reads_removed,
+            reads_retained: reads_in - reads_removed,
+            bases_in,
+            bases_retained,
+            bases_in_human: format_bytes(bases_in),
+            bases_retained_human: format_bytes(bases_retained),
+            elapsed_secs,
+        });
+    }
+
+    /// Adds removed-read counts resolved per taxid, merging into any existing totals.
+    pub fn record_taxid_counts(&mut self, counts: &HashMap<String, u64>) {
+        for (taxid, count) in counts {
+            *self.taxid_removed.entry(taxid.clone()).or_insert(0) += count;
+        }
+    }
+
+    /// Total input bases summed across every recorded file.
+    pub fn total_bases_in(&self) -> u64 {
+        self.files.iter().map(|f| f.bases_in).sum()
+    }
+
+    /// Total retained bases summed across every recorded file.
+    pub fn total_bases_retained(&self) -> u64 {
+        self.files.iter().map(|f| f.bases_retained).sum()
+    }
+
+    /// Total wall-clock time across every recorded file, rolled up from each
+    /// [`FileDepletionStats::elapsed_secs`].
+    pub fn total_elapsed_secs(&self) -> f64 {
+        self.files.iter().map(|f| f.elapsed_secs).sum()
+    }
+
+    /// Reads observed by the most recently recorded file, used by `Scrubby::clean_async`
+    /// to report `CleanProgress.reads_processed` after each completed stage.
+    pub fn latest_reads_processed(&self) -> u64 {
+        self.files.last().map(|f| f.reads_in).unwrap_or(0)
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<(), ScrubbyError> {
+        let mut file = File::create(path)?;
+        let json_string = serde_json::to_string_pretty(self)?;
+        file.write_all(json_string.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn write_tsv(&self, path: &Path) -> Result<(), ScrubbyError> {
+        let mut file = File::create(path)?;
+        writeln!(file, "input\toutput\tmode\treads_in\treads_removed\treads_retained\tbases_in\tbases_retained\telapsed_secs")?;
+        for stats in &self.files {
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.3}",
+                stats.input.display(),
+                stats.output.display(),
+                stats.mode,
+                stats.reads_in,
+                stats.reads_removed,
+                stats.reads_retained,
+                stats.bases_in,
+                stats.bases_retained,
+                stats.elapsed_secs
+            )?;
+        }
+        if !self.taxid_removed.is_empty() {
+            writeln!(file)?;
+            writeln!(file, "taxid\treads_removed")?;
+            for (taxid, count) in &self.taxid_removed {
+                writeln!(file, "{}\t{}", taxid, count)?;
+            }
+        }
+        Ok(())
     }
 }
\ No newline at end of file
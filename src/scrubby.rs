@@ -20,15 +20,19 @@ use serde::{Serialize, Deserialize};
 use std::fs::create_dir_all;
 use std::path::PathBuf;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
 
 use crate::cleaner::Cleaner;
 use crate::error::ScrubbyError;
-use crate::utils::IntoVecPathBuf;
 use crate::report::ScrubbyReport;
-use crate::alignment::AlignmentFormat;
+use crate::utils::IntoVecPathBuf;
+use crate::alignment::{AlignmentFormat, PafFilterMode};
+use crate::compression::CompressionAlgorithm;
 
 /// Enum representing the available aligners.
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, clap::ValueEnum)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, clap::ValueEnum, schemars::JsonSchema)]
 pub enum Aligner {
     #[serde(rename="bowtie2")]
     Bowtie2,
@@ -69,12 +73,16 @@ impl fmt::Display for Aligner {
 }
 
 /// Enum representing the available classifiers.
-#[derive(Serialize, Deserialize, Clone, Debug, clap::ValueEnum)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, clap::ValueEnum, schemars::JsonSchema)]
 pub enum Classifier {
     #[serde(rename="kraken2")]
     Kraken2,
     #[serde(rename="metabuli")]
     Metabuli,
+    #[serde(rename="krakenuniq")]
+    KrakenUniq,
+    #[serde(rename="centrifuge")]
+    Centrifuge,
 }
 
 impl Classifier {
@@ -82,6 +90,8 @@ impl Classifier {
         match self {
             Classifier::Kraken2 => "k2",
             Classifier::Metabuli => "mb",
+            Classifier::KrakenUniq => "ku",
+            Classifier::Centrifuge => "cf",
         }
     }
 }
@@ -90,13 +100,44 @@ impl fmt::Display for Classifier {
         match self {
             Classifier::Kraken2 => write!(f, "kraken2"),
             Classifier::Metabuli => write!(f, "metabuli"),
+            Classifier::KrakenUniq => write!(f, "krakenuniq"),
+            Classifier::Centrifuge => write!(f, "centrifuge"),
         }
     }
 }
 
+/// Policy for merging read ID sets when both an aligner and a classifier are configured.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, clap::ValueEnum)]
+pub enum CombineMode {
+    /// Deplete a read if either the aligner or the classifier flagged it (maximizes sensitivity).
+    #[serde(rename="union")]
+    Union,
+    /// Deplete a read only if both the aligner and the classifier flagged it (maximizes specificity).
+    #[serde(rename="intersection")]
+    Intersection,
+    /// Deplete a read if more than half of the configured backends flagged it. With the two
+    /// backends currently supported (aligner, classifier) this requires agreement from both,
+    /// the same as `Intersection`; it is kept distinct so a future third backend changes the
+    /// threshold to 2-of-3 rather than silently requiring unanimous agreement.
+    #[serde(rename="majority")]
+    Majority,
+}
+impl fmt::Display for CombineMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CombineMode::Union => write!(f, "union"),
+            CombineMode::Intersection => write!(f, "intersection"),
+            CombineMode::Majority => write!(f, "majority"),
+        }
+    }
+}
 
-/// TODO: Enum representing the available classifiers output styles
-/// for direct classifier output cleaning 
+/// Selects which output-file layout to parse when cleaning directly from a
+/// pre-computed classifier report/read-classification pair (`ScrubbyConfig.reads`
+/// and `ScrubbyConfig.report`) rather than running a classifier binary.
+/// Set via `ScrubbyConfig.classifier_output`/`ScrubbyBuilder::classifier_output`;
+/// `classifier` alone is still honoured as a fallback format selector for
+/// backwards compatibility.
 #[derive(Serialize, Deserialize, Clone, Debug, clap::ValueEnum)]
 pub enum ClassifierOutput {
     #[serde(rename="kraken2")]
@@ -115,8 +156,20 @@ impl fmt::Display for ClassifierOutput {
         }
     }
 }
+impl ClassifierOutput {
+    /// Maps to the `Classifier` variant whose output-file layout matches, so
+    /// the parsing helpers on `Cleaner` (which key off `Classifier`) can be
+    /// reused for the dedicated `classifier_output` selector.
+    pub fn as_classifier(&self) -> Classifier {
+        match self {
+            ClassifierOutput::Kraken2 => Classifier::Kraken2,
+            ClassifierOutput::Metabuli => Classifier::Metabuli,
+            ClassifierOutput::Kraken2Uniq => Classifier::KrakenUniq,
+        }
+    }
+}
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, clap::ValueEnum)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, clap::ValueEnum, schemars::JsonSchema)]
 pub enum Preset {
     LrHq,
     Splice,
@@ -159,7 +212,16 @@ impl fmt::Display for Preset {
 pub struct Scrubby {
     pub input: Vec<PathBuf>,
     pub output: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
     pub json: Option<PathBuf>,
+    /// Destination for newline-delimited JSON progress/summary records, as an
+    /// alternative (or addition) to the single pretty-printed `json` report.
+    /// A path of `-` writes to stdout.
+    pub ndjson: Option<PathBuf>,
+    /// Destination for a gzip-compressed tar bundle (`report.json`,
+    /// `settings.json`, `read_ids.tsv`) combining everything needed to
+    /// reproduce or inspect this run from a single shareable file.
+    pub bundle: Option<PathBuf>,
     pub workdir: Option<PathBuf>,
     pub read_ids: Option<PathBuf>,
     pub extract: bool,
@@ -255,24 +317,229 @@ impl Scrubby {
     pub fn clean(&self) -> Result<(), ScrubbyError> {
         let cleaner = Cleaner::from_scrubby(self)?;
 
-        if self.config.aligner.is_some() {
+        if self.config.aligner.is_some() && self.config.classifier.is_some() && self.config.classifier_index.is_some() {
+            let mode = self.config.combine.as_ref().ok_or(ScrubbyError::AlignerAndClassifierConfigured)?;
+            cleaner.run_combined(mode)?;
+        } else if self.config.aligner.is_some() {
             cleaner.run_aligner()?;
-        }
-        if self.config.classifier.is_some() {
+        } else if self.config.classifier.is_some() && self.config.classifier_index.is_some() {
+            // Only spawn the classifier binary when an index is configured; a
+            // `classifier` set with `reads`/`report` but no `classifier_index`
+            // means cleaning directly from a pre-computed classifier output
+            // below, not running the classifier itself.
             cleaner.run_classifier()?;
         }
-        if self.config.reads.is_some() && self.config.report.is_some() {
+        if (self.config.reads.is_some() && self.config.report.is_some())
+            && (self.config.classifier.is_some() || self.config.classifier_output.is_some()) {
             cleaner.run_classifier_output()?;
         }
+        if let Some(bracken_report) = &self.config.bracken_report {
+            cleaner.run_bracken_report(bracken_report)?;
+        }
         if self.config.alignment.is_some() {
             cleaner.run_aligner_output()?;
         }
-        if self.json.is_some() || self.read_ids.is_some() {
-            ScrubbyReport::create(&self, true)?;
+        if self.config.complexity {
+            cleaner.run_complexity()?;
+        }
+        if self.config.sketch {
+            cleaner.run_sketch()?;
+        }
+        if self.json.is_some() || self.read_ids.is_some() || self.ndjson.is_some() || self.bundle.is_some() || crate::utils::json_log_enabled() {
+            let report = cleaner.create_report()?;
+            if self.ndjson.is_some() {
+                cleaner.write_ndjson_summary(&report)?;
+            }
+            if let Some(bundle) = &self.bundle {
+                report.to_bundle(bundle, self.read_ids.as_deref())?;
+            }
+            crate::utils::log_json_event("info", "reads", serde_json::json!({
+                "command": report.command,
+                "reads_in": report.reads_in,
+                "reads_out": report.reads_out,
+                "reads_removed": report.reads_removed,
+                "reads_extracted": report.reads_extracted,
+            }));
+        }
+        if self.config.audit {
+            cleaner.write_audit()?;
+        }
+        if self.config.stats_tsv.is_some() || self.config.stats_json.is_some() {
+            cleaner.write_stats()?;
         }
 
         Ok(())
     }
+    /// Runs `clean`'s stages on a background thread and returns a `CleanHandle`
+    /// exposing stage-level progress and a final `ScrubbyReport`, so a driver
+    /// program orchestrating many samples can poll live status for each one
+    /// instead of blocking on `clean`. Unlike `clean`, the report is always
+    /// built (not only when `json`/`read_ids`/`ndjson`/`bundle` are set) since
+    /// it is the handle's result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let handle = scrubby.clean_async()?;
+    /// while !handle.is_finished() {
+    ///     if let Some(progress) = handle.try_progress() {
+    ///         println!("{}: {} reads processed", progress.stage, progress.reads_processed);
+    ///     }
+    /// }
+    /// let report = handle.wait()?;
+    /// ```
+    pub fn clean_async(&self) -> Result<CleanHandle, ScrubbyError> {
+        let scrubby = self.clone();
+        let (progress_tx, progress_rx) = mpsc::channel();
+        let (report_tx, report_rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let cancel_thread = cancel.clone();
+
+        let thread = std::thread::spawn(move || {
+            let result = scrubby.clean_staged(&progress_tx, &cancel_thread);
+            let _ = report_tx.send(result);
+        });
+
+        Ok(CleanHandle {
+            progress_rx,
+            report_rx,
+            thread: Some(thread),
+            cancel,
+        })
+    }
+    /// Mirrors `clean`'s stage dispatch, sending a `CleanProgress` update
+    /// after each completed stage and unconditionally building the final
+    /// report. Checks `cancel` before each stage and bails out with
+    /// `ScrubbyError::CleanCancelled` as soon as `CleanHandle::cancel` sets
+    /// it, so a caller can stop a long run between stages without leaving it
+    /// to run to completion; it does not interrupt a stage already underway.
+    /// Used by `clean_async` on its background thread.
+    fn clean_staged(&self, progress: &mpsc::Sender<CleanProgress>, cancel: &Arc<AtomicBool>) -> Result<ScrubbyReport, ScrubbyError> {
+        let check_cancelled = |cancel: &Arc<AtomicBool>| -> Result<(), ScrubbyError> {
+            match cancel.load(Ordering::Relaxed) {
+                true => Err(ScrubbyError::CleanCancelled),
+                false => Ok(()),
+            }
+        };
+
+        let cleaner = Cleaner::from_scrubby(self)?;
+        let emit = |stage: &str, cleaner: &Cleaner| {
+            let _ = progress.send(CleanProgress {
+                stage: stage.to_string(),
+                reads_processed: cleaner.reads_processed(),
+            });
+        };
+
+        check_cancelled(cancel)?;
+        if self.config.aligner.is_some() && self.config.classifier.is_some() && self.config.classifier_index.is_some() {
+            let mode = self.config.combine.as_ref().ok_or(ScrubbyError::AlignerAndClassifierConfigured)?;
+            cleaner.run_combined(mode)?;
+            emit("combined", &cleaner);
+        } else if self.config.aligner.is_some() {
+            cleaner.run_aligner()?;
+            emit("aligner", &cleaner);
+        } else if self.config.classifier.is_some() && self.config.classifier_index.is_some() {
+            cleaner.run_classifier()?;
+            emit("classifier", &cleaner);
+        }
+        check_cancelled(cancel)?;
+        if (self.config.reads.is_some() && self.config.report.is_some())
+            && (self.config.classifier.is_some() || self.config.classifier_output.is_some()) {
+            cleaner.run_classifier_output()?;
+            emit("classifier_output", &cleaner);
+        }
+        check_cancelled(cancel)?;
+        if let Some(bracken_report) = &self.config.bracken_report {
+            cleaner.run_bracken_report(bracken_report)?;
+            emit("bracken_report", &cleaner);
+        }
+        check_cancelled(cancel)?;
+        if self.config.alignment.is_some() {
+            cleaner.run_aligner_output()?;
+            emit("aligner_output", &cleaner);
+        }
+        check_cancelled(cancel)?;
+        if self.config.complexity {
+            cleaner.run_complexity()?;
+            emit("complexity", &cleaner);
+        }
+        check_cancelled(cancel)?;
+        if self.config.sketch {
+            cleaner.run_sketch()?;
+            emit("sketch", &cleaner);
+        }
+
+        let report = cleaner.create_report()?;
+        if self.ndjson.is_some() {
+            cleaner.write_ndjson_summary(&report)?;
+        }
+        if let Some(bundle) = &self.bundle {
+            report.to_bundle(bundle, self.read_ids.as_deref())?;
+        }
+        if self.config.audit {
+            cleaner.write_audit()?;
+        }
+        if self.config.stats_tsv.is_some() || self.config.stats_json.is_some() {
+            cleaner.write_stats()?;
+        }
+        crate::utils::log_json_event("info", "reads", serde_json::json!({
+            "command": report.command,
+            "reads_in": report.reads_in,
+            "reads_out": report.reads_out,
+            "reads_removed": report.reads_removed,
+            "reads_extracted": report.reads_extracted,
+        }));
+        emit("report", &cleaner);
+
+        Ok(report)
+    }
+}
+
+/// Stage-level progress update delivered by a `clean_async` run's `CleanHandle`.
+#[derive(Clone, Debug)]
+pub struct CleanProgress {
+    /// Name of the stage that just completed, e.g. `"aligner"`, `"classifier"`, `"complexity"`, `"report"`.
+    pub stage: String,
+    /// Reads observed by the most recently completed depletion stage.
+    pub reads_processed: u64,
+}
+
+/// Handle to a `clean_async` run. `try_progress` polls the most recent stage
+/// update without blocking; `wait` blocks for the final `ScrubbyReport`. This
+/// lets a driver program run a pool of `Scrubby` instances concurrently and
+/// surface live status instead of waiting on opaque blocking `clean` calls.
+pub struct CleanHandle {
+    progress_rx: mpsc::Receiver<CleanProgress>,
+    report_rx: mpsc::Receiver<Result<ScrubbyReport, ScrubbyError>>,
+    thread: Option<JoinHandle<()>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl CleanHandle {
+    /// Returns the most recently emitted stage progress, or `None` if no new
+    /// update has arrived since the last call. Never blocks.
+    pub fn try_progress(&self) -> Option<CleanProgress> {
+        self.progress_rx.try_iter().last()
+    }
+    /// Returns `true` once the background thread has finished running.
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().map(|t| t.is_finished()).unwrap_or(true)
+    }
+    /// Requests that the run stop as soon as the stage currently in progress
+    /// finishes, rather than continuing to the next one. `wait` then returns
+    /// `Err(ScrubbyError::CleanCancelled)`. Does not kill an external tool
+    /// already running as part of the current stage.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+    /// Blocks until the run finishes and returns its final report.
+    pub fn wait(mut self) -> Result<ScrubbyReport, ScrubbyError> {
+        let report = self.report_rx.recv().map_err(|_| ScrubbyError::CleanAsyncChannelClosed)?;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        report
+    }
 }
 
 /// Configuration structure for Scrubby
@@ -280,10 +547,25 @@ impl Scrubby {
 pub struct ScrubbyConfig {
     pub aligner: Option<Aligner>,
     pub classifier: Option<Classifier>,
+    /// Name of a custom aligner backend registered with
+    /// `scrubby::backend::register_aligner_backend`. Takes precedence over
+    /// `aligner` in `Cleaner::run_aligner_ids`, so a library user can route
+    /// around the built-in `Aligner` enum without forking the crate.
+    pub custom_aligner: Option<String>,
+    /// Name of a custom classifier backend registered with
+    /// `scrubby::backend::register_classifier_backend`. Takes precedence over
+    /// `classifier` in `Cleaner::run_classifier_ids`, see `custom_aligner`.
+    pub custom_classifier: Option<String>,
     pub index: Option<PathBuf>,
     pub aligner_index: Option<PathBuf>,
     pub alignment: Option<PathBuf>,
     pub classifier_index: Option<PathBuf>,
+    /// Output format to parse when cleaning directly from a pre-computed
+    /// classifier output (`reads`/`report`) without running a classifier
+    /// binary. Overrides `classifier` for format selection in that mode, so
+    /// callers can clean from an archived report without also configuring
+    /// `classifier_index`/`classifier_args` for a binary that never runs.
+    pub classifier_output: Option<ClassifierOutput>,
     pub reads: Option<PathBuf>,
     pub report: Option<PathBuf>,
     pub taxa: Vec<String>,
@@ -297,16 +579,187 @@ pub struct ScrubbyConfig {
     pub min_query_length: u64,
     pub min_query_coverage: f64,
     pub min_mapq: u8,
+    /// Policy for combining `min_query_length` and `min_query_coverage` when
+    /// judging PAF/BAM alignment records as "mapped" for depletion.
+    pub paf_filter_mode: PafFilterMode,
+    /// When parsing a precomputed BAM/SAM/CRAM `--alignment` file, ignore
+    /// secondary and supplementary alignment records when deciding whether a
+    /// read passes the query length/coverage/mapq thresholds, so a noisy
+    /// partial secondary hit cannot flag a template for depletion on its own.
+    pub skip_secondary_alignments: bool,
+    /// When parsing a precomputed BAM/SAM/CRAM `--alignment` file, only count
+    /// an alignment toward depletion if it is part of a properly paired
+    /// template (the "proper pair" SAM flag). Ignored for single-end input.
+    pub require_proper_pair: bool,
+    /// Minimum alignment identity required for a record to count toward
+    /// depletion: `mlen / blen` for PAF/GAF, or `1 - NM / alignment_block_len`
+    /// (reconstructed from the `NM` tag and CIGAR) for BAM/SAM/CRAM. `0.0`
+    /// (the default) disables the filter; ignored for BAM/SAM/CRAM records
+    /// with no `NM` tag.
+    pub min_identity: f64,
+    /// Reference FASTA used to decode a CRAM `--alignment` file. CRAM records
+    /// are reference-compressed, so this is required whenever the precomputed
+    /// alignment file is CRAM; ignored for other alignment formats.
+    pub reference: Option<PathBuf>,
     pub preset: Option<Preset>,
     pub alignment_format: Option<AlignmentFormat>,
-    pub command: Option<String>
+    pub command: Option<String>,
+    /// When set, records which stage and reference database flagged each removed read,
+    /// written out to `audit_tsv` and/or `audit_json` after cleaning completes.
+    pub audit: bool,
+    pub audit_tsv: Option<PathBuf>,
+    pub audit_json: Option<PathBuf>,
+    /// Output path for a structured per-file/per-taxid depletion statistics report (.tsv).
+    pub stats_tsv: Option<PathBuf>,
+    /// Output path for a structured per-file/per-taxid depletion statistics report (.json).
+    pub stats_json: Option<PathBuf>,
+    /// When set, reads are depleted/extracted using the symmetric-DUST low-complexity
+    /// filter instead of a classifier or aligner.
+    pub complexity: bool,
+    pub complexity_method: crate::complexity::ComplexityMethod,
+    pub min_entropy: f64,
+    pub max_dust: Option<f64>,
+    pub complexity_window: usize,
+    /// When set, reads are depleted/extracted using FracMinHash sketch
+    /// containment against `sketch_index` instead of a classifier, aligner,
+    /// or the low-complexity filter.
+    pub sketch: bool,
+    /// Reference sketch file written by `scrubby sketch` (`FracMinHashSketch::write_json`).
+    pub sketch_index: Option<PathBuf>,
+    /// Minimum containment (fraction of a read's own sketch hashes found in
+    /// the reference sketch) required for depletion/extraction.
+    pub min_containment: f64,
+    /// Minimum number of a read's own sketch hashes required before its
+    /// containment score is trusted.
+    pub sketch_min_hashes: usize,
+    /// Minimum number of distinct k-mers (`KrakenUniq`'s HyperLogLog `kmers` column)
+    /// required for a selected taxon to be included in depletion/extraction.
+    pub min_unique_kmers: u64,
+    /// Minimum `dna_score` a read must have in Metabuli's read-level output to
+    /// be included in depletion/extraction, mirroring how `min_mapq` filters
+    /// the alignment path. Only used with `--classifier metabuli`; `0.0`
+    /// (the default) disables the filter.
+    pub metabuli_min_score: f64,
+    /// Path to a Bracken k-mer distribution database used to redistribute reads
+    /// assigned at ancestor nodes (above `bracken_rank`) down to species before
+    /// depletion, so selected species also pick up their share of ambiguous reads.
+    pub bracken_db: Option<PathBuf>,
+    /// Taxonomic rank at or above which directly-assigned reads are redistributed
+    /// to species using `bracken_db`. Only used with `--classifier kraken2`.
+    pub bracken_rank: Option<String>,
+    /// Path to write a standalone Bracken-style abundance re-estimation table,
+    /// built from the classifier report itself rather than `bracken_db`.
+    pub bracken_report: Option<PathBuf>,
+    /// Taxonomic rank `bracken_report` re-estimates abundance at, defaults to `species`.
+    pub bracken_level: Option<String>,
+    /// Path to a Krona text report summarising directly-assigned depleted read
+    /// counts by taxon lineage. Only used with `--classifier kraken2`.
+    pub krona: Option<PathBuf>,
+    /// Regex pattern used to strip a trailing paired-end orientation suffix
+    /// (e.g. `/1`, `/2`, `.1`, `.2`, Casava comments) from read identifiers
+    /// before depletion/extraction set membership is tested.
+    pub strip_suffix: Option<String>,
+    /// Output compression algorithm. Defaults to inferring the algorithm from
+    /// the `output` file extension when not set.
+    pub compression_format: Option<CompressionAlgorithm>,
+    /// Output compression level. Defaults to `compression_format`'s own
+    /// default level (e.g. 6 for gzip, 3 for zstd) when not set.
+    pub compression_level: Option<u32>,
+    /// Number of worker threads used to compress output. When greater than
+    /// one and the output format is gzip, writes a multithreaded BGZF stream
+    /// instead of `niffler`'s single-threaded encoder. Defaults to one thread.
+    pub compression_threads: Option<usize>,
+    /// When set, `minimap2`/`bowtie2`/`strobealign` output is parsed directly
+    /// with `rust_htslib` instead of being piped through `samtools view`,
+    /// removing the `samtools` dependency from the aligner
+    /// code path. Requires the `htslib` feature; ignored otherwise.
+    pub native_bam: bool,
+    /// When set, allows both `aligner` and `classifier` to be configured together:
+    /// reads are cleaned using the union or intersection of the two read ID sets,
+    /// rather than requiring exactly one depletion method.
+    pub combine: Option<CombineMode>,
+    /// Path to a directory containing the standard NCBI taxonomy dump
+    /// (`nodes.dmp`/`names.dmp`). When set, `taxa` sub-level extraction walks
+    /// the true taxonomic subtree from this graph instead of inferring it
+    /// from the classifier report's rank ordering.
+    pub taxonomy_directory: Option<PathBuf>,
+    /// Minimum cumulative `reads` a matched `taxa`/`taxa_direct` taxon must have
+    /// in the classifier report to be depleted, suppressing low-confidence taxa.
+    pub min_reads: Option<u64>,
+    /// Minimum `fraction` a matched `taxa`/`taxa_direct` taxon must have in the
+    /// classifier report to be depleted, suppressing low-confidence taxa.
+    pub min_fraction: Option<f64>,
+    /// Taxonomic rank name (e.g. `"genus"`, parsed with `parse_taxonomic_level`)
+    /// below which sub-level reads are rolled up into their nearest ancestor at
+    /// or above this rank before `min_reads`/`min_fraction` are applied, so
+    /// species-level noise does not each mint its own depleted taxid.
+    pub prune_rank: Option<String>,
+    /// Path to write a TSV audit table of every depleted taxon (`tax_id`,
+    /// `tax_name`, `tax_rank`, `parent`, `reads_direct`).
+    pub taxon_report: Option<PathBuf>,
+    /// When set, `input` must be a single interleaved FASTQ (alternating R1/R2
+    /// records) which is split into a paired R1/R2 stream before cleaning,
+    /// rather than requiring the pair to already be in separate files.
+    pub interleaved: bool,
+    /// Mean-quality cutoff for a sliding-window trim from both read ends, run
+    /// before alignment/classification. `None` disables quality trimming.
+    pub trim_quality: Option<u8>,
+    /// Adapter sequence trimmed from the 3' end of each read, either in full
+    /// or as a partial overlap with the read's end, before alignment/classification.
+    pub trim_adapter: Option<String>,
+    /// Minimum read length retained after `trim_quality`/`trim_adapter`; shorter
+    /// reads (or, for paired input, both mates of a pair) are dropped.
+    pub min_read_length: usize,
+    /// Sliding window size (bases) used by `trim_quality`.
+    pub preprocess_window: usize,
+    /// When set, attempts to merge each overlapping read pair into a single
+    /// consensus read before alignment/classification, collapsing the run to a
+    /// single-end pipeline over the merged sequence stream.
+    pub merge_pairs: bool,
+    /// Drop pairs that fail to merge instead of keeping their (trimmed) R1 mate
+    /// as a single-end read. Only used with `merge_pairs`.
+    pub exclude_unmerged: bool,
+    /// Minimum overlap (bases) required between R1 and the reverse complement
+    /// of R2 to call a merge. Only used with `merge_pairs`.
+    pub min_merge_overlap: usize,
+    /// When set, the aligner/classifier read-ID resolution stage is checkpointed
+    /// to `workdir` (required) and skipped on a later run if its configuration
+    /// is unchanged and the checkpointed read-ID cache is still present, so an
+    /// interrupted or crashed pipeline can pick back up without re-invoking the
+    /// external tool. Ignored (with a warning) if no `workdir` is set.
+    pub resume: bool,
+    /// When set, the JSON summary's `provenance` block is populated with the
+    /// parsed `--version` output of the aligner/classifier actually invoked
+    /// and a SHA-256 digest of every input file and the reference
+    /// database/index path, so a depleted dataset can be traced back to
+    /// exactly what produced it. Off by default since digesting a large
+    /// reference database is not free.
+    pub provenance: bool,
+    /// Additional reference indices run against the configured
+    /// aligner/classifier, after the primary `aligner_index`/`classifier_index`.
+    /// Each index's mapped/classified read IDs are resolved independently and
+    /// unioned with the primary index's set in memory before the single final
+    /// `clean_reads` pass, so depleting against a chain of N reference
+    /// databases (e.g. host, then a separate contaminant panel) costs no
+    /// intermediate FASTX file beyond what the primary index's own run
+    /// already writes. Run one at a time unless `index_concurrency` raises
+    /// the worker count.
+    pub additional_indices: Vec<PathBuf>,
+    /// Number of `additional_indices` resolved concurrently. Defaults to `1`
+    /// (sequential, the original behavior); raising it bounds how many extra
+    /// aligner/classifier invocations run in parallel, trading peak memory/IO
+    /// for wall-clock time on a run with several independent reference indices.
+    pub index_concurrency: usize,
 }
 
 /// Builder for constructing a `Scrubby` instance.
 pub struct ScrubbyBuilder {
     pub input: Vec<PathBuf>,
     pub output: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
     pub json: Option<PathBuf>,
+    pub ndjson: Option<PathBuf>,
+    pub bundle: Option<PathBuf>,
     pub workdir: Option<PathBuf>,
     pub read_ids: Option<PathBuf>,
     pub extract: bool,
@@ -353,7 +806,10 @@ impl ScrubbyBuilder {
         Self {
             input,
             output,
+            removed: Vec::new(),
             json: None,
+            ndjson: None,
+            bundle: None,
             workdir: None,
             read_ids: None,
             extract: false,
@@ -362,10 +818,13 @@ impl ScrubbyBuilder {
             config: ScrubbyConfig {
                 aligner: None,
                 classifier: None,
+                custom_aligner: None,
+                custom_classifier: None,
                 index: None,
                 aligner_index: None,
                 alignment: None,
                 classifier_index: None,
+                classifier_output: None,
                 reads: None,
                 report: None,
                 taxa: Vec::new(),
@@ -379,12 +838,138 @@ impl ScrubbyBuilder {
                 min_query_length: 0,
                 min_query_coverage: 0.0,
                 min_mapq: 0,
+                paf_filter_mode: PafFilterMode::default(),
+                skip_secondary_alignments: false,
+                require_proper_pair: false,
+                min_identity: 0.0,
+                reference: None,
                 alignment_format: None,
                 preset: None,
-                command: None
+                command: None,
+                audit: false,
+                audit_tsv: None,
+                audit_json: None,
+                stats_tsv: None,
+                stats_json: None,
+                complexity: false,
+                complexity_method: crate::complexity::ComplexityMethod::Dust,
+                min_entropy: crate::complexity::DEFAULT_MIN_ENTROPY,
+                max_dust: None,
+                complexity_window: crate::complexity::DEFAULT_COMPLEXITY_WINDOW,
+                sketch: false,
+                sketch_index: None,
+                min_containment: crate::sketch::DEFAULT_MIN_CONTAINMENT,
+                sketch_min_hashes: crate::sketch::DEFAULT_MIN_SKETCH_HASHES,
+                min_unique_kmers: 0,
+                metabuli_min_score: 0.0,
+                bracken_db: None,
+                bracken_rank: None,
+                bracken_report: None,
+                bracken_level: None,
+                krona: None,
+                strip_suffix: None,
+                compression_format: None,
+                compression_level: None,
+                compression_threads: None,
+                native_bam: false,
+                combine: None,
+                taxonomy_directory: None,
+                min_reads: None,
+                min_fraction: None,
+                prune_rank: None,
+                taxon_report: None,
+                interleaved: false,
+                trim_quality: None,
+                trim_adapter: None,
+                min_read_length: crate::preprocess::DEFAULT_MIN_READ_LENGTH,
+                preprocess_window: crate::preprocess::DEFAULT_TRIM_WINDOW,
+                merge_pairs: false,
+                exclude_unmerged: false,
+                min_merge_overlap: crate::preprocess::DEFAULT_MIN_MERGE_OVERLAP,
+                resume: false,
+                provenance: false,
+                additional_indices: Vec::new(),
+                index_concurrency: 1,
             },
         }
     }
+    /// Sets the `interleaved` field. When set, `input` must be a single file
+    /// containing alternating R1/R2 records, which is split into a paired
+    /// stream before cleaning runs (`paired_end` is then forced to `true`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).interleaved(true);
+    /// ```
+    pub fn interleaved(mut self, interleaved: bool) -> Self {
+        self.config.interleaved = interleaved;
+        self
+    }
+    /// Sets the `trim_quality` mean-quality cutoff for the pre-depletion sliding-window trim.
+    pub fn trim_quality<T: Into<Option<u8>>>(mut self, trim_quality: T) -> Self {
+        self.config.trim_quality = trim_quality.into();
+        self
+    }
+    /// Sets the `trim_adapter` sequence trimmed from the 3' end before depletion.
+    pub fn trim_adapter<T: Into<Option<String>>>(mut self, trim_adapter: T) -> Self {
+        self.config.trim_adapter = trim_adapter.into();
+        self
+    }
+    /// Sets the `min_read_length` retained after pre-depletion trimming.
+    pub fn min_read_length(mut self, min_read_length: usize) -> Self {
+        self.config.min_read_length = min_read_length;
+        self
+    }
+    /// Sets the sliding window size (bases) used by `trim_quality`.
+    pub fn preprocess_window(mut self, preprocess_window: usize) -> Self {
+        self.config.preprocess_window = preprocess_window;
+        self
+    }
+    /// Sets `merge_pairs`, attempting to merge overlapping read pairs into a
+    /// single consensus read before alignment/classification.
+    pub fn merge_pairs(mut self, merge_pairs: bool) -> Self {
+        self.config.merge_pairs = merge_pairs;
+        self
+    }
+    /// Sets `exclude_unmerged`. Only used with `merge_pairs`.
+    pub fn exclude_unmerged(mut self, exclude_unmerged: bool) -> Self {
+        self.config.exclude_unmerged = exclude_unmerged;
+        self
+    }
+    /// Sets the minimum overlap (bases) required to merge a read pair. Only used with `merge_pairs`.
+    pub fn min_merge_overlap(mut self, min_merge_overlap: usize) -> Self {
+        self.config.min_merge_overlap = min_merge_overlap;
+        self
+    }
+    /// Sets `resume`, checkpointing the aligner/classifier read-ID resolution
+    /// stage to `workdir` and skipping it on a later run with matching
+    /// configuration and an intact checkpoint cache.
+    pub fn resume(mut self, resume: bool) -> Self {
+        self.config.resume = resume;
+        self
+    }
+    /// Sets `provenance`, populating the JSON summary's tool-version and
+    /// input/database digest manifest.
+    pub fn provenance(mut self, provenance: bool) -> Self {
+        self.config.provenance = provenance;
+        self
+    }
+    /// Sets `additional_indices`, chaining extra reference indices against
+    /// the configured aligner/classifier after the primary
+    /// `aligner_index`/`classifier_index`.
+    pub fn additional_indices(mut self, additional_indices: Vec<PathBuf>) -> Self {
+        self.config.additional_indices = additional_indices;
+        self
+    }
+    /// Sets `index_concurrency`, bounding how many `additional_indices` are
+    /// resolved in parallel rather than one at a time.
+    pub fn index_concurrency(mut self, index_concurrency: usize) -> Self {
+        self.config.index_concurrency = index_concurrency;
+        self
+    }
     /// Sets the `read_ids` field.
     ///
     /// # Example
@@ -399,6 +984,22 @@ impl ScrubbyBuilder {
         self.read_ids = read_ids.into();
         self
     }
+    /// Sets the output paths for the reads removed during cleaning, one per
+    /// `output` file, respecting the same paired layout and compression
+    /// inference as `output`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    /// use std::path::PathBuf;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).removed(vec![PathBuf::from("removed.fastq")]);
+    /// ```
+    pub fn removed<P: IntoVecPathBuf>(mut self, removed: P) -> Self {
+        self.removed = removed.into_vec_path_buf();
+        self
+    }
     /// Sets the `json` field.
     ///
     /// # Example
@@ -413,6 +1014,37 @@ impl ScrubbyBuilder {
         self.json = json.into();
         self
     }
+    /// Sets the `ndjson` field - a destination for newline-delimited JSON
+    /// progress/summary records (`-` for stdout), streamed during cleaning
+    /// instead of (or alongside) the single pretty-printed `json` report.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    /// use std::path::PathBuf;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).ndjson(PathBuf::from("report.ndjson"));
+    /// ```
+    pub fn ndjson<T: Into<Option<PathBuf>>>(mut self, ndjson: T) -> Self {
+        self.ndjson = ndjson.into();
+        self
+    }
+    /// Sets the `bundle` field - a destination for a gzip-compressed tar
+    /// bundle combining the `json` report, `read_ids` list, and settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    /// use std::path::PathBuf;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).bundle(PathBuf::from("run.tar.gz"));
+    /// ```
+    pub fn bundle<T: Into<Option<PathBuf>>>(mut self, bundle: T) -> Self {
+        self.bundle = bundle.into();
+        self
+    }
     /// Sets the `command` field.
     ///
     /// # Example
@@ -506,6 +1138,21 @@ impl ScrubbyBuilder {
         self.config.aligner = aligner.into();
         self
     }
+    /// Sets the `custom_aligner` field, selecting a backend registered with
+    /// `scrubby::backend::register_aligner_backend` by name instead of one
+    /// of the built-in `Aligner` variants.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).custom_aligner("bwa-mem2");
+    /// ```
+    pub fn custom_aligner<T: Into<Option<String>>>(mut self, custom_aligner: T) -> Self {
+        self.config.custom_aligner = custom_aligner.into();
+        self
+    }
     /// Sets the `alignment` field.
     ///
     /// # Example
@@ -576,6 +1223,44 @@ impl ScrubbyBuilder {
         self.config.min_mapq = min_mapq;
         self
     }
+    /// Sets the `paf_filter_mode` field - whether `min_query_length` and
+    /// `min_query_coverage` must both be met (`All`) or either is sufficient
+    /// (`Any`, the default) for a PAF/BAM record to count as "mapped".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    /// use scrubby::prelude::PafFilterMode;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).paf_filter_mode(PafFilterMode::All);
+    /// ```
+    pub fn paf_filter_mode(mut self, paf_filter_mode: PafFilterMode) -> Self {
+        self.config.paf_filter_mode = paf_filter_mode;
+        self
+    }
+    /// Sets the `skip_secondary_alignments` field used when parsing a
+    /// precomputed BAM/SAM/CRAM `--alignment` file.
+    pub fn skip_secondary_alignments(mut self, skip_secondary_alignments: bool) -> Self {
+        self.config.skip_secondary_alignments = skip_secondary_alignments;
+        self
+    }
+    /// Sets the `require_proper_pair` field used when parsing a precomputed
+    /// BAM/SAM/CRAM `--alignment` file.
+    pub fn require_proper_pair(mut self, require_proper_pair: bool) -> Self {
+        self.config.require_proper_pair = require_proper_pair;
+        self
+    }
+    /// Sets the `min_identity` threshold applied to PAF/GAF/BAM/SAM/CRAM alignment records.
+    pub fn min_identity(mut self, min_identity: f64) -> Self {
+        self.config.min_identity = min_identity;
+        self
+    }
+    /// Sets the reference FASTA used to decode a CRAM `--alignment` file.
+    pub fn reference(mut self, reference: Option<PathBuf>) -> Self {
+        self.config.reference = reference;
+        self
+    }
     /// Sets the `classifier` field.
     ///
     /// # Example
@@ -589,6 +1274,21 @@ impl ScrubbyBuilder {
         self.config.classifier = classifier.into();
         self
     }
+    /// Sets the `custom_classifier` field, selecting a backend registered with
+    /// `scrubby::backend::register_classifier_backend` by name instead of one
+    /// of the built-in `Classifier` variants.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).custom_classifier("centrifuge2");
+    /// ```
+    pub fn custom_classifier<T: Into<Option<String>>>(mut self, custom_classifier: T) -> Self {
+        self.config.custom_classifier = custom_classifier.into();
+        self
+    }
     /// Sets the `reads` field.
     ///
     /// # Example
@@ -659,6 +1359,21 @@ impl ScrubbyBuilder {
         self.config.classifier_index = classifier_index.into();
         self
     }
+    /// Sets the `classifier_output` field, selecting the output format to
+    /// parse when cleaning directly from a pre-computed `reads`/`report`
+    /// pair without running a classifier binary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::{ScrubbyBuilder, ClassifierOutput};
+    ///
+    /// let builder = ScrubbyBuilder::new(...).classifier_output(ClassifierOutput::Kraken2Uniq);
+    /// ```
+    pub fn classifier_output<T: Into<Option<ClassifierOutput>>>(mut self, classifier_output: T) -> Self {
+        self.config.classifier_output = classifier_output.into();
+        self
+    }
     /// Sets the `taxa` field.
     ///
     /// # Example
@@ -751,20 +1466,410 @@ impl ScrubbyBuilder {
         self.config.preset = preset.into();
         self
     }
+    /// Enables recording a per-read removal audit trail attributing each removed
+    /// read to the stage and reference database that flagged it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).audit(true);
+    /// ```
+    pub fn audit(mut self, audit: bool) -> Self {
+        self.config.audit = audit;
+        self
+    }
+    /// Sets the `audit_tsv` output path.
+    pub fn audit_tsv<T: Into<Option<PathBuf>>>(mut self, audit_tsv: T) -> Self {
+        self.config.audit_tsv = audit_tsv.into();
+        self
+    }
+    /// Sets the `audit_json` output path.
+    pub fn audit_json<T: Into<Option<PathBuf>>>(mut self, audit_json: T) -> Self {
+        self.config.audit_json = audit_json.into();
+        self
+    }
+    /// Sets the `stats_tsv` output path for the structured depletion statistics report.
+    pub fn stats_tsv<T: Into<Option<PathBuf>>>(mut self, stats_tsv: T) -> Self {
+        self.config.stats_tsv = stats_tsv.into();
+        self
+    }
+    /// Sets the `stats_json` output path for the structured depletion statistics report.
+    pub fn stats_json<T: Into<Option<PathBuf>>>(mut self, stats_json: T) -> Self {
+        self.config.stats_json = stats_json.into();
+        self
+    }
+    /// Sets the `min_entropy` threshold used by the low-complexity (DUST) filter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).min_entropy(0.9);
+    /// ```
+    pub fn min_entropy(mut self, min_entropy: f64) -> Self {
+        self.config.min_entropy = min_entropy;
+        self
+    }
+    /// Sets the scoring method (`dust` or `entropy`) used by the low-complexity filter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    /// use scrubby::complexity::ComplexityMethod;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).complexity_method(ComplexityMethod::Entropy);
+    /// ```
+    pub fn complexity_method(mut self, complexity_method: crate::complexity::ComplexityMethod) -> Self {
+        self.config.complexity_method = complexity_method;
+        self
+    }
+    /// Sets an explicit symmetric-DUST cutoff (`--max-dust`), overriding the value
+    /// otherwise derived from `--min-entropy` when `complexity_method` is `Dust`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).max_dust(3.0);
+    /// ```
+    pub fn max_dust<T: Into<Option<f64>>>(mut self, max_dust: T) -> Self {
+        self.config.max_dust = max_dust.into();
+        self
+    }
+    /// Sets the sliding window size (bases) used by the low-complexity (DUST) filter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).complexity_window(64);
+    /// ```
+    pub fn complexity_window(mut self, complexity_window: usize) -> Self {
+        self.config.complexity_window = complexity_window;
+        self
+    }
+    /// Sets the reference sketch file used by the FracMinHash containment filter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).sketch_index("human.sketch.json");
+    /// ```
+    pub fn sketch_index<T: Into<Option<PathBuf>>>(mut self, sketch_index: T) -> Self {
+        self.config.sketch_index = sketch_index.into();
+        self
+    }
+    /// Sets the `min_containment` threshold used by the FracMinHash containment filter.
+    pub fn min_containment(mut self, min_containment: f64) -> Self {
+        self.config.min_containment = min_containment;
+        self
+    }
+    /// Sets the `sketch_min_hashes` guard used by the FracMinHash containment filter.
+    pub fn sketch_min_hashes(mut self, sketch_min_hashes: usize) -> Self {
+        self.config.sketch_min_hashes = sketch_min_hashes;
+        self
+    }
+    /// Sets the `min_unique_kmers` threshold used to suppress `KrakenUniq` taxa
+    /// with insufficient distinct k-mer support.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).min_unique_kmers(100);
+    /// ```
+    pub fn min_unique_kmers(mut self, min_unique_kmers: u64) -> Self {
+        self.config.min_unique_kmers = min_unique_kmers;
+        self
+    }
+    /// Sets the minimum Metabuli `dna_score` a read must have to be included
+    /// in depletion/extraction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).metabuli_min_score(0.5);
+    /// ```
+    pub fn metabuli_min_score(mut self, metabuli_min_score: f64) -> Self {
+        self.config.metabuli_min_score = metabuli_min_score;
+        self
+    }
+    /// Sets the path to a Bracken k-mer distribution database for read redistribution.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    /// use std::path::PathBuf;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).bracken_db(PathBuf::from("bracken.db"));
+    /// ```
+    pub fn bracken_db<T: Into<Option<PathBuf>>>(mut self, bracken_db: T) -> Self {
+        self.config.bracken_db = bracken_db.into();
+        self
+    }
+    /// Sets the rank at or above which reads are redistributed to species using `bracken_db`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).bracken_rank("genus".to_string());
+    /// ```
+    pub fn bracken_rank<T: Into<Option<String>>>(mut self, bracken_rank: T) -> Self {
+        self.config.bracken_rank = bracken_rank.into();
+        self
+    }
+    /// Sets the output path for a standalone Bracken-style abundance re-estimation table.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    /// use std::path::PathBuf;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).bracken_report(PathBuf::from("abundance.tsv"));
+    /// ```
+    pub fn bracken_report<T: Into<Option<PathBuf>>>(mut self, bracken_report: T) -> Self {
+        self.config.bracken_report = bracken_report.into();
+        self
+    }
+    /// Sets the taxonomic rank `bracken_report` re-estimates abundance at.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).bracken_level("species".to_string());
+    /// ```
+    pub fn bracken_level<T: Into<Option<String>>>(mut self, bracken_level: T) -> Self {
+        self.config.bracken_level = bracken_level.into();
+        self
+    }
+    /// Sets the output path for a Krona text report of depleted taxon lineages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    /// use std::path::PathBuf;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).krona(PathBuf::from("report.krona.txt"));
+    /// ```
+    pub fn krona<T: Into<Option<PathBuf>>>(mut self, krona: T) -> Self {
+        self.config.krona = krona.into();
+        self
+    }
+    /// Sets the `strip_suffix` pattern used to normalize read identifiers before matching.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).strip_suffix("/[12]$".to_string());
+    /// ```
+    pub fn strip_suffix<T: Into<Option<String>>>(mut self, strip_suffix: T) -> Self {
+        self.config.strip_suffix = strip_suffix.into();
+        self
+    }
+    /// Sets the output compression algorithm, overriding extension-based inference.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::{ScrubbyBuilder, CompressionAlgorithm};
+    ///
+    /// let builder = ScrubbyBuilder::new(...).compression_format(CompressionAlgorithm::Zstd);
+    /// ```
+    pub fn compression_format<T: Into<Option<CompressionAlgorithm>>>(mut self, compression_format: T) -> Self {
+        self.config.compression_format = compression_format.into();
+        self
+    }
+    /// Sets the output compression level, validated against `compression_format` at build time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).compression_level(19u32);
+    /// ```
+    pub fn compression_level<T: Into<Option<u32>>>(mut self, compression_level: T) -> Self {
+        self.config.compression_level = compression_level.into();
+        self
+    }
+    /// Sets the number of worker threads used to compress output, enabling a
+    /// multithreaded BGZF writer for gzip output when greater than one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).compression_threads(4usize);
+    /// ```
+    pub fn compression_threads<T: Into<Option<usize>>>(mut self, compression_threads: T) -> Self {
+        self.config.compression_threads = compression_threads.into();
+        self
+    }
+    /// Sets the `native_bam` field, routing aligner output through an
+    /// in-process `rust_htslib` parser instead of the `samtools` shell pipeline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).native_bam(true);
+    /// ```
+    pub fn native_bam(mut self, native_bam: bool) -> Self {
+        self.config.native_bam = native_bam;
+        self
+    }
+    /// Sets the `combine` field, allowing `aligner` and `classifier` to be
+    /// configured together and merged via `CombineMode::Union` or `CombineMode::Intersection`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::{ScrubbyBuilder, CombineMode};
+    ///
+    /// let builder = ScrubbyBuilder::new(...).combine(CombineMode::Union);
+    /// ```
+    pub fn combine<T: Into<Option<CombineMode>>>(mut self, combine: T) -> Self {
+        self.config.combine = combine.into();
+        self
+    }
+    /// Sets the directory containing the NCBI taxonomy dump (`nodes.dmp`/`names.dmp`)
+    /// used to resolve true taxonomic subtrees for `taxa` extraction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).taxonomy_directory(PathBuf::from("taxdump"));
+    /// ```
+    pub fn taxonomy_directory<T: Into<Option<PathBuf>>>(mut self, taxonomy_directory: T) -> Self {
+        self.config.taxonomy_directory = taxonomy_directory.into();
+        self
+    }
+    /// Sets the minimum cumulative report `reads` a matched taxon must have to be depleted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).min_reads(100);
+    /// ```
+    pub fn min_reads<T: Into<Option<u64>>>(mut self, min_reads: T) -> Self {
+        self.config.min_reads = min_reads.into();
+        self
+    }
+    /// Sets the minimum report `fraction` a matched taxon must have to be depleted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).min_fraction(0.001);
+    /// ```
+    pub fn min_fraction<T: Into<Option<f64>>>(mut self, min_fraction: T) -> Self {
+        self.config.min_fraction = min_fraction.into();
+        self
+    }
+    /// Sets the taxonomic rank below which sub-level reads are rolled up into
+    /// their nearest ancestor before abundance thresholds are applied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).prune_rank("genus".to_string());
+    /// ```
+    pub fn prune_rank<T: Into<Option<String>>>(mut self, prune_rank: T) -> Self {
+        self.config.prune_rank = prune_rank.into();
+        self
+    }
+    /// Sets the path to write a TSV audit table of every depleted taxon.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let builder = ScrubbyBuilder::new(...).taxon_report(PathBuf::from("taxa.tsv"));
+    /// ```
+    pub fn taxon_report<T: Into<Option<PathBuf>>>(mut self, taxon_report: T) -> Self {
+        self.config.taxon_report = taxon_report.into();
+        self
+    }
     pub fn validate_base_config(&mut self) -> Result<(), ScrubbyError> {
 
+        // Interleaved input is a single file split into an R1/R2 stream later
+        // in the cleaning pipeline, so it must not already be given as a pair
+        if self.config.interleaved {
+            if self.input.len() != 1 {
+                return Err(ScrubbyError::InterleavedInputNotSingleFile);
+            }
+            self.config.paired_end = true;
+        }
+
+        // Pair merging collapses the paired input into a single merged/unmerged
+        // output stream, so it requires exactly one pair of input files and a
+        // single output file to write that stream into
+        if self.config.merge_pairs && (self.input.len() != 2 || self.output.len() != 1) {
+            return Err(ScrubbyError::MergePairsRequiresPairedSingleOutput);
+        }
+
         // Check if input and output vectors are not empty
         if self.input.is_empty() || self.output.is_empty() {
             return Err(ScrubbyError::EmptyInputOutput);
         }
+        // Interleaved input is split into a paired R1/R2 stream before cleaning,
+        // so it is validated against the two output files it will produce,
+        // rather than against the single interleaved input file itself; merged
+        // pairs collapse into one stream written to the single output file
+        let input_arity = if self.config.interleaved { 2 } else if self.config.merge_pairs { 1 } else { self.input.len() };
         // Check if input and output vectors have the same length
-        if self.input.len() != self.output.len() {
+        if input_arity != self.output.len() {
             return Err(ScrubbyError::MismatchedInputOutputLength);
         }
         // Check if input and output vectors length is limited to one or two
         if self.input.len() > 2 || self.output.len() > 2 {
             return Err(ScrubbyError::InputOutputLengthExceeded);
         }
+        // If removed-read output paths are provided, they must match the output arity
+        if !self.removed.is_empty() && self.removed.len() != self.output.len() {
+            return Err(ScrubbyError::MismatchedRemovedOutputLength);
+        }
+        // Validate the compression level against the algorithm's supported range,
+        // resolving the algorithm from the first output file's extension if not set explicitly
+        if let Some(level) = self.config.compression_level {
+            let algorithm = self.config.compression_format.unwrap_or_else(|| {
+                CompressionAlgorithm::from_extension(
+                    self.output.first().and_then(|path| path.extension()).and_then(|ext| ext.to_str())
+                )
+            });
+            crate::compression::Compression::new(algorithm, Some(level))?;
+        }
         // Check if each input file exists and is a file
         for input_file in &self.input {
             if !input_file.exists() || !input_file.is_file() {
@@ -777,6 +1882,9 @@ impl ScrubbyBuilder {
                 create_dir_all(&dir)?;
             }
         }
+        if self.config.resume && self.workdir.is_none() {
+            log::warn!("--resume has no effect without --workdir - nowhere to checkpoint to");
+        }
 
         if self.config.index.is_some() {
             if self.config.aligner.is_some() {
@@ -821,12 +1929,14 @@ impl ScrubbyBuilder {
                 self.config.aligner = Some(Aligner::Minimap2Rs)
             }
         }
-        // Check if only one of aligner or classifier is set
-        if self.config.aligner.is_some() && self.config.classifier.is_some() {
+        // Check if only one of aligner or classifier is set, unless a combine mode
+        // is configured to merge the read ID sets from both
+        if self.config.aligner.is_some() && self.config.classifier.is_some() && self.config.combine.is_none() {
             return Err(ScrubbyError::AlignerAndClassifierConfigured);
         }
-        // Check if only one of aligner or classifier index is set
-        if self.config.aligner_index.is_some() && self.config.classifier_index.is_some() {
+        // Check if only one of aligner or classifier index is set, unless a combine
+        // mode is configured (both indices are then required, see checks below)
+        if self.config.aligner_index.is_some() && self.config.classifier_index.is_some() && self.config.combine.is_none() {
             return Err(ScrubbyError::AlignerAndClassifierIndexConfigured);
         }
         // Check if classifier is set and necessary fields are populated
@@ -851,94 +1961,26 @@ impl ScrubbyBuilder {
             }
         }
         
-        // If the index file for Strobealign ends in ".sti" strobealign expects the 
-        // underlying FASTA file to be in the same directory (v0.13.0) - this is 
-        // kinda weird...
-        if let Some(Aligner::Strobealign) = &self.config.aligner {
-            if let Some(file) = &self.config.aligner_index {
-                if file.extension().unwrap_or_default() == "sti" {
-                    let index_base_file = file.with_extension("").with_extension("");
-                    if !index_base_file.exists() {
-                        return Err(ScrubbyError::MissingStrobealignIndexBaseFile(index_base_file.clone()));
-                    }
-                }
-            }
-        }
-        // If Bowtie2 aligner is set, check index files exist and are files
-        // otherwise check if the aligner index file provided exists and is a file
-        if let Some(Aligner::Bowtie2) = &self.config.aligner {
-            if let Some(file) = &self.config.aligner_index {
-                // Check if Bowtie2 index files are all present
-                let bowtie2_small_extensions = ["1.bt2", "2.bt2", "3.bt2", "4.bt2", "rev.1.bt2", "rev.2.bt2"];
-                let bowtie2_large_extensions = ["1.bt21", "2.bt21", "3.bt21", "4.bt21", "rev.1.bt21", "rev.2.bt21"];
-                for (small_ext, large_ext) in bowtie2_small_extensions.iter().zip(bowtie2_large_extensions.iter()) {
-                    let small_index_file = file.with_extension(small_ext);
-                    let large_index_file = file.with_extension(large_ext);
-                    if !small_index_file.exists() || !small_index_file.is_file() {
-                        if !large_index_file.exists() || !large_index_file.is_file() {
-                            return Err(ScrubbyError::MissingBowtie2IndexFiles(file.clone()));
-                        }
-                    }
-                }
-            }
-        } else {
+        // Index and preset validation is delegated to the aligner's registered
+        // `AlignerBackend` profile (see `backend::resolve_aligner_backend`),
+        // rather than a hardcoded per-variant match here: each built-in
+        // aligner's index layout (single file, Bowtie2's `.bt2` set,
+        // Strobealign's `.sti` companion file) and preset defaults/support are
+        // encoded once on its profile and reused by any aligner a downstream
+        // crate registers via `backend::register_aligner_backend`.
+        if let Some(aligner) = &self.config.aligner {
+            let profile = crate::backend::resolve_aligner_backend(aligner);
+
             if let Some(file) = &self.config.aligner_index {
-                if !file.exists() || !file.is_file() {
-                    return Err(ScrubbyError::MissingAlignmentIndexFile(file.clone()));
-                }
+                profile.validate_index(file)?;
             }
-        }
-
-
 
-        // Check that a default preset is set with Minimap2 or that 
-        // the preset is supported by Minimap2
-        if let Some(Aligner::Minimap2) = &self.config.aligner {
-            match self.config.preset {
-                None => {
-                    if self.config.paired_end {
-                        self.config.preset = Some(Preset::Sr)
-                    } else {
-                        self.config.preset = Some(Preset::MapOnt)
-                    }
-                },
-                Some(ref preset) => {
-                    if [Preset::Lr].contains(preset) {
-                        return Err(ScrubbyError::Minimap2PresetNotSupported(preset.to_owned()))
-                    }
-                }
-            }
-        }
-        // Check that a default preset is set with Minigraph
-        if let Some(Aligner::Minigraph) = &self.config.aligner {
-            match self.config.preset {
-                None => {
-                    if self.config.paired_end {
-                        self.config.preset = Some(Preset::Sr)
-                    } else {
-                        self.config.preset = Some(Preset::Lr)
-                    }
-                },
-                Some(ref preset) => {
-                    if ![Preset::Lr, Preset::Sr, Preset::Asm].contains(preset) {
-                        return Err(ScrubbyError::MinigraphPresetNotSupported(preset.to_owned()))
-                    }
-                }
-            }
-        }
-        #[cfg(feature = "mm2")]
-        if let Some(Aligner::Minimap2Rs) = &self.config.aligner {
-            match self.config.preset {
-                None => {
-                    if self.config.paired_end {
-                        self.config.preset = Some(Preset::Sr)
-                    } else {
-                        self.config.preset = Some(Preset::MapOnt)
-                    }
-                },
-                Some(ref preset) => {
-                    if [Preset::Lr].contains(preset) {
-                        return Err(ScrubbyError::Minimap2PresetNotSupported(preset.to_owned()))
+            match &self.config.preset {
+                None => self.config.preset = profile.default_preset(self.config.paired_end),
+                Some(preset) => {
+                    let supported = profile.supported_presets();
+                    if !supported.is_empty() && !supported.contains(preset) {
+                        return Err(profile.preset_not_supported_error(preset));
                     }
                 }
             }
@@ -947,8 +1989,11 @@ impl ScrubbyBuilder {
         Ok(Scrubby {
             input: self.input,
             output: self.output,
+            removed: self.removed,
             read_ids: self.read_ids,
             json: self.json,
+            ndjson: self.ndjson,
+            bundle: self.bundle,
             workdir: self.workdir,
             extract: self.extract,
             keep: self.keep,
@@ -989,15 +2034,91 @@ impl ScrubbyBuilder {
         Ok(Scrubby {
             input: self.input,
             output: self.output,
+            removed: self.removed,
+            read_ids: self.read_ids,
+            json: self.json,
+            ndjson: self.ndjson,
+            bundle: self.bundle,
+            workdir: self.workdir,
+            extract: self.extract,
+            keep: self.keep,
+            threads: self.threads,
+            config: self.config,
+        })
+    }
+    /// Builds the `Scrubby` instance with the low-complexity (DUST) filtering configuration.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Scrubby, ScrubbyError>` - Ok with the constructed Scrubby instance, otherwise an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let scrubby = ScrubbyBuilder::new(...).build_complexity().unwrap();
+    /// ```
+    pub fn build_complexity(mut self) -> Result<Scrubby, ScrubbyError> {
+
+        self.validate_base_config()?;
+
+        self.config.complexity = true;
+
+        Ok(Scrubby {
+            input: self.input,
+            output: self.output,
+            removed: self.removed,
             read_ids: self.read_ids,
             json: self.json,
+            ndjson: self.ndjson,
+            bundle: self.bundle,
             workdir: self.workdir,
             extract: self.extract,
             keep: self.keep,
             threads: self.threads,
             config: self.config,
         })
-    }/// Builds the `Scrubby` instance with the alignment output cleaning configuration.
+    }
+    /// Builds the `Scrubby` instance with the FracMinHash sketch containment configuration.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Scrubby, ScrubbyError>` - Ok with the constructed Scrubby instance, otherwise an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use scrubby::ScrubbyBuilder;
+    ///
+    /// let scrubby = ScrubbyBuilder::new(...).sketch_index("human.sketch.json").build_sketch().unwrap();
+    /// ```
+    pub fn build_sketch(mut self) -> Result<Scrubby, ScrubbyError> {
+
+        self.validate_base_config()?;
+
+        if self.config.sketch_index.is_none() {
+            return Err(ScrubbyError::MissingSketchIndex);
+        }
+
+        self.config.sketch = true;
+
+        Ok(Scrubby {
+            input: self.input,
+            output: self.output,
+            removed: self.removed,
+            read_ids: self.read_ids,
+            json: self.json,
+            ndjson: self.ndjson,
+            bundle: self.bundle,
+            workdir: self.workdir,
+            extract: self.extract,
+            keep: self.keep,
+            threads: self.threads,
+            config: self.config,
+        })
+    }
+    /// Builds the `Scrubby` instance with the alignment output cleaning configuration.
     ///
     /// # Returns
     ///
@@ -1021,8 +2142,11 @@ impl ScrubbyBuilder {
         Ok(Scrubby {
             input: self.input,
             output: self.output,
+            removed: self.removed,
             read_ids: self.read_ids,
             json: self.json,
+            ndjson: self.ndjson,
+            bundle: self.bundle,
             workdir: self.workdir,
             extract: self.extract,
             keep: self.keep,
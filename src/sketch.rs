@@ -0,0 +1,181 @@
+//! FracMinHash k-mer sketch depletion: a middle ground between exact
+//! alignment and full taxonomic classification for fast host removal. A
+//! reference (e.g. a human genome) is reduced once to a small sketch -
+//! canonical k-mers hashed to 64 bits and kept only when `hash < u64::MAX /
+//! scaled` (FracMinHash sub-sampling) - giving a size-proportional summary
+//! with unbiased containment estimates, loaded once into memory instead of
+//! building a full aligner index or a multi-GB taxonomic database. Each read
+//! is then scored by containment (the fraction of its own sketch hashes
+//! found in the reference sketch) against a user threshold.
+
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ScrubbyError;
+use crate::utils::{get_id, parse_fastx_file_with_check};
+
+/// Default k-mer length, matching `sourmash`'s default for DNA sketches.
+pub const DEFAULT_SKETCH_K: u8 = 21;
+/// Default FracMinHash scaling factor (retains ~1/1000 of canonical k-mers).
+pub const DEFAULT_SKETCH_SCALED: u64 = 1000;
+/// Default containment threshold above which a read is flagged for depletion.
+pub const DEFAULT_MIN_CONTAINMENT: f64 = 0.2;
+/// Default minimum number of a read's own sketch hashes required before its
+/// containment score is trusted, so a very short read isn't misclassified
+/// from a single shared hash.
+pub const DEFAULT_MIN_SKETCH_HASHES: usize = 3;
+
+/// A FracMinHash sketch: `k`/`scaled` plus the retained canonical k-mer
+/// hashes, serialized as plain JSON so a reference sketch can be built once
+/// and shipped alongside (or instead of) a conventional aligner/classifier index.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FracMinHashSketch {
+    pub k: u8,
+    pub scaled: u64,
+    pub hashes: Vec<u64>,
+}
+
+impl FracMinHashSketch {
+    /// Builds a sketch from every sequence in `path` (FASTA/FASTQ, optionally compressed).
+    pub fn from_fasta(path: &Path, k: u8, scaled: u64) -> Result<Self, ScrubbyError> {
+        let mut hashes = HashSet::new();
+        if let Some(mut reader) = parse_fastx_file_with_check(path)? {
+            while let Some(record) = reader.next() {
+                hashes.extend(sketch_hashes(&record?.seq(), k, scaled));
+            }
+        }
+
+        let mut hashes: Vec<u64> = hashes.into_iter().collect();
+        hashes.sort_unstable();
+
+        Ok(Self { k, scaled, hashes })
+    }
+
+    /// Loads a sketch previously written by `write_json`.
+    pub fn from_json(path: &Path) -> Result<Self, ScrubbyError> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Writes this sketch as JSON (`k`, `scaled`, sorted `hashes`).
+    pub fn write_json(&self, path: &Path) -> Result<(), ScrubbyError> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Canonicalizes and hashes every overlapping `k`-mer of `seq`, keeping only
+/// those below `u64::MAX / scaled` (the FracMinHash sub-sample cutoff).
+fn sketch_hashes(seq: &[u8], k: u8, scaled: u64) -> HashSet<u64> {
+    let k = k as usize;
+    let mut hashes = HashSet::new();
+    if k == 0 || seq.len() < k {
+        return hashes;
+    }
+
+    let threshold = u64::MAX / scaled.max(1);
+    for window in seq.windows(k) {
+        let Some(canonical) = canonical_kmer(window) else { continue };
+        let hash = hash_kmer(&canonical);
+        if hash < threshold {
+            hashes.insert(hash);
+        }
+    }
+
+    hashes
+}
+
+/// Returns the lexicographically smaller of `seq` and its reverse
+/// complement, or `None` if `seq` contains a base outside `ACGT`
+/// (case-insensitive), so ambiguous/masked k-mers are skipped entirely
+/// rather than hashed inconsistently.
+fn canonical_kmer(seq: &[u8]) -> Option<Vec<u8>> {
+    let mut forward = Vec::with_capacity(seq.len());
+    for &base in seq {
+        match base.to_ascii_uppercase() {
+            b'A' | b'C' | b'G' | b'T' => forward.push(base.to_ascii_uppercase()),
+            _ => return None,
+        }
+    }
+
+    let reverse: Vec<u8> = forward.iter().rev().map(|&b| complement(b)).collect();
+    Some(if forward <= reverse { forward } else { reverse })
+}
+
+fn complement(base: u8) -> u8 {
+    match base {
+        b'A' => b'T',
+        b'T' => b'A',
+        b'C' => b'G',
+        b'G' => b'C',
+        _ => base,
+    }
+}
+
+/// Hashes a canonical k-mer to a 64-bit value via the standard library's
+/// (fixed-seed, so reproducible across runs) `SipHash`-based `DefaultHasher`.
+fn hash_kmer(kmer: &[u8]) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    kmer.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Sketch-containment read filter, mirroring `ComplexityFilter`'s role for
+/// the low-complexity filter: scans read files and returns the set of read
+/// identifiers whose own FracMinHash sketch is contained in a reference
+/// sketch above a configured threshold.
+pub struct SketchFilter {
+    reference: HashSet<u64>,
+    k: u8,
+    scaled: u64,
+    min_containment: f64,
+    min_hashes: usize,
+}
+
+impl SketchFilter {
+    /// Constructs a filter from an already-loaded reference sketch.
+    pub fn new(reference: FracMinHashSketch, min_containment: f64, min_hashes: usize) -> Self {
+        Self {
+            k: reference.k,
+            scaled: reference.scaled,
+            reference: reference.hashes.into_iter().collect(),
+            min_containment,
+            min_hashes,
+        }
+    }
+
+    /// Returns `true` if `seq`'s own sketch is contained in the reference
+    /// sketch at or above `min_containment`, given at least `min_hashes` of
+    /// its own hashes (below that, containment is not computed at all).
+    pub fn is_contained(&self, seq: &[u8]) -> bool {
+        let read_hashes = sketch_hashes(seq, self.k, self.scaled);
+        if read_hashes.len() < self.min_hashes {
+            return false;
+        }
+
+        let shared = read_hashes.iter().filter(|hash| self.reference.contains(hash)).count();
+        (shared as f64 / read_hashes.len() as f64) >= self.min_containment
+    }
+
+    /// Scans the provided input read file(s) and returns the set of read
+    /// identifiers flagged for depletion/extraction via sketch containment,
+    /// so they can be run through the same `clean_reads` path used for
+    /// classifier, aligner and low-complexity sources.
+    pub fn sketch_contained_reads(&self, input: &[PathBuf]) -> Result<HashSet<String>, ScrubbyError> {
+        let mut flagged = HashSet::new();
+        for path in input {
+            if let Some(mut reader) = parse_fastx_file_with_check(path)? {
+                while let Some(record) = reader.next() {
+                    let record = record?;
+                    if self.is_contained(&record.seq()) {
+                        flagged.insert(get_id(record.id())?);
+                    }
+                }
+            }
+        }
+        Ok(flagged)
+    }
+}
@@ -0,0 +1,199 @@
+//! Loads the standard NCBI taxonomy dump (`nodes.dmp`/`names.dmp`) into an
+//! in-memory graph so taxon subtree extraction can walk true parent/child
+//! relationships instead of inferring them from a classifier report's rank
+//! ordering, which breaks on `no rank` clades, strain-level entries, and
+//! reports that don't preserve indentation.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use crate::error::ScrubbyError;
+
+/// In-memory NCBI taxonomy graph parsed from `nodes.dmp` and `names.dmp`.
+#[derive(Debug, Clone, Default)]
+pub struct Taxonomy {
+    parents: HashMap<String, String>,
+    children: HashMap<String, Vec<String>>,
+    ranks: HashMap<String, String>,
+    names: HashMap<String, String>,
+    ids_by_name: HashMap<String, String>,
+}
+
+impl Taxonomy {
+    /// Loads `nodes.dmp` and `names.dmp` from the given NCBI taxonomy dump directory.
+    pub fn from_directory(directory: &Path) -> Result<Self, ScrubbyError> {
+        Self::from_files(&directory.join("nodes.dmp"), &directory.join("names.dmp"))
+    }
+
+    /// Parses `nodes.dmp` (fields separated by `\t|\t`: `tax_id`, `parent_tax_id`,
+    /// `rank`, ...) into a child-to-parent map and a parent-to-children adjacency
+    /// list, and `names.dmp` into a `tax_id`-to-scientific-name map, discarding
+    /// every row whose name class is not `scientific name`.
+    pub fn from_files(nodes: &Path, names: &Path) -> Result<Self, ScrubbyError> {
+        let mut parents = HashMap::new();
+        let mut ranks = HashMap::new();
+        let mut children: HashMap<String, Vec<String>> = HashMap::new();
+
+        for line in BufReader::new(File::open(nodes)?).lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split("\t|\t").collect();
+            if fields.len() < 3 {
+                continue;
+            }
+            let tax_id = fields[0].trim().to_string();
+            let parent_tax_id = fields[1].trim().to_string();
+            let rank = fields[2].trim().to_string();
+
+            if tax_id != parent_tax_id {
+                children.entry(parent_tax_id.clone()).or_default().push(tax_id.clone());
+            }
+            ranks.insert(tax_id.clone(), rank);
+            parents.insert(tax_id, parent_tax_id);
+        }
+
+        let mut scientific_names = HashMap::new();
+        for line in BufReader::new(File::open(names)?).lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split("\t|\t").collect();
+            if fields.len() < 4 {
+                continue;
+            }
+            if fields[3].trim_end_matches("\t|").trim() != "scientific name" {
+                continue;
+            }
+            scientific_names.insert(fields[0].trim().to_string(), fields[1].trim().to_string());
+        }
+
+        let ids_by_name = scientific_names.iter().map(|(id, name)| (name.clone(), id.clone())).collect();
+
+        Ok(Self { parents, children, ranks, names: scientific_names, ids_by_name })
+    }
+
+    /// Returns every tax_id in the subtree rooted at `tax_id`, including `tax_id`
+    /// itself, via a breadth-first walk of the parent-to-children adjacency list.
+    pub fn descendants(&self, tax_id: &str) -> HashSet<String> {
+        let mut seen = HashSet::from([tax_id.to_string()]);
+        let mut queue = VecDeque::from([tax_id.to_string()]);
+
+        while let Some(current) = queue.pop_front() {
+            if let Some(children) = self.children.get(&current) {
+                for child in children {
+                    if seen.insert(child.clone()) {
+                        queue.push_back(child.clone());
+                    }
+                }
+            }
+        }
+
+        seen
+    }
+
+    /// Resolves the scientific name for `tax_id`, if known.
+    pub fn name(&self, tax_id: &str) -> Option<&str> {
+        self.names.get(tax_id).map(String::as_str)
+    }
+
+    /// Resolves `id_or_name` to a tax_id: if it is already a known tax_id it
+    /// is returned as-is, otherwise it is looked up as a scientific name.
+    pub fn resolve(&self, id_or_name: &str) -> Option<&str> {
+        if let Some((tax_id, _)) = self.parents.get_key_value(id_or_name) {
+            return Some(tax_id.as_str());
+        }
+        self.ids_by_name.get(id_or_name).map(String::as_str)
+    }
+
+    /// Resolves the rank for `tax_id`, if known.
+    pub fn rank(&self, tax_id: &str) -> Option<&str> {
+        self.ranks.get(tax_id).map(String::as_str)
+    }
+
+    /// Resolves the parent tax_id for `tax_id`, if known.
+    pub fn parent(&self, tax_id: &str) -> Option<&str> {
+        self.parents.get(tax_id).map(String::as_str)
+    }
+
+    /// Returns the root-to-node lineage for `tax_id`: `tax_id` itself followed
+    /// by every ancestor up to (and including) the root, in root-first order.
+    /// If `tax_id` is not present in the graph, the lineage is just `[tax_id]`.
+    pub fn lineage(&self, tax_id: &str) -> Vec<String> {
+        let mut lineage = vec![tax_id.to_string()];
+        let mut current = tax_id.to_string();
+
+        while let Some(parent) = self.parents.get(&current) {
+            if *parent == current {
+                break;
+            }
+            lineage.push(parent.clone());
+            current = parent.clone();
+        }
+
+        lineage.reverse();
+        lineage
+    }
+}
+
+/// Resolves `kraken_taxa`/`kraken_taxa_direct` straight against `taxonomy`,
+/// without reading a classifier report at all: each entry (a tax_id or
+/// scientific name) is resolved via [`Taxonomy::resolve`], `kraken_taxa`
+/// contributing its whole subtree via [`Taxonomy::descendants`] and
+/// `kraken_taxa_direct` contributing just the resolved node. Unresolvable
+/// entries are skipped rather than erroring, since a name absent from this
+/// particular taxonomy dump is not necessarily a user mistake.
+pub fn get_taxids_from_taxonomy(
+    taxonomy: &Taxonomy,
+    kraken_taxa: &[String],
+    kraken_taxa_direct: &[String],
+) -> HashSet<String> {
+    let mut taxids = HashSet::new();
+
+    for entry in kraken_taxa {
+        match taxonomy.resolve(entry.trim()) {
+            Some(tax_id) => taxids.extend(taxonomy.descendants(tax_id)),
+            None => log::warn!("Could not resolve taxon '{entry}' against the loaded taxonomy"),
+        }
+    }
+    for entry in kraken_taxa_direct {
+        match taxonomy.resolve(entry.trim()) {
+            Some(tax_id) => { taxids.insert(tax_id.to_string()); },
+            None => log::warn!("Could not resolve taxon '{entry}' against the loaded taxonomy"),
+        }
+    }
+
+    taxids
+}
+
+/// Annotates a stream of bare taxids (such as those produced by
+/// `get_taxids_from_report`) with their scientific name and rank, writing a
+/// `taxon_id\ttaxon_name\ttaxon_rank` TSV. Blank lines and FASTA header lines
+/// (starting with `>`) are copied through unchanged, so a taxid list
+/// interleaved with the headers it was extracted from can be annotated in
+/// place rather than needing to be stripped down first.
+pub fn annotate_taxids<R: BufRead, W: Write>(
+    taxonomy: &Taxonomy,
+    reader: R,
+    mut writer: W,
+    header: bool,
+) -> Result<(), ScrubbyError> {
+    if header {
+        writeln!(writer, "taxon_id\ttaxon_name\ttaxon_rank")?;
+    }
+
+    for line in reader.lines() {
+        let line = line?;
+        let taxon_id = line.trim();
+
+        if taxon_id.is_empty() || taxon_id.starts_with('>') {
+            writeln!(writer, "{line}")?;
+            continue;
+        }
+
+        let taxon_name = taxonomy.name(taxon_id).unwrap_or("");
+        let taxon_rank = taxonomy.rank(taxon_id).unwrap_or("");
+
+        writeln!(writer, "{taxon_id}\t{taxon_name}\t{taxon_rank}")?;
+    }
+
+    Ok(())
+}
@@ -1,9 +1,33 @@
 use std::path::PathBuf;
+use std::str::FromStr;
+use camino::Utf8PathBuf;
 use clap::{crate_version, Args, Parser, Subcommand};
 
 use crate::prelude::*;
 use crate::error::ScrubbyError;
 use crate::utils::{ReadDifference, ReadDifferenceBuilder};
+use crate::classifier::ClassifierOutputFormat;
+
+/// Checks that each of `paths` exists, without canonicalizing it.
+///
+/// Used to validate required input files at the CLI boundary, where paths
+/// are still UTF-8 (`Utf8PathBuf`) and can be embedded verbatim in a
+/// `ScrubbyError::PathDoesNotExist` and in JSON summaries. Canonicalization
+/// is deliberately not performed here, so a symlinked path given on the
+/// command line is preserved rather than silently resolved.
+fn require_existing(paths: &[Utf8PathBuf]) -> Result<(), ScrubbyError> {
+    for path in paths {
+        if !path.exists() {
+            return Err(ScrubbyError::PathDoesNotExist(path.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Converts a `Vec<Utf8PathBuf>` into the `Vec<PathBuf>` used internally by `ScrubbyBuilder`.
+fn into_path_bufs(paths: Vec<Utf8PathBuf>) -> Vec<PathBuf> {
+    paths.into_iter().map(Utf8PathBuf::into_std_path_buf).collect()
+}
 
 #[derive(Debug, Parser)]
 #[command(
@@ -29,6 +53,15 @@ pub struct App {
     #[arg(short, long)]
     pub log_file: Option<PathBuf>,
 
+    /// Write a structured NDJSON run-log to this path alongside the human-readable log
+    ///
+    /// Each line is one JSON object (timestamp, level, subcommand, and
+    /// run-relevant fields such as reads processed/depleted or, for `nn
+    /// --train`, epoch and loss) so automation can consume scrubby's
+    /// progress and results without scraping formatted text.
+    #[arg(long)]
+    pub json_log: Option<PathBuf>,
+
     #[clap(subcommand)]
     pub command: Commands,
 }
@@ -42,12 +75,45 @@ pub enum Commands {
     Classifier(ClassifierArgs),
     /// Deplete or extract reads from aligner output with additional filters (SAM/BAM/PAF/GAF).
     Alignment(AlignmentArgs),
+    /// Deplete or extract low-complexity reads using a symmetric-DUST/entropy filter.
+    Complexity(ComplexityArgs),
+    /// Deplete or extract reads via FracMinHash sketch containment against a reference.
+    Sketch(SketchArgs),
+    /// Build a FracMinHash reference sketch from a FASTA file for use with `sketch`.
+    SketchBuild(SketchBuildArgs),
+    /// Run the depletion pipeline over many samples described by a sample sheet.
+    Batch(BatchArgs),
+    /// Compare depletion configurations (tool, preset, thresholds) on fixed inputs described by a workload file.
+    Benchmark(BenchmarkArgs),
+    /// Deplete or extract reads from an aligned BAM/CRAM/SAM using a taxid assignment.
+    Bam(BamFilterArgs),
+    /// Annotate a list of taxids with their scientific name and rank.
+    Taxonomy(TaxonomyArgs),
     /// List available indices and download files for aligners and classfiers.
     Download(DownloadArgs),
     /// Get read counts and identifiers of the difference between input and output read files.
     Diff(DiffArgs),
+    /// Aggregate many `--json` summary reports into a cohort-level summary.
+    Merge(MergeArgs),
+    /// Unpack a `--bundle` tar.gz and print the contained summary report.
+    Restore(RestoreArgs),
     /// Train and test the neural network for identity prediction.
-    Nn(NeuralNetArgs)
+    Nn(NeuralNetArgs),
+    /// Generate a JSON Schema for the `--config` run-settings file format.
+    Config(ConfigArgs)
+}
+
+/// Command-line arguments for the `config` subcommand.
+#[derive(Args, Debug, Clone)]
+pub struct ConfigArgs {
+    /// Print the JSON Schema for the `--config` file format and exit
+    ///
+    /// The schema describes every field accepted by `scrubby reads --config`,
+    /// so editors can offer validation and autocomplete for hand-written
+    /// config files. Save the output to a file and reference it from the
+    /// config file's `"$schema"` key.
+    #[arg(long)]
+    pub emit_schema: bool,
 }
 
 /// Command-line arguments for the cleaning operation
@@ -57,29 +123,59 @@ pub enum Commands {
 /// files, aligners, classifiers, and various other parameters.
 #[derive(Args, Debug)]
 pub struct ReadsArgs {
-    /// Input read files (optional .gz)
+    /// Input read files (.gz | .xz | .bz)
     ///
-    /// One or two input read files, can be in gzipped format. This parameter is required and multiple file
-    /// can be specified (1 for long reads or 2 for paired-end short reads) either consecutively or using 
-    /// multiple input arguments, for example: '-i R1.fq.gz -i R2.fq.gz' or '-i R1.fq.gz R2.fq.gz'
+    /// One or two input read files, can be compressed (.gz, .xz, .bz), detected
+    /// from the leading magic bytes regardless of extension. This parameter is
+    /// required and multiple file can be specified (1 for long reads or 2 for
+    /// paired-end short reads) either consecutively or using multiple input
+    /// arguments, for example: '-i R1.fq.gz -i R2.fq.gz' or '-i R1.fq.gz R2.fq.gz'
     #[arg(short, long, num_args(0..))]
-    input: Vec<PathBuf>,
-    /// Output read files (optional .gz)
+    input: Vec<Utf8PathBuf>,
+    /// Output read files (.gz | .xz | .bz)
     ///
-    /// One or two output read files, can be in gzipped format. This parameter is required and multiple 
-    /// files can be specified either consecutively or using multiple output arguments for example:
-    /// '-o R1.fq.gz -o R2.fq.gz' or '-o R1.fq.gz R2.fq.gz'. Output must be directed to files if 
+    /// One or two output read files, can be compressed (.gz, .xz, .bz), inferred
+    /// from the file extension unless overridden with '--compression-format'.
+    /// This parameter is required and multiple files can be specified either
+    /// consecutively or using multiple output arguments for example:
+    /// '-o R1.fq.gz -o R2.fq.gz' or '-o R1.fq.gz R2.fq.gz'. Output must be directed to files if
     /// '--json' or '--read-ids' arguments are provided.
     #[arg(short, long, num_args(0..))]
-    output: Vec<PathBuf>,
+    output: Vec<Utf8PathBuf>,
+    /// Removed read output files (.gz | .xz | .bz)
+    ///
+    /// One or two files to additionally write the removed (or, with '--extract',
+    /// non-extracted) reads to, alongside the retained '--output' files. Must
+    /// match the number of '--output' files and respects the same paired layout
+    /// and compression inference, for example: '--removed r1.rm.fq.gz r2.rm.fq.gz'.
+    #[arg(long, num_args(0..))]
+    removed: Vec<Utf8PathBuf>,
     /// Reference index for aligner or classifier
     ///
-    /// Depending on whether --aligner or --classifier is chosen, the index is an 
-    /// alignment index for 'bowtie2' (index), 'minimap2' and 'strobealign' 
+    /// Depending on whether --aligner or --classifier is chosen, the index is an
+    /// alignment index for 'bowtie2' (index), 'minimap2' and 'strobealign'
     /// (index or FASTA) and 'minigraph' (graph index or FASTA) or a classifier
     /// index directory for Kraken2 (index) and Metabuli (index).
     #[arg(long, short='I')]
     index: PathBuf,
+    /// Additional reference indices to chain after '--index'
+    ///
+    /// Each additional index is run against the same configured aligner/
+    /// classifier, and its resolved read IDs are unioned with '--index's set
+    /// in memory before the single final output pass, so depleting against a
+    /// sequence of reference databases (e.g. host, then a separate
+    /// contaminant panel) costs no intermediate FASTX file beyond the one
+    /// '--index' itself already produces.
+    #[arg(long, num_args(0..))]
+    additional_index: Vec<PathBuf>,
+    /// Number of '--additional-index' entries resolved concurrently
+    ///
+    /// Defaults to 1 (resolved one at a time, the original behavior).
+    /// Raising it bounds how many extra aligner/classifier invocations run in
+    /// parallel, trading peak memory/IO for wall-clock time on a run with
+    /// several independent reference indices.
+    #[arg(long, default_value_t = 1)]
+    index_concurrency: usize,
     /// Aligner to use, default is 'bowtie2' (paired) or 'minimap2' (single)
     ///
     /// Aligner to be used for the cleaning process. Default for paired-end short 
@@ -115,6 +211,41 @@ pub struct ReadsArgs {
     /// only reads directly classified as 'Homo sapiens' at species level.
     #[arg(long, short='D', num_args(0..))]
     taxa_direct: Vec<String>,
+    /// NCBI taxonomy dump directory (`nodes.dmp`/`names.dmp`) for true subtree resolution
+    ///
+    /// When set, '--taxa' descendants are resolved by walking this taxonomy graph
+    /// instead of inferring them from the classifier report's rank ordering, so
+    /// extraction is correct regardless of `no rank` clades, strain-level entries,
+    /// or reports that don't preserve indentation.
+    #[arg(long)]
+    taxonomy_directory: Option<PathBuf>,
+    /// Minimum cumulative reads a matched taxon must have to be depleted
+    ///
+    /// Taxa selected via '--taxa'/'--taxa-direct' whose report `reads` column falls
+    /// below this value are ignored, suppressing likely false-positive depletion
+    /// from low-confidence classifications.
+    #[arg(long)]
+    min_reads: Option<u64>,
+    /// Minimum fraction of total reads a matched taxon must have to be depleted
+    #[arg(long)]
+    min_fraction: Option<f64>,
+    /// Taxonomic rank below which sub-level reads are rolled up into their nearest ancestor
+    ///
+    /// Sub-level rows below this rank (e.g. 'species' rows when this is
+    /// 'genus') have their reads attributed to the nearest enclosing ancestor
+    /// at or above the given rank instead of their own taxid, before
+    /// '--min-reads'/'--min-fraction' are applied. Reduces spurious
+    /// single-read species/strain-level noise from triggering depletion.
+    #[arg(long)]
+    prune_rank: Option<String>,
+    /// Taxon audit report output file (.tsv)
+    ///
+    /// Writes one row per depleted taxon (tax_id, tax_name, tax_rank, parent,
+    /// reads_direct), so the exact taxa and read counts driving the depletion
+    /// can be reviewed. Resolves tax_name/tax_rank from '--taxonomy-directory'
+    /// when set.
+    #[arg(long)]
+    taxon_report: Option<PathBuf>,
     /// Additional aligner arguments
     ///
     /// Aligner arguments must be a quoted string e.g. '-m 40'
@@ -137,24 +268,203 @@ pub struct ReadsArgs {
     /// cleaning process.
     #[arg(short, long)]
     json: Option<PathBuf>,
+    /// Streaming progress and summary records (.ndjson)
+    ///
+    /// Path to a newline-delimited JSON file that receives a 'progress' record
+    /// every 100,000 reads processed, followed by a final 'summary' record
+    /// equivalent to '--json'. Use '-' to write to stdout.
+    #[arg(long)]
+    ndjson: Option<PathBuf>,
+    /// Reproducible run bundle (.tar.gz)
+    ///
+    /// Path to a gzip-compressed tar archive combining the '--json' report,
+    /// the '--read-ids' list, and the effective settings, so a collaborator
+    /// can inspect or re-apply this exact run from one shareable file.
+    #[arg(long)]
+    bundle: Option<PathBuf>,
     /// Optional working directory
     ///
-    /// Working directory for temporary files. If not provided, the system 
+    /// Working directory for temporary files. If not provided, the system
     /// temporary directory will be used.
     #[arg(short, long)]
     workdir: Option<PathBuf>,
     /// Read identifier file (.tsv)
     ///
-    /// Path to a TSV file containing read identifiers. This file can 
+    /// Path to a TSV file containing read identifiers. This file can
     /// be used to identify reads that were depleted or extracted.
     #[arg(short, long)]
     read_ids: Option<PathBuf>,
     /// Read extraction instead of depletion
     ///
-    /// Enable this option to extract reads matching the specified criteria instead 
+    /// Enable this option to extract reads matching the specified criteria instead
     /// of depleting them.
     #[arg(short, long)]
     extract: bool,
+    /// Record a per-read removal audit trail
+    ///
+    /// When set, tracks which stage and reference database flagged each removed
+    /// read, written to '--audit-tsv' and/or '--audit-json'.
+    #[arg(long)]
+    audit: bool,
+    /// Audit trail output file (.tsv)
+    #[arg(long)]
+    audit_tsv: Option<PathBuf>,
+    /// Audit trail output file (.json)
+    #[arg(long)]
+    audit_json: Option<PathBuf>,
+    /// Structured depletion statistics report (.tsv)
+    ///
+    /// Writes per-input-file read counts (seen/removed/retained) and, for
+    /// classifier-based depletion, removed-read counts per resolved taxid.
+    #[arg(long)]
+    stats_tsv: Option<PathBuf>,
+    /// Structured depletion statistics report (.json)
+    #[arg(long)]
+    stats_json: Option<PathBuf>,
+    /// Minimum distinct k-mers for a `KrakenUniq` taxon to be retained
+    ///
+    /// Only used with '--classifier krakenuniq'. Taxa backed by fewer than this many
+    /// distinct k-mers (the HyperLogLog-derived 'kmers' column of the report) are
+    /// ignored even if selected via '--taxa'/'--taxa-direct', suppressing likely
+    /// false-positive depletion from low-confidence classifications.
+    #[arg(long, default_value = "0")]
+    min_unique_kmers: u64,
+    /// Minimum Metabuli `dna_score` for a read to be retained
+    ///
+    /// Only used with '--classifier metabuli'. Reads assigned to a selected taxon
+    /// whose 'dna_score' falls below this threshold are ignored, mirroring how
+    /// '--min-mapq' filters low-confidence alignments.
+    #[arg(long, default_value = "0.0")]
+    metabuli_min_score: f64,
+    /// Krona text report of depleted taxon lineages (.txt)
+    ///
+    /// Only used with '--classifier kraken2'. Writes one line per depleted taxon
+    /// giving its directly-assigned read count followed by the tab-separated
+    /// root-to-taxon lineage, suitable for 'ktImportText'.
+    #[arg(long)]
+    krona: Option<PathBuf>,
+    /// Strip trailing paired-end orientation suffixes from read IDs before matching
+    ///
+    /// When set, normalizes both depletion read IDs and FASTQ record IDs by
+    /// stripping a trailing orientation suffix before testing set membership,
+    /// so differently-suffixed IDs from paired FASTQs (or reads pulled from
+    /// SAM/BAM) still match. Pass with no value to use the default pattern
+    /// (covers '/1', '/2', '.1', '.2' and Illumina/Casava ' 1:N:0:...'
+    /// comments), or supply a custom regex.
+    #[arg(long, num_args(0..=1), default_missing_value = crate::readid::DEFAULT_SUFFIX_PATTERN)]
+    strip_suffix: Option<String>,
+    /// Output compression algorithm, overrides extension-based inference
+    ///
+    /// Accepts a short letter or full extension alias, case-insensitively:
+    /// 'g'/'gz'/'gzip', 'b'/'bz'/'bz2'/'bzip'/'bzip2', 'l'/'xz'/'lzma',
+    /// 'z'/'zst'/'zstd'/'zstandard', '4'/'lz4', 'u'/'none'/'uncompressed'.
+    #[arg(long, value_parser = CompressionAlgorithm::from_str)]
+    compression_format: Option<CompressionAlgorithm>,
+    /// Output compression level, defaults to the algorithm's own default level
+    #[arg(long)]
+    compression_level: Option<u32>,
+    /// Number of threads to use for compressing output
+    ///
+    /// When set above one and the output format is gzip, writes a multithreaded
+    /// BGZF stream (64 KiB blocks) instead of the single-threaded encoder.
+    /// BGZF output is also readable by any plain gzip decoder and is
+    /// additionally bgzip-index compatible. Ignored for bzip/lzma/zstd/lz4 output.
+    #[arg(long)]
+    compression_threads: Option<usize>,
+    /// Parse aligner output in-process with `rust_htslib` instead of piping it
+    /// through `samtools view`
+    ///
+    /// Removes the runtime dependency on `samtools` for the `minimap2`,
+    /// `bowtie2` and `strobealign` aligners. Requires the crate to be compiled
+    /// with the `htslib` feature; ignored otherwise.
+    #[arg(long)]
+    native_bam: bool,
+    /// Combine an aligner and a classifier, merging their mapped read ID sets
+    ///
+    /// When set, allows both '--aligner' and '--classifier' to be configured
+    /// together: reads are depleted/extracted using the union (either method
+    /// flags the read), intersection (both methods must flag the read), or
+    /// majority (more than half of the configured backends must flag the
+    /// read) of their mapped read ID sets.
+    #[arg(long)]
+    combine: Option<CombineMode>,
+    /// Treat a single '--input' file as interleaved paired-end FASTQ
+    ///
+    /// When set, '--input' must be exactly one file containing alternating
+    /// R1/R2 records, which is split into a paired stream before cleaning
+    /// runs. '--output' must then be given as two files (R1/R2).
+    #[arg(long)]
+    interleaved: bool,
+    /// Load run settings from a TOML or JSONC config file
+    ///
+    /// Reads a reusable depletion profile (aligner/classifier, taxa, extra
+    /// aligner/classifier arguments, preset, extract) from a `*.toml` file,
+    /// or otherwise a JSON file that may contain `//` and `/* */` comments
+    /// and an optional `"$schema"` key. If a `scrubby.toml` is also found in
+    /// the current directory or one of its ancestors, it is used as a lower-
+    /// precedence base layer under this file. Settings given directly on the
+    /// command line take precedence over both. Run `scrubby config
+    /// --emit-schema` to generate a schema for editor validation/autocomplete.
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Mean-quality sliding-window trim from both read ends, e.g. '--trim-quality 20'
+    ///
+    /// Runs before depletion. Each end is trimmed back while the mean quality
+    /// of a '--trim-window'-sized window anchored there stays below this
+    /// cutoff. Reads left shorter than '--min-read-length' are dropped.
+    #[arg(long)]
+    trim_quality: Option<u8>,
+    /// 3' adapter sequence trimmed from each read when found
+    ///
+    /// Matches either the full adapter sequence inside the read or a partial
+    /// prefix of it overlapping the read's 3' end, the usual case when the
+    /// sequenced insert is shorter than the read length.
+    #[arg(long)]
+    trim_adapter: Option<String>,
+    /// Sliding window size (bases) for '--trim-quality'
+    #[arg(long, default_value_t = crate::preprocess::DEFAULT_TRIM_WINDOW)]
+    trim_window: usize,
+    /// Minimum read length retained after '--trim-quality'/'--trim-adapter'
+    ///
+    /// Reads (or, for pairs, either mate) shorter than this after trimming
+    /// are dropped before depletion runs.
+    #[arg(long, default_value_t = crate::preprocess::DEFAULT_MIN_READ_LENGTH)]
+    min_read_length: usize,
+    /// Merge overlapping paired-end reads into a single consensus read
+    ///
+    /// Runs after '--trim-quality'/'--trim-adapter'. Since merging collapses
+    /// R1/R2 into one sequence, '--input' must be exactly two files (R1/R2)
+    /// and '--output' exactly one file, which receives the merged reads and,
+    /// unless '--exclude-unmerged' is set, the (trimmed) R1 mate of pairs
+    /// that failed to merge.
+    #[arg(long)]
+    merge_pairs: bool,
+    /// Drop pairs that fail to merge, instead of keeping their R1 mate
+    ///
+    /// Only used with '--merge-pairs'.
+    #[arg(long)]
+    exclude_unmerged: bool,
+    /// Minimum overlap (bases) required to merge a read pair
+    ///
+    /// Only used with '--merge-pairs'.
+    #[arg(long, default_value_t = crate::preprocess::DEFAULT_MIN_MERGE_OVERLAP)]
+    min_merge_overlap: usize,
+    /// Resume an interrupted run, skipping aligner/classifier re-invocation if
+    /// its checkpointed result in '--workdir' is still valid
+    ///
+    /// Requires '--workdir', where the checkpoint and cached read-ID set are
+    /// written. A later run is only skipped if its resolved configuration
+    /// (index, args, input, paired/single) matches exactly.
+    #[arg(long)]
+    resume: bool,
+    /// Record tool versions and input/database digests in the JSON summary
+    ///
+    /// Populates '--json's "provenance" block with the parsed `--version`
+    /// output of the aligner/classifier invoked and a SHA-256 digest of every
+    /// input file and the reference database/index path. Off by default
+    /// since digesting a large reference database is not free.
+    #[arg(long)]
+    provenance: bool,
 }
 impl ReadsArgs {
     /// Validates the provided arguments and builds a 'Scrubby' instance.
@@ -176,26 +486,99 @@ impl ReadsArgs {
     /// ```
     pub fn validate_and_build(self) -> Result<Scrubby, ScrubbyError> {
 
+        require_existing(&self.input)?;
+
         let command = std::env::args().collect::<Vec<String>>().join(" ");
-        
+
+        // Layer settings lowest-to-highest: a discovered workspace
+        // `scrubby.toml` first, then the explicit `--config` file on top of
+        // it (if both are given, `--config` wins field-by-field). CLI flags
+        // are applied on top of the merged result below, so they always win.
+        let workspace_settings = std::env::current_dir().ok()
+            .and_then(|dir| ScrubbySettings::discover_workspace_config(&dir))
+            .map(|path| ScrubbySettings::from_config_file(&path))
+            .transpose()?;
+        let config_settings = self.config.as_deref()
+            .map(ScrubbySettings::from_config_file)
+            .transpose()?;
+        let settings = match (config_settings, workspace_settings) {
+            (Some(config), Some(workspace)) => Some(config.merge(workspace)),
+            (Some(config), None) => Some(config),
+            (None, Some(workspace)) => Some(workspace),
+            (None, None) => None,
+        };
+
+        let aligner = self.aligner.or_else(|| settings.as_ref().and_then(|s| s.aligner.clone()));
+        let classifier = self.classifier.or_else(|| settings.as_ref().and_then(|s| s.classifier.clone()));
+        let preset = self.preset.or_else(|| settings.as_ref().and_then(|s| s.preset.clone()));
+        let aligner_args = self.aligner_args.or_else(|| settings.as_ref().and_then(|s| s.aligner_args.clone()));
+        let classifier_args = self.classifier_args.or_else(|| settings.as_ref().and_then(|s| s.classifier_args.clone()));
+        let compression_format = self.compression_format.or_else(|| settings.as_ref().and_then(|s| s.compression_format));
+        let compression_level = self.compression_level.or_else(|| settings.as_ref().and_then(|s| s.compression_level));
+        let taxa = if self.taxa.is_empty() {
+            settings.as_ref().map(|s| s.taxa.clone()).unwrap_or_default()
+        } else {
+            self.taxa
+        };
+        let taxa_direct = if self.taxa_direct.is_empty() {
+            settings.as_ref().map(|s| s.taxa_direct.clone()).unwrap_or_default()
+        } else {
+            self.taxa_direct
+        };
+        let extract = self.extract || settings.as_ref().is_some_and(|s| s.extract);
+
         let builder = ScrubbyBuilder::new(
-            self.input, 
-            self.output
+            into_path_bufs(self.input),
+            into_path_bufs(self.output)
         )
             .command(command)
             .json(self.json)
+            .ndjson(self.ndjson)
+            .bundle(self.bundle)
             .workdir(self.workdir)
             .read_ids(self.read_ids)
-            .extract(self.extract)
+            .extract(extract)
             .threads(self.threads)
             .index(self.index)
-            .aligner(self.aligner)
-            .classifier(self.classifier)
-            .taxa(self.taxa)
-            .taxa_direct(self.taxa_direct)
-            .classifier_args(self.classifier_args)
-            .aligner_args(self.aligner_args)
-            .preset(self.preset);
+            .aligner(aligner)
+            .classifier(classifier)
+            .taxa(taxa)
+            .taxa_direct(taxa_direct)
+            .taxonomy_directory(self.taxonomy_directory)
+            .min_reads(self.min_reads)
+            .min_fraction(self.min_fraction)
+            .prune_rank(self.prune_rank)
+            .taxon_report(self.taxon_report)
+            .classifier_args(classifier_args)
+            .aligner_args(aligner_args)
+            .preset(preset)
+            .audit(self.audit)
+            .audit_tsv(self.audit_tsv)
+            .audit_json(self.audit_json)
+            .stats_tsv(self.stats_tsv)
+            .stats_json(self.stats_json)
+            .combine(self.combine)
+            .min_unique_kmers(self.min_unique_kmers)
+            .metabuli_min_score(self.metabuli_min_score)
+            .krona(self.krona)
+            .strip_suffix(self.strip_suffix)
+            .removed(into_path_bufs(self.removed))
+            .compression_format(compression_format)
+            .compression_level(compression_level)
+            .compression_threads(self.compression_threads)
+            .native_bam(self.native_bam)
+            .interleaved(self.interleaved)
+            .trim_quality(self.trim_quality)
+            .trim_adapter(self.trim_adapter)
+            .preprocess_window(self.trim_window)
+            .min_read_length(self.min_read_length)
+            .merge_pairs(self.merge_pairs)
+            .exclude_unmerged(self.exclude_unmerged)
+            .min_merge_overlap(self.min_merge_overlap)
+            .resume(self.resume)
+            .provenance(self.provenance)
+            .additional_indices(self.additional_index)
+            .index_concurrency(self.index_concurrency);
 
         let scrubby = builder.build()?;
 
@@ -203,26 +586,303 @@ impl ReadsArgs {
     }
 }
 
+/// Command-line arguments for the `batch` subcommand
+///
+/// Runs the `reads` depletion pipeline over every sample described by a
+/// sample sheet instead of a single `-i/-o` pair, so a collaborator does not
+/// have to script a loop over `scrubby reads` for a multi-sample run.
+#[derive(Args, Debug)]
+pub struct BatchArgs {
+    /// Sample sheet (.csv | .tsv)
+    ///
+    /// Must have a header row with columns 'sample', 'fastq_1' and optionally
+    /// 'run'/'fastq_2'. 'fastq_2' pairs short reads; 'run' distinguishes
+    /// multiple sequencing runs of the same sample, combinable with
+    /// '--merge-runs'. Delimiter is inferred from the file extension ('.tsv'
+    /// is tab-delimited, anything else comma-delimited).
+    #[arg(short, long)]
+    sheet: Utf8PathBuf,
+    /// Output directory for per-sample read and '--json' report files
+    #[arg(short, long)]
+    outdir: PathBuf,
+    /// Combine multiple sequencing runs of the same sample before depletion
+    ///
+    /// Concatenates every sample sheet row sharing a 'sample' value into a
+    /// single input pair before running the pipeline, instead of treating
+    /// each row as its own job.
+    #[arg(long)]
+    merge_runs: bool,
+    /// Maximum number of samples to process concurrently
+    #[arg(long, default_value_t = 1)]
+    parallel: usize,
+    /// Resume an interrupted run, skipping samples already `Succeeded` in 'outdir/queue.json'
+    #[arg(long)]
+    resume: bool,
+    /// Reference index for aligner or classifier, applied to every sample
+    #[arg(long, short = 'I')]
+    index: PathBuf,
+    /// Aligner to use for every sample, default is 'bowtie2' (paired) or 'minimap2' (single)
+    #[arg(long, short)]
+    aligner: Option<Aligner>,
+    /// Minimap2 or minigraph preset to use for every sample
+    #[arg(long, short)]
+    preset: Option<Preset>,
+    /// Classifier to use for every sample
+    #[arg(long, short)]
+    classifier: Option<Classifier>,
+    /// Taxa and all sub-taxa to deplete using classifiers, applied to every sample
+    #[arg(long, short = 'T', num_args(0..))]
+    taxa: Vec<String>,
+    /// Taxa to deplete directly using classifiers, applied to every sample
+    #[arg(long, short = 'D', num_args(0..))]
+    taxa_direct: Vec<String>,
+    /// Read extraction instead of depletion, applied to every sample
+    #[arg(long, short)]
+    extract: bool,
+    /// Number of threads to use for aligner and classifier, per sample
+    #[arg(short, long, default_value = "4")]
+    threads: usize,
+    /// Output compression format override, applied to every sample
+    #[arg(long)]
+    compression_format: Option<CompressionAlgorithm>,
+    /// Output compression level override, applied to every sample
+    #[arg(long)]
+    compression_level: Option<u32>,
+    /// Number of threads to use for compressing output, per sample
+    #[arg(long)]
+    compression_threads: Option<usize>,
+    /// Aggregated batch summary output file (.json), keyed by sample
+    #[arg(short, long)]
+    json: Option<PathBuf>,
+}
+impl BatchArgs {
+    /// Validates the sample sheet and shared options, runs the pipeline over
+    /// every sample, and writes the aggregated `--json` summary.
+    pub fn validate_and_build(self) -> Result<BatchReport, ScrubbyError> {
+        require_existing(std::slice::from_ref(&self.sheet))?;
+        let sheet = self.sheet.into_std_path_buf();
+
+        let options = BatchOptions {
+            index: self.index,
+            aligner: self.aligner,
+            classifier: self.classifier,
+            preset: self.preset,
+            taxa: self.taxa,
+            taxa_direct: self.taxa_direct,
+            extract: self.extract,
+            threads: self.threads,
+            compression_format: self.compression_format,
+            compression_level: self.compression_level,
+            compression_threads: self.compression_threads,
+            merge_runs: self.merge_runs,
+            parallel: self.parallel,
+            resume: self.resume,
+        };
+
+        let report = crate::batch::run_batch(&sheet, &self.outdir, options)?;
+
+        if let Some(json) = &self.json {
+            report.write_json(json)?;
+        }
+
+        Ok(report)
+    }
+}
+
+/// Command-line arguments for the `benchmark` subcommand
+///
+/// Runs a set of named depletion configurations described by a declarative
+/// workload file (JSON), so a maintainer can compare e.g. Kraken2 vs
+/// minimap2, or a preset/thread/threshold sweep, without scripting `scrubby
+/// reads` in a loop and timing it externally. Each run in the workload is
+/// independent (its own input, index, tool, thresholds).
+#[derive(Args, Debug)]
+pub struct BenchmarkArgs {
+    /// Workload file (.json) describing the runs to benchmark
+    ///
+    /// A `{"runs": [...]}` object; each run has 'name', 'input', 'index', and
+    /// optionally 'aligner'/'classifier', 'preset', 'taxa'/'taxa_direct',
+    /// 'threads', 'min_query_length'/'min_query_coverage'/'min_mapq', and 'extract'.
+    #[arg(short, long)]
+    workload: Utf8PathBuf,
+    /// Output directory for per-run depleted reads and '--json' report files
+    #[arg(short, long)]
+    outdir: PathBuf,
+    /// Aggregated results table output file (.tsv)
+    #[arg(short, long)]
+    tsv: Option<PathBuf>,
+    /// Aggregated results table output file (.json), keyed by run name
+    #[arg(short, long)]
+    json: Option<PathBuf>,
+    /// Append-only run history (.jsonl), one timestamped line per run
+    ///
+    /// Unlike '--json'/'--tsv', which are overwritten with this invocation's
+    /// results, this file only grows: point the same path at every benchmark
+    /// invocation across commits/machines to build a history that can be
+    /// diffed for regressions.
+    #[arg(long)]
+    history: Option<PathBuf>,
+}
+impl BenchmarkArgs {
+    /// Validates the workload file, runs every benchmark in turn, and writes
+    /// the aggregated `--tsv`/`--json` results table and `--history` log.
+    pub fn validate_and_build(self) -> Result<BenchmarkReport, ScrubbyError> {
+        require_existing(std::slice::from_ref(&self.workload))?;
+
+        let workload = BenchmarkWorkload::from_json(self.workload.as_std_path())?;
+        let report = crate::benchmark::run_benchmark(&workload, &self.outdir)?;
+
+        if let Some(tsv) = &self.tsv {
+            report.write_tsv(tsv)?;
+        }
+        if let Some(json) = &self.json {
+            report.write_json(json)?;
+        }
+        if let Some(history) = &self.history {
+            report.append_jsonl(history)?;
+        }
+
+        Ok(report)
+    }
+}
+
+
+#[derive(Args, Debug)]
+pub struct BamFilterArgs {
+    /// Alignment file in BAM/CRAM/SAM format, if compiled with 'htslib' feature
+    ///
+    /// Allows '-' to read an uncompressed BAM stream from stdin.
+    #[arg(short, long)]
+    bam: PathBuf,
+    /// Filtered alignment output file (.bam | .cram | .sam)
+    ///
+    /// Format is inferred from the output extension, defaulting to BAM.
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Classifier read-level output, or a precomputed `read_id<TAB>tax_id` TSV
+    ///
+    /// Provide the path to a classifier's per-read output file to select
+    /// '--format', or omit '--format' to parse this as a two-column TSV
+    /// mapping each read identifier directly to a tax_id.
+    #[arg(short, long)]
+    reads: PathBuf,
+    /// Classifier output style of '--reads'
+    ///
+    /// Leave unset if '--reads' is a precomputed `read_id<TAB>tax_id` TSV.
+    #[arg(short, long)]
+    format: Option<ClassifierOutputFormat>,
+    /// Taxa and all sub-taxa to match against the read-to-taxon assignment
+    ///
+    /// List of taxa names or taxids. Reads assigned to these taxa or their
+    /// sub-taxa are selected for depletion or, with '--extract', extraction.
+    /// Subtree expansion requires '--taxonomy-directory'; without it, entries
+    /// are matched as literal taxids.
+    #[arg(long, short = 'T', num_args(0..))]
+    taxa: Vec<String>,
+    /// Taxa to match directly against the read-to-taxon assignment, without sub-taxa
+    #[arg(long, short = 'D', num_args(0..))]
+    taxa_direct: Vec<String>,
+    /// NCBI taxonomy dump directory (`nodes.dmp`/`names.dmp`) for '--taxa' subtree resolution
+    #[arg(long)]
+    taxonomy_directory: Option<PathBuf>,
+    /// Read extraction instead of depletion
+    ///
+    /// Enable this option to write only the matching reads instead of
+    /// everything else.
+    #[arg(short, long)]
+    extract: bool,
+}
+impl BamFilterArgs {
+    /// Resolves the requested taxa against the read-to-taxon assignment and
+    /// writes the filtered BAM/CRAM/SAM.
+    pub fn validate_and_build(self) -> Result<(), ScrubbyError> {
+        let target_taxids = match &self.taxonomy_directory {
+            Some(directory) => {
+                let taxonomy = Taxonomy::from_directory(directory)?;
+                get_taxids_from_taxonomy(&taxonomy, &self.taxa, &self.taxa_direct)
+            }
+            None => self.taxa.iter().chain(self.taxa_direct.iter()).cloned().collect(),
+        };
+
+        let read_ids = read_ids_for_taxa(&self.reads, self.format, &target_taxids)?;
+
+        filter_bam_by_read_ids(&self.bam, &self.output, &read_ids, self.extract)
+    }
+}
+
+
+#[derive(Args, Debug)]
+pub struct TaxonomyArgs {
+    /// NCBI taxonomy dump directory (`nodes.dmp`/`names.dmp`)
+    #[arg(short, long)]
+    taxonomy_directory: PathBuf,
+    /// Input file of bare taxids, one per line, or '-' to read from stdin
+    ///
+    /// FASTA header lines ('>') are copied through unchanged, so a taxid list
+    /// interleaved with the headers it was extracted from can be annotated
+    /// in place.
+    #[arg(short, long, default_value = "-")]
+    input: PathBuf,
+    /// Output TSV file, or '-' to write to stdout
+    #[arg(short, long, default_value = "-")]
+    output: PathBuf,
+    /// Suppress the 'taxon_id'/'taxon_name'/'taxon_rank' header row
+    #[arg(short = 'H', long)]
+    no_header: bool,
+}
+impl TaxonomyArgs {
+    /// Loads the taxonomy dump and annotates every taxid read from '--input'
+    /// with its scientific name and rank, writing a TSV to '--output'.
+    pub fn validate_and_build(self) -> Result<(), ScrubbyError> {
+        let taxonomy = Taxonomy::from_directory(&self.taxonomy_directory)?;
+
+        let reader: Box<dyn std::io::BufRead> = if self.input.to_str() == Some("-") {
+            Box::new(std::io::BufReader::new(std::io::stdin()))
+        } else {
+            crate::compression::open_reader(&self.input)?
+        };
+
+        let writer: Box<dyn std::io::Write> = if self.output.to_str() == Some("-") {
+            Box::new(std::io::stdout())
+        } else {
+            Box::new(std::fs::File::create(&self.output)?)
+        };
+
+        annotate_taxids(&taxonomy, reader, writer, !self.no_header)
+    }
+}
+
 
 #[derive(Args, Debug)]
 pub struct ClassifierArgs {
-    /// Input read files (can be compressed with .gz)
+    /// Input read files (.gz | .xz | .bz)
     ///
-    /// One or two input read files. These files can be in gzipped format.
-    /// This parameter is required and multiple files can be specified (1 for long
-    /// reads or 2 for paired-end short reads) either consecutively or using multiple
+    /// One or two input read files, can be compressed (.gz, .xz, .bz), detected
+    /// from the leading magic bytes regardless of extension. This parameter is
+    /// required and multiple files can be specified (1 for long reads or 2 for
+    /// paired-end short reads) either consecutively or using multiple
     /// input arguments, for example: '-i R1.fq.gz -i R2.fq.gz' or '-i R1.fq.gz R2.fq.gz'
     #[arg(short, long, num_args(0..))]
-    input: Vec<PathBuf>,
-    /// Output read files (can be compressed with .gz)
+    input: Vec<Utf8PathBuf>,
+    /// Output read files (.gz | .xz | .bz)
     ///
-    /// One or two output read files. These files will store the processed 
-    /// data and can be in gzipped format. This parameter is required and multiple 
-    /// files can be specified either consecutively or using multiple output arguments
-    /// for example: '-o R1.fq.gz -o R2.fq.gz' or '-o R1.fq.gz R2.fq.gz'. Output must be 
-    /// directed to files if '--json' or '--read-ids' arguments are provided.
+    /// One or two output read files. These files will store the processed
+    /// data and can be compressed (.gz, .xz, .bz), inferred from the file
+    /// extension unless overridden with '--compression-format'. This parameter
+    /// is required and multiple files can be specified either consecutively or
+    /// using multiple output arguments for example: '-o R1.fq.gz -o R2.fq.gz' or
+    /// '-o R1.fq.gz R2.fq.gz'. Output must be directed to files if '--json' or
+    /// '--read-ids' arguments are provided.
     #[arg(short, long, num_args(0..))]
-    output: Vec<PathBuf>,
+    output: Vec<Utf8PathBuf>,
+    /// Removed read output files (optional .gz)
+    ///
+    /// One or two files to additionally write the removed (or, with '--extract',
+    /// non-extracted) reads to, alongside the retained '--output' files. Must
+    /// match the number of '--output' files and respects the same paired layout
+    /// and compression inference, for example: '--removed r1.rm.fq.gz r2.rm.fq.gz'.
+    #[arg(long, num_args(0..))]
+    removed: Vec<Utf8PathBuf>,
     /// Kraken-style report output from classifier
     ///
     /// Specify the path to the Kraken-style report file generated by the classifier.
@@ -253,15 +913,59 @@ pub struct ClassifierArgs {
     /// considering sub-taxa.
     #[arg(long, short='D', num_args(0..))]
     taxa_direct: Vec<String>,
+    /// NCBI taxonomy dump directory (`nodes.dmp`/`names.dmp`) for true subtree resolution
+    ///
+    /// When set, '--taxa' descendants are resolved by walking this taxonomy graph
+    /// instead of inferring them from the classifier report's rank ordering.
+    #[arg(long)]
+    taxonomy_directory: Option<PathBuf>,
+    /// Minimum cumulative reads a matched taxon must have to be depleted
+    ///
+    /// Taxa selected via '--taxa'/'--taxa-direct' whose report `reads` column falls
+    /// below this value are ignored, suppressing likely false-positive depletion
+    /// from low-confidence classifications.
+    #[arg(long)]
+    min_reads: Option<u64>,
+    /// Minimum fraction of total reads a matched taxon must have to be depleted
+    #[arg(long)]
+    min_fraction: Option<f64>,
+    /// Taxonomic rank below which sub-level reads are rolled up into their nearest ancestor
+    ///
+    /// Sub-level rows below this rank (e.g. 'species' rows when this is
+    /// 'genus') have their reads attributed to the nearest enclosing ancestor
+    /// at or above the given rank instead of their own taxid, before
+    /// '--min-reads'/'--min-fraction' are applied.
+    #[arg(long)]
+    prune_rank: Option<String>,
+    /// Taxon audit report output file (.tsv)
+    ///
+    /// Writes one row per depleted taxon (tax_id, tax_name, tax_rank, parent,
+    /// reads_direct). Resolves tax_name/tax_rank from '--taxonomy-directory' when set.
+    #[arg(long)]
+    taxon_report: Option<PathBuf>,
     /// Summary output file (.json)
     ///
-    /// Path to a JSON file for storing summary information about the 
+    /// Path to a JSON file for storing summary information about the
     /// cleaning process.
     #[arg(short, long)]
     json: Option<PathBuf>,
+    /// Streaming progress and summary records (.ndjson)
+    ///
+    /// Path to a newline-delimited JSON file that receives a 'progress' record
+    /// every 100,000 reads processed, followed by a final 'summary' record
+    /// equivalent to '--json'. Use '-' to write to stdout.
+    #[arg(long)]
+    ndjson: Option<PathBuf>,
+    /// Reproducible run bundle (.tar.gz)
+    ///
+    /// Path to a gzip-compressed tar archive combining the '--json' report,
+    /// the '--read-ids' list, and the effective settings, so a collaborator
+    /// can inspect or re-apply this exact run from one shareable file.
+    #[arg(long)]
+    bundle: Option<PathBuf>,
     /// Optional working directory
     ///
-    /// Working directory for temporary files. If not provided, the system 
+    /// Working directory for temporary files. If not provided, the system
     /// temporary directory will be used.
     #[arg(short, long)]
     workdir: Option<PathBuf>,
@@ -277,6 +981,84 @@ pub struct ClassifierArgs {
     /// of depleting them.
     #[arg(short, long)]
     extract: bool,
+    /// Minimum distinct k-mers for a `KrakenUniq` taxon to be retained
+    ///
+    /// Only used with '--classifier krakenuniq'. Taxa backed by fewer than this many
+    /// distinct k-mers (the HyperLogLog-derived 'kmers' column of the report) are
+    /// ignored even if selected via '--taxa'/'--taxa-direct'.
+    #[arg(long, default_value = "0")]
+    min_unique_kmers: u64,
+    /// Minimum Metabuli `dna_score` for a read to be retained
+    ///
+    /// Only used with '--classifier metabuli'. Reads assigned to a selected taxon
+    /// whose 'dna_score' falls below this threshold are ignored, mirroring how
+    /// '--min-mapq' filters low-confidence alignments.
+    #[arg(long, default_value = "0.0")]
+    metabuli_min_score: f64,
+    /// Bracken k-mer distribution database for redistributing higher-rank reads
+    ///
+    /// Only used with '--classifier kraken2'. Tab-separated file mapping
+    /// `species_taxid`, `node_taxid`, `probability` - the probability that a read
+    /// from the species is classified at the ancestor node. When set, reads
+    /// Kraken2 assigned at or above '--bracken-rank' are redistributed down to
+    /// species proportionally to this probability before taxa are selected,
+    /// so depleting a species also captures its share of ambiguous reads.
+    #[arg(long)]
+    bracken_db: Option<PathBuf>,
+    /// Rank at or above which reads are redistributed to species using '--bracken-db'
+    #[arg(long, default_value = "genus")]
+    bracken_rank: String,
+    /// Bracken-style abundance re-estimation table (.tsv)
+    ///
+    /// Only used with '--classifier kraken2'. Unlike '--bracken-db', requires no
+    /// external database: redistributes reads Kraken2 assigned at ancestor nodes
+    /// down to '--bracken-level' directly from the classifier report, writing a
+    /// TSV of 'name', 'taxid', 'rank', 'kraken_assigned_reads', 'added_reads',
+    /// 'new_est_reads' and 'fraction_total_reads' per taxon, folded into the
+    /// '--json' summary alongside the depletion counts.
+    #[arg(long)]
+    bracken_report: Option<PathBuf>,
+    /// Taxonomic rank '--bracken-report' re-estimates abundance at
+    #[arg(long, default_value = "species")]
+    bracken_level: String,
+    /// Krona text report of depleted taxon lineages (.txt)
+    ///
+    /// Only used with '--classifier kraken2'. Writes one line per depleted taxon
+    /// giving its directly-assigned read count followed by the tab-separated
+    /// root-to-taxon lineage, suitable for 'ktImportText'.
+    #[arg(long)]
+    krona: Option<PathBuf>,
+    /// Strip trailing paired-end orientation suffixes from read IDs before matching
+    ///
+    /// Pass with no value to use the default pattern (covers '/1', '/2', '.1',
+    /// '.2' and Illumina/Casava ' 1:N:0:...' comments), or supply a custom regex.
+    #[arg(long, num_args(0..=1), default_missing_value = crate::readid::DEFAULT_SUFFIX_PATTERN)]
+    strip_suffix: Option<String>,
+    /// Output compression algorithm, overrides extension-based inference
+    ///
+    /// Accepts a short letter or full extension alias, case-insensitively:
+    /// 'g'/'gz'/'gzip', 'b'/'bz'/'bz2'/'bzip'/'bzip2', 'l'/'xz'/'lzma',
+    /// 'z'/'zst'/'zstd'/'zstandard', '4'/'lz4', 'u'/'none'/'uncompressed'.
+    #[arg(long, value_parser = CompressionAlgorithm::from_str)]
+    compression_format: Option<CompressionAlgorithm>,
+    /// Output compression level, defaults to the algorithm's own default level
+    #[arg(long)]
+    compression_level: Option<u32>,
+    /// Number of threads to use for compressing output
+    ///
+    /// When set above one and the output format is gzip, writes a multithreaded
+    /// BGZF stream (64 KiB blocks) instead of the single-threaded encoder.
+    /// BGZF output is also readable by any plain gzip decoder and is
+    /// additionally bgzip-index compatible. Ignored for bzip/lzma/zstd/lz4 output.
+    #[arg(long)]
+    compression_threads: Option<usize>,
+    /// Treat a single '--input' file as interleaved paired-end FASTQ
+    ///
+    /// When set, '--input' must be exactly one file containing alternating
+    /// R1/R2 records, which is split into a paired stream before cleaning
+    /// runs. '--output' must then be given as two files (R1/R2).
+    #[arg(long)]
+    interleaved: bool,
 }
 impl ClassifierArgs {
     /// Validates the provided arguments and builds a `Scrubby` instance.
@@ -298,14 +1080,18 @@ impl ClassifierArgs {
     /// ```
     pub fn validate_and_build(self) -> Result<Scrubby, ScrubbyError> {
 
+        require_existing(&self.input)?;
+
         let command = std::env::args().collect::<Vec<String>>().join(" ");
 
         let scrubby = ScrubbyBuilder::new(
-            self.input, 
-            self.output
+            into_path_bufs(self.input),
+            into_path_bufs(self.output)
         )
             .command(command)
             .json(self.json)
+            .ndjson(self.ndjson)
+            .bundle(self.bundle)
             .workdir(self.workdir)
             .read_ids(self.read_ids)
             .extract(self.extract)
@@ -314,6 +1100,24 @@ impl ClassifierArgs {
             .report(self.report)
             .taxa(self.taxa)
             .taxa_direct(self.taxa_direct)
+            .taxonomy_directory(self.taxonomy_directory)
+            .min_reads(self.min_reads)
+            .min_fraction(self.min_fraction)
+            .prune_rank(self.prune_rank)
+            .taxon_report(self.taxon_report)
+            .min_unique_kmers(self.min_unique_kmers)
+            .metabuli_min_score(self.metabuli_min_score)
+            .bracken_db(self.bracken_db)
+            .bracken_rank(self.bracken_rank)
+            .bracken_report(self.bracken_report)
+            .bracken_level(self.bracken_level)
+            .krona(self.krona)
+            .strip_suffix(self.strip_suffix)
+            .removed(into_path_bufs(self.removed))
+            .compression_format(self.compression_format)
+            .compression_level(self.compression_level)
+            .compression_threads(self.compression_threads)
+            .interleaved(self.interleaved)
             .build_classifier()?;
 
         Ok(scrubby)
@@ -323,29 +1127,42 @@ impl ClassifierArgs {
 
 #[derive(Args, Debug)]
 pub struct AlignmentArgs {
-    /// Input read files (can be compressed with .gz)
+    /// Input read files (.gz | .xz | .bz)
     ///
-    /// One or two input read files. These files can be in gzipped format.
-    /// This parameter is required and multiple files can be specified (1 for long
-    /// reads or 2 for paired-end short reads) either consecutively or using multiple
+    /// One or two input read files, can be compressed (.gz, .xz, .bz), detected
+    /// from the leading magic bytes regardless of extension. This parameter is
+    /// required and multiple files can be specified (1 for long reads or 2 for
+    /// paired-end short reads) either consecutively or using multiple
     /// input arguments, for example: '-i R1.fq.gz -i R2.fq.gz' or '-i R1.fq.gz R2.fq.gz'
     #[arg(short, long, num_args(0..))]
-    input: Vec<PathBuf>,
-    /// Output read files (can be compressed with .gz)
+    input: Vec<Utf8PathBuf>,
+    /// Output read files (.gz | .xz | .bz)
     ///
-    /// One or two output read files. These files will store the processed 
-    /// data and can be in gzipped format. This parameter is required and multiple 
-    /// files can be specified either consecutively or using multiple output arguments
-    /// for example: '-o R1.fq.gz -o R2.fq.gz' or '-o R1.fq.gz R2.fq.gz'. Output must be 
-    /// to file if '--json' or '--read-ids' arguments are provided.
+    /// One or two output read files. These files will store the processed
+    /// data and can be compressed (.gz, .xz, .bz), inferred from the file
+    /// extension unless overridden with '--compression-format'. This parameter
+    /// is required and multiple files can be specified either consecutively or
+    /// using multiple output arguments for example: '-o R1.fq.gz -o R2.fq.gz' or
+    /// '-o R1.fq.gz R2.fq.gz'. Output must be directed to file if '--json' or
+    /// '--read-ids' arguments are provided.
     #[arg(short, long, num_args(0..))]
-    output: Vec<PathBuf>,
+    output: Vec<Utf8PathBuf>,
+    /// Removed read output files (optional .gz)
+    ///
+    /// One or two files to additionally write the removed (or, with '--extract',
+    /// non-extracted) reads to, alongside the retained '--output' files. Must
+    /// match the number of '--output' files and respects the same paired layout
+    /// and compression inference, for example: '--removed r1.rm.fq.gz r2.rm.fq.gz'.
+    #[arg(long, num_args(0..))]
+    removed: Vec<Utf8PathBuf>,
     /// Alignment file in PAF/GAF/TXT or SAM/BAM/CRAM, if compiled with 'htslib' feature.
     ///
     /// Specify the path to an alignment in SAM/BAM/CRAM/PAF/GAF format (.sam, .bam, .cram, .paf, .gaf),  
     /// or a read identifier file for any reads to deplete directly (.txt). PAF/GAF/TXT format
-    /// can be compressed (.gz, .xz, .bz). Allows '-' to read from stdin, but input stream cannot be 
-    /// compressed and requires explicit setting of '--format'.
+    /// can be compressed (.gz, .xz, .bz). Allows '-' to read from stdin, for example directly
+    /// piped from an aligner; PAF/GAF/TXT streamed this way may also be compressed, sniffed from
+    /// the leading bytes of the stream. Since there is no extension to infer the format from,
+    /// stdin input requires explicit setting of '--format'.
     #[arg(short, long)]
     alignment: PathBuf,
     /// Explicit alignment format
@@ -365,15 +1182,68 @@ pub struct AlignmentArgs {
     /// Minimum mapping quality filter.
     #[arg(short='q', long, default_value = "0")]
     min_mapq: u8,
+    /// Combination policy for '--min-len' and '--min-cov'
+    ///
+    /// 'any' (default) accepts a read if either threshold is met; 'all' requires
+    /// both, for stricter high-specificity depletion. Coverage is accumulated
+    /// across all alignment lines for the same read (e.g. supplementary/chimeric
+    /// alignments) before either threshold is checked.
+    #[arg(long, default_value = "any")]
+    paf_filter_mode: PafFilterMode,
+    /// Ignore secondary/supplementary BAM/SAM/CRAM alignment records
+    ///
+    /// Only used for BAM/SAM/CRAM input. A secondary or supplementary alignment
+    /// of a read already has a primary alignment recorded elsewhere in the file;
+    /// enable this to stop a weaker secondary/supplementary hit alone from
+    /// flagging the read for depletion.
+    #[arg(long)]
+    skip_secondary: bool,
+    /// Require proper-pair concordance for paired-end BAM/SAM/CRAM alignments
+    ///
+    /// Only used for paired-end BAM/SAM/CRAM input. Ignores an alignment record
+    /// unless its template mapped as a concordant pair (the SAM "proper pair"
+    /// flag), for stricter depletion than mapping quality alone provides.
+    #[arg(long)]
+    require_proper_pair: bool,
+    /// Minimum alignment identity filter
+    ///
+    /// For PAF/GAF input, identity is 'mlen / blen' accumulated across all
+    /// alignment lines for the same read. For BAM/SAM/CRAM input, identity is
+    /// reconstructed as '1 - NM / alignment_block_len' from the 'NM' edit-distance
+    /// tag and the CIGAR string; records without an 'NM' tag are not filtered,
+    /// since not every aligner writes one.
+    #[arg(long, default_value = "0.0")]
+    min_identity: f64,
+    /// Reference FASTA used to decode a CRAM alignment file
+    ///
+    /// Required when '--alignment' is CRAM: CRAM records are
+    /// reference-compressed and cannot be decoded without the FASTA used
+    /// to align them. Ignored for other alignment formats.
+    #[arg(long)]
+    reference: Option<PathBuf>,
     /// Summary output file (.json)
     ///
-    /// Path to a JSON file for storing summary information about the 
+    /// Path to a JSON file for storing summary information about the
     /// cleaning process.
     #[arg(short, long)]
     json: Option<PathBuf>,
+    /// Streaming progress and summary records (.ndjson)
+    ///
+    /// Path to a newline-delimited JSON file that receives a 'progress' record
+    /// every 100,000 reads processed, followed by a final 'summary' record
+    /// equivalent to '--json'. Use '-' to write to stdout.
+    #[arg(long)]
+    ndjson: Option<PathBuf>,
+    /// Reproducible run bundle (.tar.gz)
+    ///
+    /// Path to a gzip-compressed tar archive combining the '--json' report,
+    /// the '--read-ids' list, and the effective settings, so a collaborator
+    /// can inspect or re-apply this exact run from one shareable file.
+    #[arg(long)]
+    bundle: Option<PathBuf>,
     /// Optional working directory
     ///
-    /// Working directory for temporary files. If not provided, the system 
+    /// Working directory for temporary files. If not provided, the system
     /// temporary directory will be used.
     #[arg(short, long)]
     workdir: Option<PathBuf>,
@@ -389,6 +1259,38 @@ pub struct AlignmentArgs {
     /// of depleting them.
     #[arg(short, long)]
     extract: bool,
+    /// Strip trailing paired-end orientation suffixes from read IDs before matching
+    ///
+    /// Pass with no value to use the default pattern (covers '/1', '/2', '.1',
+    /// '.2' and Illumina/Casava ' 1:N:0:...' comments), or supply a custom regex.
+    /// Useful when read IDs were pulled from SAM/BAM, which often retain these suffixes.
+    #[arg(long, num_args(0..=1), default_missing_value = crate::readid::DEFAULT_SUFFIX_PATTERN)]
+    strip_suffix: Option<String>,
+    /// Output compression algorithm, overrides extension-based inference
+    ///
+    /// Accepts a short letter or full extension alias, case-insensitively:
+    /// 'g'/'gz'/'gzip', 'b'/'bz'/'bz2'/'bzip'/'bzip2', 'l'/'xz'/'lzma',
+    /// 'z'/'zst'/'zstd'/'zstandard', '4'/'lz4', 'u'/'none'/'uncompressed'.
+    #[arg(long, value_parser = CompressionAlgorithm::from_str)]
+    compression_format: Option<CompressionAlgorithm>,
+    /// Output compression level, defaults to the algorithm's own default level
+    #[arg(long)]
+    compression_level: Option<u32>,
+    /// Number of threads to use for compressing output
+    ///
+    /// When set above one and the output format is gzip, writes a multithreaded
+    /// BGZF stream (64 KiB blocks) instead of the single-threaded encoder.
+    /// BGZF output is also readable by any plain gzip decoder and is
+    /// additionally bgzip-index compatible. Ignored for bzip/lzma/zstd/lz4 output.
+    #[arg(long)]
+    compression_threads: Option<usize>,
+    /// Treat a single '--input' file as interleaved paired-end FASTQ
+    ///
+    /// When set, '--input' must be exactly one file containing alternating
+    /// R1/R2 records, which is split into a paired stream before cleaning
+    /// runs. '--output' must then be given as two files (R1/R2).
+    #[arg(long)]
+    interleaved: bool,
 }
 impl AlignmentArgs {
     /// Validates the provided arguments and builds a `Scrubby` instance.
@@ -410,14 +1312,18 @@ impl AlignmentArgs {
     /// ```
     pub fn validate_and_build(self) -> Result<Scrubby, ScrubbyError> {
 
+        require_existing(&self.input)?;
+
         let command = std::env::args().collect::<Vec<String>>().join(" ");
 
         let scrubby = ScrubbyBuilder::new(
-            self.input, 
-            self.output,
-        )   
+            into_path_bufs(self.input),
+            into_path_bufs(self.output),
+        )
             .command(command)
             .json(self.json)
+            .ndjson(self.ndjson)
+            .bundle(self.bundle)
             .workdir(self.workdir)
             .read_ids(self.read_ids)
             .extract(self.extract)
@@ -426,6 +1332,17 @@ impl AlignmentArgs {
             .min_query_length(self.min_len)
             .min_query_coverage(self.min_cov)
             .min_mapq(self.min_mapq)
+            .paf_filter_mode(self.paf_filter_mode)
+            .skip_secondary_alignments(self.skip_secondary)
+            .require_proper_pair(self.require_proper_pair)
+            .min_identity(self.min_identity)
+            .reference(self.reference)
+            .strip_suffix(self.strip_suffix)
+            .removed(into_path_bufs(self.removed))
+            .compression_format(self.compression_format)
+            .compression_level(self.compression_level)
+            .compression_threads(self.compression_threads)
+            .interleaved(self.interleaved)
             .build_alignment()?;
 
         Ok(scrubby)
@@ -433,14 +1350,306 @@ impl AlignmentArgs {
 }
 
 
+#[derive(Args, Debug)]
+pub struct ComplexityArgs {
+    /// Input read files (can be compressed with .gz)
+    ///
+    /// One or two input read files. These files can be in gzipped format.
+    /// This parameter is required and multiple files can be specified (1 for long
+    /// reads or 2 for paired-end short reads) either consecutively or using multiple
+    /// input arguments, for example: '-i R1.fq.gz -i R2.fq.gz' or '-i R1.fq.gz R2.fq.gz'
+    #[arg(short, long, num_args(0..))]
+    input: Vec<Utf8PathBuf>,
+    /// Output read files (can be compressed with .gz)
+    ///
+    /// One or two output read files. These files will store the processed
+    /// data and can be in gzipped format. This parameter is required and multiple
+    /// files can be specified either consecutively or using multiple output arguments
+    /// for example: '-o R1.fq.gz -o R2.fq.gz' or '-o R1.fq.gz R2.fq.gz'. Output must be
+    /// directed to files if '--json' or '--read-ids' arguments are provided.
+    #[arg(short, long, num_args(0..))]
+    output: Vec<Utf8PathBuf>,
+    /// Removed read output files (optional .gz)
+    ///
+    /// One or two files to additionally write the removed (or, with '--extract',
+    /// non-extracted) reads to, alongside the retained '--output' files. Must
+    /// match the number of '--output' files and respects the same paired layout
+    /// and compression inference, for example: '--removed r1.rm.fq.gz r2.rm.fq.gz'.
+    #[arg(long, num_args(0..))]
+    removed: Vec<Utf8PathBuf>,
+    /// Scoring method for low-complexity detection ('dust' or 'entropy')
+    ///
+    /// 'dust' evaluates the symmetric-DUST score over a sliding window (see '--window'),
+    /// with '--min-entropy' mapped onto an equivalent cutoff unless '--max-dust' is set
+    /// directly. 'entropy' compares the Shannon entropy of each read's base composition
+    /// directly against '--min-entropy', without the DUST conversion.
+    #[arg(long, value_enum, default_value_t = crate::complexity::ComplexityMethod::Dust)]
+    method: crate::complexity::ComplexityMethod,
+    /// Minimum entropy threshold below which a read is flagged low-complexity
+    ///
+    /// Shannon-style entropy normalised to [0, 1], following 'bbduk' conventions. Under
+    /// '--method dust' (the default) this is internally mapped onto a symmetric-DUST
+    /// cutoff; under '--method entropy' it is compared directly.
+    #[arg(long, short='e', default_value_t = crate::complexity::DEFAULT_MIN_ENTROPY)]
+    min_entropy: f64,
+    /// Explicit symmetric-DUST cutoff, overriding the value derived from '--min-entropy'
+    ///
+    /// Only used with '--method dust'. Typical values range from about 2.5 to 4.0;
+    /// reads whose maximum windowed DUST score exceeds this cutoff are flagged.
+    #[arg(long)]
+    max_dust: Option<f64>,
+    /// Sliding window size (bases) for the symmetric-DUST score
+    #[arg(long, short='w', default_value_t = crate::complexity::DEFAULT_COMPLEXITY_WINDOW)]
+    window: usize,
+    /// Summary output file (.json)
+    ///
+    /// Path to a JSON file for storing summary information about the
+    /// cleaning process. reads_in/reads_out/reads_removed are computed
+    /// from the finished low-complexity-filtered '--output', not the raw input.
+    #[arg(short, long)]
+    json: Option<PathBuf>,
+    /// Streaming progress and summary records (.ndjson)
+    ///
+    /// Path to a newline-delimited JSON file that receives a 'progress' record
+    /// every 100,000 reads processed, followed by a final 'summary' record
+    /// equivalent to '--json'. Use '-' to write to stdout.
+    #[arg(long)]
+    ndjson: Option<PathBuf>,
+    /// Reproducible run bundle (.tar.gz)
+    ///
+    /// Path to a gzip-compressed tar archive combining the '--json' report,
+    /// the '--read-ids' list, and the effective settings, so a collaborator
+    /// can inspect or re-apply this exact run from one shareable file.
+    #[arg(long)]
+    bundle: Option<PathBuf>,
+    /// Optional working directory
+    ///
+    /// Working directory for temporary files. If not provided, the system
+    /// temporary directory will be used.
+    #[arg(short, long)]
+    workdir: Option<PathBuf>,
+    /// Read identifier file (.tsv)
+    ///
+    /// Path to a TSV file containing read identifiers. This file will
+    /// be used to identify specific reads for depletion or extraction.
+    #[arg(short, long)]
+    read_ids: Option<PathBuf>,
+    /// Read extraction instead of depletion
+    ///
+    /// Enable this option to extract reads matching the specified criteria instead
+    /// of depleting them.
+    #[arg(short, long)]
+    extract: bool,
+    /// Treat a single '--input' file as interleaved paired-end FASTQ
+    ///
+    /// When set, '--input' must be exactly one file containing alternating
+    /// R1/R2 records, which is split into a paired stream before cleaning
+    /// runs. '--output' must then be given as two files (R1/R2).
+    #[arg(long)]
+    interleaved: bool,
+}
+impl ComplexityArgs {
+    /// Validates the provided arguments and builds a `Scrubby` instance.
+    ///
+    /// This method checks the provided arguments for consistency and constructs
+    /// a `Scrubby` instance based on the validated arguments.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Scrubby, ScrubbyError>` - Ok with the constructed Scrubby instance, otherwise an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clap::Parser;
+    ///
+    /// let complexity_args = ComplexityArgs::parse();
+    /// let scrubby = complexity_args.validate_and_build().unwrap();
+    /// ```
+    pub fn validate_and_build(self) -> Result<Scrubby, ScrubbyError> {
+
+        require_existing(&self.input)?;
+
+        let command = std::env::args().collect::<Vec<String>>().join(" ");
+
+        let scrubby = ScrubbyBuilder::new(
+            into_path_bufs(self.input),
+            into_path_bufs(self.output)
+        )
+            .command(command)
+            .json(self.json)
+            .ndjson(self.ndjson)
+            .bundle(self.bundle)
+            .workdir(self.workdir)
+            .read_ids(self.read_ids)
+            .extract(self.extract)
+            .removed(into_path_bufs(self.removed))
+            .min_entropy(self.min_entropy)
+            .max_dust(self.max_dust)
+            .complexity_method(self.method)
+            .complexity_window(self.window)
+            .interleaved(self.interleaved)
+            .build_complexity()?;
+
+        Ok(scrubby)
+    }
+}
+
+
+#[derive(Args, Debug)]
+pub struct SketchArgs {
+    /// Input read files (.gz | .xz | .bz)
+    ///
+    /// One or two input read files, can be compressed (.gz, .xz, .bz), detected
+    /// from the leading magic bytes regardless of extension. This parameter is
+    /// required and multiple files can be specified (1 for long reads or 2 for
+    /// paired-end short reads) either consecutively or using multiple
+    /// input arguments, for example: '-i R1.fq.gz -i R2.fq.gz' or '-i R1.fq.gz R2.fq.gz'
+    #[arg(short, long, num_args(0..))]
+    input: Vec<Utf8PathBuf>,
+    /// Output read files (.gz | .xz | .bz)
+    ///
+    /// One or two output read files. These files will store the processed
+    /// data and can be compressed (.gz, .xz, .bz), inferred from the file
+    /// extension unless overridden with '--compression-format'. This parameter
+    /// is required and multiple files can be specified either consecutively or
+    /// using multiple output arguments for example: '-o R1.fq.gz -o R2.fq.gz' or
+    /// '-o R1.fq.gz R2.fq.gz'. Output must be directed to files if '--json' or
+    /// '--read-ids' arguments are provided.
+    #[arg(short, long, num_args(0..))]
+    output: Vec<Utf8PathBuf>,
+    /// Removed read output files (optional .gz)
+    ///
+    /// One or two files to additionally write the removed (or, with '--extract',
+    /// non-extracted) reads to, alongside the retained '--output' files. Must
+    /// match the number of '--output' files and respects the same paired layout
+    /// and compression inference, for example: '--removed r1.rm.fq.gz r2.rm.fq.gz'.
+    #[arg(long, num_args(0..))]
+    removed: Vec<Utf8PathBuf>,
+    /// Reference sketch file built with `scrubby sketch-build`
+    #[arg(short='s', long)]
+    sketch: PathBuf,
+    /// Minimum containment (fraction of a read's own sketch hashes found in the reference sketch)
+    #[arg(long, default_value_t = crate::sketch::DEFAULT_MIN_CONTAINMENT)]
+    min_containment: f64,
+    /// Minimum number of a read's own sketch hashes required before its containment score is trusted
+    #[arg(long, default_value_t = crate::sketch::DEFAULT_MIN_SKETCH_HASHES)]
+    min_hashes: usize,
+    /// Summary output file (.json)
+    ///
+    /// Path to a JSON file for storing summary information about the
+    /// cleaning process.
+    #[arg(short, long)]
+    json: Option<PathBuf>,
+    /// Streaming progress and summary records (.ndjson)
+    ///
+    /// Path to a newline-delimited JSON file that receives a 'progress' record
+    /// every 100,000 reads processed, followed by a final 'summary' record
+    /// equivalent to '--json'. Use '-' to write to stdout.
+    #[arg(long)]
+    ndjson: Option<PathBuf>,
+    /// Reproducible run bundle (.tar.gz)
+    ///
+    /// Path to a gzip-compressed tar archive combining the '--json' report,
+    /// the '--read-ids' list, and the effective settings, so a collaborator
+    /// can inspect or re-apply this exact run from one shareable file.
+    #[arg(long)]
+    bundle: Option<PathBuf>,
+    /// Optional working directory
+    ///
+    /// Working directory for temporary files. If not provided, the system
+    /// temporary directory will be used.
+    #[arg(short, long)]
+    workdir: Option<PathBuf>,
+    /// Read identifier file (.tsv)
+    ///
+    /// Path to a TSV file containing read identifiers. This file will
+    /// be used to identify specific reads for depletion or extraction.
+    #[arg(short, long)]
+    read_ids: Option<PathBuf>,
+    /// Read extraction instead of depletion
+    ///
+    /// Enable this option to extract reads matching the specified criteria instead
+    /// of depleting them.
+    #[arg(short, long)]
+    extract: bool,
+    /// Treat a single '--input' file as interleaved paired-end FASTQ
+    ///
+    /// When set, '--input' must be exactly one file containing alternating
+    /// R1/R2 records, which is split into a paired stream before cleaning
+    /// runs. '--output' must then be given as two files (R1/R2).
+    #[arg(long)]
+    interleaved: bool,
+}
+impl SketchArgs {
+    /// Validates the provided arguments and builds a `Scrubby` instance.
+    pub fn validate_and_build(self) -> Result<Scrubby, ScrubbyError> {
+
+        require_existing(&self.input)?;
+
+        let command = std::env::args().collect::<Vec<String>>().join(" ");
+
+        let scrubby = ScrubbyBuilder::new(
+            into_path_bufs(self.input),
+            into_path_bufs(self.output)
+        )
+            .command(command)
+            .json(self.json)
+            .ndjson(self.ndjson)
+            .bundle(self.bundle)
+            .workdir(self.workdir)
+            .read_ids(self.read_ids)
+            .extract(self.extract)
+            .removed(into_path_bufs(self.removed))
+            .sketch_index(self.sketch)
+            .min_containment(self.min_containment)
+            .sketch_min_hashes(self.min_hashes)
+            .interleaved(self.interleaved)
+            .build_sketch()?;
+
+        Ok(scrubby)
+    }
+}
+
+
+/// Command-line arguments for the `sketch-build` subcommand
+///
+/// Builds a FracMinHash reference sketch from a FASTA file for use with the
+/// `sketch` subcommand: a fast, memory-light alternative to a full aligner
+/// index or taxonomic database for host depletion.
+#[derive(Args, Debug)]
+pub struct SketchBuildArgs {
+    /// Reference FASTA to sketch (can be compressed, .gz | .xz | .bz)
+    #[arg(short, long)]
+    fasta: PathBuf,
+    /// Output sketch file (.json)
+    #[arg(short, long)]
+    output: PathBuf,
+    /// K-mer length
+    #[arg(short, long, default_value_t = crate::sketch::DEFAULT_SKETCH_K)]
+    kmer_size: u8,
+    /// FracMinHash scaling factor, retains roughly 1/scaled of canonical k-mers
+    #[arg(short, long, default_value_t = crate::sketch::DEFAULT_SKETCH_SCALED)]
+    scaled: u64,
+}
+impl SketchBuildArgs {
+    /// Builds a `FracMinHashSketch` from '--fasta' and writes it to '--output'.
+    pub fn validate_and_build(self) -> Result<(), ScrubbyError> {
+        let sketch = FracMinHashSketch::from_fasta(&self.fasta, self.kmer_size, self.scaled)?;
+        sketch.write_json(&self.output)
+    }
+}
+
+
 #[derive(Args, Debug, Clone)]
 pub struct DownloadArgs {
-    /// Index name to download 
-    /// 
+    /// Index id to download, as listed by '--list'
+    ///
     /// Default is 'bowtie2' aligner unless '--aligner' or
-    /// '--classfier' arguments are set explicitly.
+    /// '--classfier' arguments are set explicitly. Validated against the
+    /// remote index catalog (or the bundled catalog if it cannot be fetched).
     #[arg(short, long, num_args(0..))]
-    pub name: Vec<ScrubbyIndex>,
+    pub name: Vec<String>,
     /// Output directory for index download
     /// 
     /// Output directory will be created if it does not exist.
@@ -458,6 +1667,24 @@ pub struct DownloadArgs {
     /// List available index names and exit
     #[arg(short, long)]
     pub list: bool,
+    /// Skip SHA-256 checksum verification of downloaded index files
+    #[arg(long)]
+    pub no_verify: bool,
+    /// Maximum retries per file on connection/timeout errors and server errors
+    #[arg(long, default_value="3")]
+    pub max_retries: u32,
+    /// Base delay in seconds for exponential backoff between retries
+    #[arg(long, default_value="5")]
+    pub backoff: u64,
+    /// Always download to a file before unpacking, even for tar archives
+    ///
+    /// By default, tar-packaged indices (`.tar`, `.tar.gz`, `.tar.xz`, `.tar.bz2`, `.tgz`)
+    /// are extracted directly from the download stream without ever writing the
+    /// compressed archive to disk. Set this to fall back to the buffered
+    /// download-then-unpack path, which verifies the whole archive's checksum
+    /// before anything is extracted.
+    #[arg(long)]
+    pub no_stream_unpack: bool,
 }
 impl DownloadArgs {
     /// Validates the provided arguments and builds a `ScrubbyDownloader` instance.
@@ -485,6 +1712,10 @@ impl DownloadArgs {
         .classifier(self.classfier)
         .aligner(self.aligner)
         .timeout(self.timeout)
+        .verify(!self.no_verify)
+        .max_retries(self.max_retries)
+        .backoff(self.backoff)
+        .stream_unpack(!self.no_stream_unpack)
         .build()?;
 
         Ok(downloader)
@@ -554,6 +1785,78 @@ impl DiffArgs {
     }
 }
 
+/// Command-line arguments for the `merge` subcommand.
+#[derive(Args, Debug)]
+pub struct MergeArgs {
+    /// Per-sample summary reports to merge (.json, from `--json`)
+    #[arg(short, long, num_args(1..), required = true)]
+    reports: Vec<PathBuf>,
+    /// Cohort summary output file (.json)
+    #[arg(short, long)]
+    json: Option<PathBuf>,
+    /// Per-sample cohort table output file (.tsv)
+    ///
+    /// Flat table with one row per sample, for import into a spreadsheet or
+    /// QC dashboard.
+    #[arg(short, long)]
+    tsv: Option<PathBuf>,
+}
+impl MergeArgs {
+    /// Loads each input report and merges them into a single `CohortReport`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clap::Parser;
+    ///
+    /// let merge_args = MergeArgs::parse();
+    /// let cohort = merge_args.validate_and_build().unwrap();
+    /// ```
+    pub fn validate_and_build(self) -> Result<CohortReport, ScrubbyError> {
+        let reports = self.reports.iter().map(|path| {
+            Ok::<_, ScrubbyError>((path.clone(), ScrubbyReport::from_json(path)?))
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        let cohort = CohortReport::merge(&reports);
+
+        if let Some(path) = &self.json {
+            cohort.write_json(path)?;
+        }
+        if let Some(path) = &self.tsv {
+            cohort.write_tsv(path)?;
+        }
+
+        Ok(cohort)
+    }
+}
+
+/// Command-line arguments for the `restore` subcommand.
+#[derive(Args, Debug)]
+pub struct RestoreArgs {
+    /// Run bundle to unpack (.tar.gz, from `--bundle`)
+    #[arg(short, long)]
+    bundle: PathBuf,
+    /// Directory to unpack the bundle's files into
+    #[arg(short, long)]
+    outdir: PathBuf,
+}
+impl RestoreArgs {
+    /// Unpacks the bundle into `outdir` and returns its summary report.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use clap::Parser;
+    ///
+    /// let restore_args = RestoreArgs::parse();
+    /// let report = restore_args.validate_and_build().unwrap();
+    /// ```
+    pub fn validate_and_build(self) -> Result<ScrubbyReport, ScrubbyError> {
+        std::fs::create_dir_all(&self.outdir)?;
+        ScrubbyReport::from_bundle(&self.bundle, &self.outdir)
+    }
+}
+
 
 
 #[derive(Args, Debug)]
@@ -573,6 +1876,9 @@ pub struct NeuralNetArgs {
     /// Check GPU connect
     #[arg(short, long)]
     pub check: bool,
+    /// List available CUDA devices and their reported memory, then exit
+    #[arg(long)]
+    pub list_devices: bool,
     /// Train model from input reads 
     #[arg(short, long)]
     pub train: bool,
@@ -582,9 +1888,30 @@ pub struct NeuralNetArgs {
     /// Train with batch size
     #[arg(short, long, default_value="32")]
     pub batch_size: usize,
-    /// CUDA device to use
+    /// Fall back to the CPU instead of failing when the requested device cannot be bound
+    #[arg(long)]
+    pub allow_cpu_fallback: bool,
+    /// Smallest batch size to retry with on a CUDA out-of-memory error before giving up
+    #[arg(long, default_value="1")]
+    pub min_batch_size: usize,
+    /// Do not resume training from an existing checkpoint even if one matches the current run
+    #[arg(long)]
+    pub no_resume: bool,
+    /// Compute device to use: `cpu`, `auto`, or a CUDA device index
     #[arg(short, long, default_value="0")]
-    pub device: usize,
+    pub device: crate::identity::ComputeDevice,
+    /// Use a numerically stable "quiet" softmax so a read matching none of the trained classes can receive near-zero probability across the board
+    #[arg(short, long)]
+    pub quiet: bool,
+    /// Minimum top-class probability required to accept a prediction; predictions below this are reported as unclassified
+    #[arg(long, default_value="0.5")]
+    pub threshold: f64,
+    /// Export the trained model as a traced TorchScript module instead of training or predicting
+    #[arg(short='x', long)]
+    pub export: bool,
+    /// Output path for the exported model, required with `--export`
+    #[arg(short='O', long)]
+    pub onnx_output: Option<PathBuf>,
 }
 
 /// Configures the styles for the command-line interface.
@@ -1,7 +1,63 @@
 use std::ffi::OsStr;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 use crate::scrub::ScrubberError;
 
+static JSON_LOG: OnceLock<Mutex<std::fs::File>> = OnceLock::new();
+
+/// Initializes the human-readable logger (to `log_file`, or stderr if not
+/// given) and, if `json_log` is set, a structured NDJSON sink written
+/// alongside it. Automation that needs run events (reads processed/depleted,
+/// training epoch/loss, per-device memory) reads `json_log` instead of
+/// scraping the formatted text log.
+pub fn init_logger(log_file: Option<PathBuf>, json_log: Option<PathBuf>) {
+    let mut builder = env_logger::Builder::from_default_env();
+    builder.filter_level(log::LevelFilter::Info);
+
+    match log_file {
+        Some(path) => match std::fs::File::create(&path) {
+            Ok(file) => { builder.target(env_logger::Target::Pipe(Box::new(file))); },
+            Err(error) => eprintln!("Failed to open log file {}: {}", path.display(), error),
+        },
+        None => { builder.target(env_logger::Target::Stderr); }
+    }
+    builder.init();
+
+    if let Some(path) = json_log {
+        match std::fs::File::create(&path) {
+            Ok(file) => { let _ = JSON_LOG.set(Mutex::new(file)); },
+            Err(error) => log::warn!("Failed to open JSON log file {}: {}", path.display(), error),
+        }
+    }
+}
+
+/// Whether `--json-log` was set, so a pipeline stage can decide it is worth
+/// assembling a summary record even when `--json`/`--ndjson` were not given.
+pub fn json_log_enabled() -> bool {
+    JSON_LOG.get().is_some()
+}
+
+/// Appends one record to the `--json-log` sink, if configured. `subcommand`
+/// and `fields` are merged with a `timestamp` and `level` into a single
+/// line, e.g. `{"timestamp":"...","level":"info","subcommand":"reads",...}`.
+pub fn log_json_event(level: &str, subcommand: &str, fields: serde_json::Value) {
+    let Some(lock) = JSON_LOG.get() else { return };
+
+    let mut record = serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "level": level,
+        "subcommand": subcommand,
+    });
+    if let (Some(record), Some(fields)) = (record.as_object_mut(), fields.as_object()) {
+        record.extend(fields.clone());
+    }
+
+    if let Ok(mut file) = lock.lock() {
+        let _ = writeln!(file, "{record}");
+    }
+}
+
 pub trait CompressionExt {
     fn from_path<S: AsRef<OsStr> + ?Sized>(p: &S) -> Self;
 }
@@ -70,3 +126,58 @@ pub fn get_file_strings_from_input(
     }
 }
 
+/// Opens `path` for writing through `niffler::get_writer`, applying `format`
+/// (or leaving the stream uncompressed when `None`) at `level`.
+///
+/// Used by `compression::build_output_writer` for every output format other
+/// than the ones it handles directly (multithreaded BGZF for gzip, the
+/// `zstd` crate's own encoder for Zstandard).
+pub fn get_fastx_writer(
+    path: &Path,
+    level: niffler::compression::Level,
+    format: Option<niffler::compression::Format>,
+) -> Result<Box<dyn Write>, crate::error::ScrubbyError> {
+    let file = std::fs::File::create(path)?;
+    Ok(niffler::get_writer(
+        Box::new(file),
+        format.unwrap_or(niffler::compression::Format::No),
+        level,
+    )?)
+}
+
+/// Extracts a FASTX record's identifier: the header up to (not including)
+/// the first whitespace, matching the convention aligner/classifier tools
+/// use when reporting read IDs (needletail's `record.id()` otherwise
+/// includes the full description).
+pub fn get_id(id: &[u8]) -> Result<String, crate::error::ScrubbyError> {
+    let header = std::str::from_utf8(id).map_err(crate::error::ScrubbyError::NeedletailHeader)?;
+    Ok(header.split(char::is_whitespace).next().unwrap_or("").to_string())
+}
+
+/// Opens `path` for FASTX reading, returning `None` instead of an error for a
+/// zero-byte file (e.g. a sample with no reads left after upstream
+/// preprocessing), which `needletail` otherwise rejects outright.
+///
+/// Tries `needletail::parse_fastx_file` first; if that fails, falls back to
+/// an external decompressor registered for `path`'s extension via
+/// `compression::register_external_decompressor` (covering formats outside
+/// what niffler/needletail can sniff, e.g. `.sra` or a long-range-mode
+/// `.zst`), piping its stdout into `needletail::parse_fastx_reader`. Returns
+/// the original parse error unchanged when no decompressor is registered for
+/// the extension.
+pub fn parse_fastx_file_with_check(
+    path: &Path,
+) -> Result<Option<Box<dyn needletail::FastxReader>>, crate::error::ScrubbyError> {
+    if std::fs::metadata(path).map(|m| m.len()).unwrap_or(0) == 0 {
+        return Ok(None);
+    }
+
+    match needletail::parse_fastx_file(path) {
+        Ok(reader) => Ok(Some(reader)),
+        Err(native_error) => match crate::compression::get_external_decompressor(path) {
+            Some(decompressor) => Ok(Some(crate::compression::parse_with_external_decompressor(&decompressor, path)?)),
+            None => Err(native_error.into()),
+        },
+    }
+}
+